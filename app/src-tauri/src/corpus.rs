@@ -0,0 +1,111 @@
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::CallMetadata;
+
+/// One labeled call in an evaluation corpus: the rule IDs a reviewer decided
+/// should alert on it
+#[derive(Debug, Clone, Deserialize)]
+pub struct CorpusCase {
+    pub id: String,
+    pub metadata: CallMetadata,
+    pub transcript: String,
+    #[serde(default)]
+    pub expected_alerts: Vec<String>,
+}
+
+/// Precision, recall and F1 for one rule across the corpus. Ratios with no
+/// denominator (e.g. precision for a rule never raised) are 0.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleScore {
+    pub rule_id: String,
+    pub true_positives: u32,
+    pub false_positives: u32,
+    pub false_negatives: u32,
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+}
+
+/// How well a model's alerts matched a labeled corpus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelCorpusReport {
+    pub model: String,
+    pub cases: u32,
+    /// Cases the model failed to evaluate; they aren't scored
+    pub llm_failures: u32,
+    /// Rules that were expected or raised at least once
+    pub rules: Vec<RuleScore>,
+    /// Micro-averaged over every rule
+    pub precision: f64,
+    pub recall: f64,
+    pub f1: f64,
+    pub total_latency_ms: u64,
+    pub mean_latency_ms: f64,
+}
+
+/// Read a corpus file: a JSON array of cases
+pub fn load_corpus(path: &Path) -> Result<Vec<CorpusCase>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read corpus {}: {}", path.display(), e))?;
+    let cases: Vec<CorpusCase> = serde_json::from_str(&content)
+        .map_err(|e| format!("Invalid corpus {}: {}", path.display(), e))?;
+    if cases.is_empty() {
+        return Err(format!("Corpus {} has no cases", path.display()));
+    }
+    Ok(cases)
+}
+
+/// Per-rule (true positive, false positive, false negative) counts
+#[derive(Debug, Default)]
+pub struct CorpusTally {
+    counts: BTreeMap<String, (u32, u32, u32)>,
+}
+
+impl CorpusTally {
+    /// Count one case, comparing the raised rules against the expected ones
+    pub fn record(&mut self, expected: &[String], raised: &HashSet<String>) {
+        let expected: HashSet<&String> = expected.iter().collect();
+        for rule_id in raised {
+            let entry = self.counts.entry(rule_id.clone()).or_default();
+            if expected.contains(rule_id) {
+                entry.0 += 1;
+            } else {
+                entry.1 += 1;
+            }
+        }
+        for rule_id in expected.into_iter().filter(|r| !raised.contains(*r)) {
+            self.counts.entry(rule_id.clone()).or_default().2 += 1;
+        }
+    }
+
+    /// Scores per rule in rule ID order, and the micro-averaged
+    /// (precision, recall, F1) over all of them
+    pub fn scores(&self) -> (Vec<RuleScore>, (f64, f64, f64)) {
+        let rules = self.counts.iter()
+            .map(|(rule_id, &(tp, fp, fn_))| {
+                let (precision, recall, f1) = precision_recall_f1(tp, fp, fn_);
+                RuleScore {
+                    rule_id: rule_id.clone(),
+                    true_positives: tp,
+                    false_positives: fp,
+                    false_negatives: fn_,
+                    precision,
+                    recall,
+                    f1,
+                }
+            })
+            .collect();
+        let (tp, fp, fn_) = self.counts.values()
+            .fold((0, 0, 0), |(a, b, c), &(tp, fp, fn_)| (a + tp, b + fp, c + fn_));
+        (rules, precision_recall_f1(tp, fp, fn_))
+    }
+}
+
+fn precision_recall_f1(tp: u32, fp: u32, fn_: u32) -> (f64, f64, f64) {
+    let ratio = |num: u32, den: u32| if den == 0 { 0.0 } else { num as f64 / den as f64 };
+    let precision = ratio(tp, tp + fp);
+    let recall = ratio(tp, tp + fn_);
+    let f1 = if precision + recall == 0.0 { 0.0 } else { 2.0 * precision * recall / (precision + recall) };
+    (precision, recall, f1)
+}
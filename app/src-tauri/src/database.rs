@@ -1,6 +1,13 @@
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 use serde::{Deserialize, Serialize};
-use crate::{Alert, CallMetadata};
+use sha2::{Digest, Sha256};
+use crate::{AckSla, Alert, CallMetadata, EscalationPolicy};
+use crate::evaluator::{AlertTextOverride, DisclosureMilestone};
+use crate::exposure::PenaltyRange;
+use crate::rules::RuleSet;
+use crate::transcript::TalkTime;
 
 /// Stored alert with full context
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -18,11 +25,89 @@ pub struct StoredAlert {
     pub end_char: usize,
     pub why_it_matters: String,
     pub agent_fix_suggestion: String,
+    pub segment_id: Option<String>,
+    pub timestamp_ms: Option<u64>,
     pub created_at: String,
+    /// Review labels such as "coaching needed" or "escalate to legal"
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Severity the rule raised before repeat-violation escalation, if escalated
+    #[serde(default)]
+    pub escalated_from: Option<String>,
+    /// When the agent first acknowledged the alert, if they did
+    #[serde(default)]
+    pub acknowledged_at: Option<String>,
+    /// Provenance of the call metadata behind a metadata-rule alert
+    #[serde(default)]
+    pub metadata_source: Option<String>,
+    /// Named capture groups of the pattern that raised the alert
+    #[serde(default)]
+    pub captures: HashMap<String, String>,
 }
 
-/// Analytics summary data
+/// Filters for `get_alerts`. Set fields are combined; unset ones match every
+/// alert.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlertQuery {
+    #[serde(default)]
+    pub start_date: Option<String>,
+    #[serde(default)]
+    pub end_date: Option<String>,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    #[serde(default)]
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub rule_id: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// One page of `get_alerts`. `next_cursor` is set when more alerts follow and
+/// is passed back as `after_cursor` to fetch them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertPage {
+    pub alerts: Vec<StoredAlert>,
+    pub next_cursor: Option<String>,
+}
+
+/// Opaque cursor for the position just after `alert` in newest-first order:
+/// its `(created_at, id)` key, hex-encoded
+pub fn encode_alert_cursor(alert: &StoredAlert) -> String {
+    format!("{}\n{}", alert.created_at, alert.id)
+        .bytes()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// The `(created_at, id)` key in a cursor from `encode_alert_cursor`
+pub fn decode_alert_cursor(cursor: &str) -> Result<(String, String), String> {
+    let invalid = || format!("Invalid alert cursor: {}", cursor);
+    if cursor.len() % 2 != 0 || !cursor.is_ascii() {
+        return Err(invalid());
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect::<Result<Vec<u8>, _>>()
+        .map_err(|_| invalid())?;
+    let key = String::from_utf8(bytes).map_err(|_| invalid())?;
+    let (created_at, id) = key.split_once('\n').ok_or_else(invalid)?;
+    Ok((created_at.to_string(), id.to_string()))
+}
+
+/// Number of alerts carrying a tag
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: u32,
+}
+
+/// Separator for tags concatenated in a single column (ASCII unit separator)
+const TAG_SEPARATOR: char = '\u{1f}';
+
+/// Analytics summary data
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AnalyticsData {
     pub total_calls: u32,
     pub total_alerts: u32,
@@ -30,9 +115,53 @@ pub struct AnalyticsData {
     pub alerts_by_rule: Vec<RuleAlertCount>,
     pub alerts_by_agent: Vec<AgentAlertCount>,
     pub daily_trend: Vec<DailyAlertCount>,
+    pub agent_baselines: Vec<AgentBaselineComparison>,
+    pub suggestion_usage: Vec<SuggestionUsageCount>,
+    pub call_durations: CallDurationStats,
+    pub escalations: Vec<EscalationCount>,
+    pub talk_time: TalkTimeStats,
 }
 
+/// Analytics sections in the order they are computed, as reported in progress
+pub const ANALYTICS_SECTIONS: &[&str] = &[
+    "totals", "rules", "agents", "daily", "baselines", "suggestions", "durations", "escalations", "talk_time",
+];
+
+/// Progress of a long analytics computation: the section just finished and
+/// the analytics so far, with sections not yet computed left empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnalyticsProgress {
+    pub section: String,
+    pub completed: u32,
+    pub total: u32,
+    pub partial: AnalyticsData,
+}
+
+/// Acknowledgment of one agent's alerts of one severity against the SLA
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AckSlaRow {
+    pub agent_id: String,
+    pub agent_name: String,
+    pub severity: String,
+    pub sla_secs: u32,
+    pub alerts: u32,
+    pub acknowledged: u32,
+    pub within_sla: u32,
+    /// `within_sla` as a percentage of all alerts, acknowledged or not
+    pub within_sla_pct: f64,
+    pub avg_ack_secs: Option<f64>,
+}
+
+/// Escalated repeat violations of one rule by one agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalationCount {
+    pub agent_id: String,
+    pub agent_name: String,
+    pub rule_id: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AlertsBySeverity {
     pub high: u32,
     pub medium: u32,
@@ -58,13 +187,359 @@ pub struct DailyAlertCount {
     pub count: u32,
 }
 
+/// A day whose alert count was unusually high against the days before it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnomalyDay {
+    pub date: String,
+    pub count: u32,
+    pub trailing_mean: f64,
+    pub trailing_std_dev: f64,
+    /// Standard deviations above the trailing mean
+    pub z_score: f64,
+}
+
+/// An agent's alert rate in the selected window against their trailing baseline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentBaselineComparison {
+    pub agent_id: String,
+    pub agent_name: String,
+    /// Alerts per call in the selected window
+    pub window_alert_rate: f64,
+    /// Alerts per call over the 30 days before the window, if the agent had calls then
+    pub baseline_alert_rate: Option<f64>,
+    pub delta: Option<f64>,
+    /// "improving", "regressing", "steady" or "no_baseline"
+    pub direction: String,
+}
+
+/// How often an agent used a suggested line, and how the call went afterwards
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SuggestionUsageCount {
+    pub suggestion_text: String,
+    pub uses: u32,
+    pub calls: u32,
+    /// Alerts raised later in the same call, per use. Compare against
+    /// `total_alerts / total_calls` to see whether the suggestion helps.
+    pub alerts_after_per_use: f64,
+}
+
+/// Call length statistics for calls started in the window
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CallDurationStats {
+    pub completed_calls: u32,
+    /// Calls with no end time: still in progress, or the app exited mid-call
+    pub open_calls: u32,
+    pub avg_seconds: Option<f64>,
+    pub p50_seconds: Option<u64>,
+    pub p90_seconds: Option<u64>,
+    /// Alerts per call by call length, to see whether longer calls drift
+    pub by_length: Vec<DurationBucket>,
+}
+
+/// Agent vs customer speaking time for calls in the window that were streamed
+/// as timed segments
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TalkTimeStats {
+    pub calls: u32,
+    /// Mean of each call's agent share of speaking time
+    pub avg_agent_ratio: Option<f64>,
+    /// Calls where the agent did at least `AGENT_DOMINANT_RATIO` of the talking
+    pub agent_dominant_calls: u32,
+    pub by_agent: Vec<AgentTalkTime>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentTalkTime {
+    pub agent_id: String,
+    pub agent_name: String,
+    pub calls: u32,
+    pub avg_agent_ratio: f64,
+    pub agent_dominant_calls: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DurationBucket {
+    /// e.g. "2-5m"
+    pub label: String,
+    pub calls: u32,
+    pub alerts_per_call: f64,
+}
+
+/// How often a rule fired in a date range, zero included
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleCoverage {
+    pub rule_id: String,
+    pub count: u32,
+    pub last_fired: Option<String>,
+}
+
+/// Outcomes on calls where a rule's suggested line was shown
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SuggestionStats {
+    pub rule_id: String,
+    pub calls_shown: u32,
+    /// The violation didn't happen (again) after the line was shown
+    pub calls_avoided: u32,
+    pub calls_repeated: u32,
+    /// `calls_avoided / calls_shown`, 0 when never shown
+    pub avoidance_rate: f64,
+    /// Calls where the agent used the line
+    pub calls_used: u32,
+    pub calls_used_avoided: u32,
+}
+
+/// How often a phrasing was quoted as evidence for a rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteCount {
+    /// Lowercased with whitespace collapsed
+    pub quote: String,
+    pub count: u32,
+}
+
+/// A call and the (rule_id, severity) of each alert raised on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallAlertSummary {
+    pub call_id: String,
+    pub agent_id: String,
+    pub agent_name: String,
+    pub call_start_time: String,
+    pub alerts: Vec<(String, String)>,
+}
+
+/// One entry in a call's review timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    /// "call_start", "alert", "disclosure" or "call_end"
+    pub kind: String,
+    pub title: String,
+    pub rule_id: Option<String>,
+    pub alert_id: Option<String>,
+    pub severity: Option<String>,
+    pub quote: Option<String>,
+    /// UTF-16 offset of the evidence or disclosure in the transcript
+    pub start_char: Option<usize>,
+    /// Audio position, when the transcript was evaluated by segment
+    pub timestamp_ms: Option<u64>,
+    /// Wall-clock time (UTC) for the call anchors and stored alerts
+    pub at: Option<String>,
+}
+
+/// Which alerts a bulk acknowledgment covers. Set fields are combined, so
+/// `call_id` with `agent_id` only covers that agent's alerts on that call.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlertFilter {
+    #[serde(default)]
+    pub call_id: Option<String>,
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    #[serde(default)]
+    pub alert_ids: Option<Vec<String>>,
+}
+
+impl AlertFilter {
+    /// A filter must narrow the alerts down; an empty one would acknowledge everything
+    pub fn validate(&self) -> Result<(), String> {
+        if self.call_id.is_none() && self.agent_id.is_none() && self.alert_ids.is_none() {
+            return Err("Alert filter needs a call_id, agent_id or alert_ids".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// A call preserved for litigation, exempt from retention purges
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LegalHold {
+    pub call_id: String,
+    pub agent_id: String,
+    pub agent_name: String,
+    pub call_start_time: String,
+    pub call_end_time: Option<String>,
+    pub alert_count: u32,
+}
+
+/// What a retention purge removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeSummary {
+    pub alerts_deleted: u32,
+    pub calls_deleted: u32,
+    /// Held calls that would otherwise have been purged
+    pub held_calls_skipped: u32,
+}
+
+/// What a consumer deletion request removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletionReport {
+    pub calls_deleted: u32,
+    pub alerts_deleted: u32,
+    /// Disclosure milestones, which quote the transcript
+    pub milestones_deleted: u32,
+    /// Calls to the number kept because they're under legal hold
+    pub held_call_ids: Vec<String>,
+}
+
+/// A ruleset version kept for point-in-time evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RulesetVersion {
+    pub version: String,
+    pub last_updated: String,
+    pub rule_count: u32,
+    /// When this version was first activated
+    pub retained_at: String,
+}
+
+/// Upper bounds (exclusive, in seconds) and labels for the call length buckets
+const DURATION_BUCKETS: [(u64, &str); 4] = [
+    (120, "0-2m"),
+    (300, "2-5m"),
+    (600, "5-10m"),
+    (u64::MAX, "10m+"),
+];
+
+/// Agent share of speaking time at or above which a call counts as agent-dominant
+const AGENT_DOMINANT_RATIO: f64 = 0.9;
+
+/// How long a `store_alert` idempotency key is remembered
+const IDEMPOTENCY_KEY_TTL_HOURS: u32 = 24;
+
+/// Marks a `calls.customer_phone` value as a hash rather than the number
+const PHONE_HASH_PREFIX: &str = "sha256:";
+
+/// Length of the trailing window used for agent baselines
+const BASELINE_DAYS: i64 = 30;
+
+/// Change in alerts per call treated as noise rather than a trend
+const BASELINE_TOLERANCE: f64 = 0.1;
+
+/// Days before each day that its alert count is compared against
+const ANOMALY_TRAILING_DAYS: i64 = 14;
+
+/// Fewest trailing days with data before a day can be flagged
+const ANOMALY_MIN_HISTORY_DAYS: usize = 7;
+
+/// Standard deviations above the trailing mean at which a day is flagged
+const ANOMALY_STD_DEVS: f64 = 3.0;
+
+/// Floor on the trailing standard deviation, so a day or two of alerts after
+/// a run of identical days isn't flagged as a breakdown
+const ANOMALY_MIN_STD_DEV: f64 = 1.0;
+
+/// Most date ranges the analytics cache holds before it is cleared
+const ANALYTICS_CACHE_CAPACITY: usize = 64;
+
+/// Database file for the default (single-tenant) deployment
+const DEFAULT_DB_PATH: &str = "whisperwire.db";
+
+/// Called with the tenant and the stored row each time an alert is written
+pub type AlertStoredHook = Arc<dyn Fn(Option<String>, StoredAlert) + Send + Sync>;
+
+/// Per-tenant databases. Each tenant gets its own SQLite file so queries and
+/// analytics can never cross tenants; handles are opened on first use.
+pub struct TenantDatabases {
+    default: Arc<Mutex<Database>>,
+    tenants: Mutex<HashMap<String, Arc<Mutex<Database>>>>,
+    alert_stored: Mutex<Option<AlertStoredHook>>,
+}
+
+impl TenantDatabases {
+    pub fn new() -> Result<Self, rusqlite::Error> {
+        Ok(TenantDatabases {
+            default: Arc::new(Mutex::new(Database::new()?)),
+            tenants: Mutex::new(HashMap::new()),
+            alert_stored: Mutex::new(None),
+        })
+    }
+    
+    /// Call `hook` after every alert written to any tenant's database
+    pub fn on_alert_stored(&self, hook: AlertStoredHook) {
+        let mut databases = vec![self.default.clone()];
+        if let Ok(tenants) = self.tenants.lock() {
+            databases.extend(tenants.values().cloned());
+        }
+        for db in databases {
+            if let Ok(mut db) = db.lock() {
+                db.alert_stored = Some(hook.clone());
+            }
+        }
+        *self.alert_stored.lock().unwrap() = Some(hook);
+    }
+    
+    /// Get the database for a tenant, or the default database when none is given
+    pub fn for_tenant(&self, tenant_id: Option<&str>) -> Result<Arc<Mutex<Database>>, String> {
+        let Some(tenant_id) = tenant_id else {
+            return Ok(self.default.clone());
+        };
+        
+        // Tenant IDs become file names, so keep them to a safe character set
+        let valid = !tenant_id.is_empty()
+            && tenant_id.len() <= 64
+            && tenant_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(format!("Invalid tenant ID: {:?}", tenant_id));
+        }
+        
+        let mut tenants = self.tenants.lock().map_err(|e| e.to_string())?;
+        if let Some(db) = tenants.get(tenant_id) {
+            return Ok(db.clone());
+        }
+        
+        let mut db = Database::open(&format!("whisperwire-{}.db", tenant_id)).map_err(|e| e.to_string())?;
+        db.alert_stored = self.alert_stored.lock().map_err(|e| e.to_string())?.clone();
+        let db = Arc::new(Mutex::new(db));
+        tenants.insert(tenant_id.to_string(), db.clone());
+        log::info!("Opened database for tenant: {}", tenant_id);
+        Ok(db)
+    }
+    
+    /// Checkpoint the WAL of every open database, logging failures
+    pub fn checkpoint_all(&self) {
+        let mut databases = vec![(None, self.default.clone())];
+        if let Ok(tenants) = self.tenants.lock() {
+            databases.extend(tenants.iter().map(|(id, db)| (Some(id.clone()), db.clone())));
+        }
+        for (tenant_id, db) in databases {
+            let result = db.lock().map_err(|e| e.to_string())
+                .and_then(|db| db.checkpoint().map_err(|e| e.to_string()));
+            if let Err(e) = result {
+                log::error!("Failed to checkpoint database for tenant {:?}: {}", tenant_id, e);
+            }
+        }
+    }
+    
+    /// Run a database operation for a tenant on tokio's blocking pool, so long
+    /// queries (e.g. analytics over a large database) don't stall other commands
+    pub async fn run_blocking<T, F>(&self, tenant_id: Option<&str>, op: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Database) -> Result<T, rusqlite::Error> + Send + 'static,
+    {
+        let db = self.for_tenant(tenant_id)?;
+        tokio::task::spawn_blocking(move || {
+            let db = db.lock().map_err(|e| e.to_string())?;
+            op(&db).map_err(|e| e.to_string())
+        })
+        .await
+        .map_err(|e| format!("Database task failed: {}", e))?
+    }
+}
+
 pub struct Database {
     conn: Connection,
+    /// Computed analytics keyed by (start_date, end_date)
+    analytics_cache: Mutex<HashMap<(String, String), AnalyticsData>>,
+    /// Random per-database key mixed into hashed phone numbers, so a stolen
+    /// database can't be reversed by hashing every possible number
+    phone_hash_key: String,
+    alert_stored: Option<AlertStoredHook>,
 }
 
 impl Database {
     pub fn new() -> Result<Self, rusqlite::Error> {
-        let conn = Connection::open("whisperwire.db")?;
+        Self::open(DEFAULT_DB_PATH)
+    }
+    
+    /// Open (creating if needed) the database at `path`
+    pub fn open(path: &str) -> Result<Self, rusqlite::Error> {
+        let conn = Connection::open(path)?;
         
         // Create tables
         conn.execute_batch(r#"
@@ -79,6 +554,9 @@ impl Database {
                 has_prior_consent INTEGER NOT NULL DEFAULT 0,
                 is_prerecorded INTEGER NOT NULL DEFAULT 0,
                 call_type TEXT NOT NULL,
+                consumer_state TEXT,
+                duration_seconds INTEGER,
+                legal_hold INTEGER NOT NULL DEFAULT 0,
                 created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
             );
             
@@ -96,25 +574,161 @@ impl Database {
                 end_char INTEGER NOT NULL,
                 why_it_matters TEXT NOT NULL,
                 agent_fix_suggestion TEXT NOT NULL,
+                segment_id TEXT,
+                timestamp_ms INTEGER,
+                escalated_from TEXT,
                 created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
                 FOREIGN KEY (call_id) REFERENCES calls(call_id)
             );
             
+            CREATE TABLE IF NOT EXISTS rule_overrides (
+                rule_id TEXT PRIMARY KEY,
+                enabled INTEGER NOT NULL,
+                updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            
+            CREATE TABLE IF NOT EXISTS alert_text_overrides (
+                rule_id TEXT PRIMARY KEY,
+                why_it_matters TEXT,
+                agent_fix_suggestion TEXT,
+                updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            
+            CREATE TABLE IF NOT EXISTS script_allowlist (
+                campaign_id TEXT NOT NULL,
+                phrase TEXT NOT NULL,
+                PRIMARY KEY (campaign_id, phrase)
+            );
+            
+            CREATE TABLE IF NOT EXISTS alert_tags (
+                alert_id TEXT NOT NULL,
+                tag TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (alert_id, tag)
+            );
+            
+            CREATE TABLE IF NOT EXISTS call_milestones (
+                call_id TEXT NOT NULL,
+                rule_id TEXT NOT NULL,
+                label TEXT NOT NULL,
+                quote TEXT NOT NULL,
+                start_char INTEGER NOT NULL,
+                end_char INTEGER NOT NULL,
+                timestamp_ms INTEGER,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (call_id, rule_id)
+            );
+            
+            CREATE TABLE IF NOT EXISTS penalty_table (
+                rule_id TEXT PRIMARY KEY,
+                min_amount REAL NOT NULL,
+                max_amount REAL NOT NULL
+            );
+            
+            CREATE TABLE IF NOT EXISTS idempotency_keys (
+                key TEXT PRIMARY KEY,
+                alert_id TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            
+            CREATE TABLE IF NOT EXISTS dnc_numbers (
+                phone TEXT NOT NULL,
+                list_type TEXT NOT NULL,
+                loaded_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (phone, list_type)
+            );
+            
+            CREATE TABLE IF NOT EXISTS ruleset_versions (
+                version TEXT PRIMARY KEY,
+                last_updated TEXT NOT NULL,
+                rule_count INTEGER NOT NULL,
+                ruleset TEXT NOT NULL,
+                retained_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            
+            CREATE TABLE IF NOT EXISTS settings (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+            
+            CREATE TABLE IF NOT EXISTS phone_hash_key (
+                id INTEGER PRIMARY KEY CHECK (id = 1),
+                key TEXT NOT NULL
+            );
+            
+            CREATE TABLE IF NOT EXISTS suggestion_usage (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                call_id TEXT NOT NULL,
+                suggestion_text TEXT NOT NULL,
+                created_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+            );
+            
+            CREATE TABLE IF NOT EXISTS suggestions_shown (
+                call_id TEXT NOT NULL,
+                rule_id TEXT NOT NULL,
+                prompted_by_alert INTEGER NOT NULL DEFAULT 0,
+                shown_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (call_id, rule_id)
+            );
+            
+            CREATE INDEX IF NOT EXISTS idx_alert_tags_tag ON alert_tags(tag);
+            CREATE INDEX IF NOT EXISTS idx_suggestions_shown_rule_id ON suggestions_shown(rule_id);
+            CREATE INDEX IF NOT EXISTS idx_idempotency_keys_created_at ON idempotency_keys(created_at);
+            CREATE INDEX IF NOT EXISTS idx_suggestion_usage_call_id ON suggestion_usage(call_id);
             CREATE INDEX IF NOT EXISTS idx_alerts_call_id ON alerts(call_id);
             CREATE INDEX IF NOT EXISTS idx_alerts_agent_id ON alerts(agent_id);
             CREATE INDEX IF NOT EXISTS idx_alerts_severity ON alerts(severity);
             CREATE INDEX IF NOT EXISTS idx_alerts_rule_id ON alerts(rule_id);
             CREATE INDEX IF NOT EXISTS idx_alerts_created_at ON alerts(created_at);
+            CREATE INDEX IF NOT EXISTS idx_alerts_created_at_id ON alerts(created_at, id);
         "#)?;
         
-        Ok(Database { conn })
+        // Migrate databases created before these columns existed
+        ensure_column(&conn, "alerts", "segment_id", "TEXT")?;
+        ensure_column(&conn, "alerts", "timestamp_ms", "INTEGER")?;
+        ensure_column(&conn, "calls", "consumer_state", "TEXT")?;
+        ensure_column(&conn, "calls", "duration_seconds", "INTEGER")?;
+        ensure_column(&conn, "calls", "legal_hold", "INTEGER NOT NULL DEFAULT 0")?;
+        ensure_column(&conn, "alerts", "escalated_from", "TEXT")?;
+        ensure_column(&conn, "alerts", "acknowledged_at", "TEXT")?;
+        ensure_column(&conn, "alerts", "metadata_source", "TEXT")?;
+        ensure_column(&conn, "alerts", "captures", "TEXT")?;
+        ensure_column(&conn, "calls", "metadata_source", "TEXT NOT NULL DEFAULT 'unknown'")?;
+        ensure_column(&conn, "calls", "agent_talk_ms", "INTEGER")?;
+        ensure_column(&conn, "calls", "customer_talk_ms", "INTEGER")?;
+        ensure_column(&conn, "calls", "agent_words", "INTEGER")?;
+        ensure_column(&conn, "calls", "customer_words", "INTEGER")?;
+        ensure_column(&conn, "calls", "number_reassigned", "INTEGER NOT NULL DEFAULT 0")?;
+        ensure_column(&conn, "calls", "customer_phone", "TEXT")?;
+        ensure_column(&conn, "suggestion_usage", "rule_id", "TEXT")?;
+        conn.execute("CREATE INDEX IF NOT EXISTS idx_calls_customer_phone ON calls(customer_phone)", [])?;
+        
+        // Backfill durations for calls ended before duration was stored
+        conn.execute(
+            r#"UPDATE calls SET duration_seconds = MAX(0, CAST(ROUND((julianday(call_end_time) - julianday(created_at)) * 86400) AS INTEGER))
+               WHERE duration_seconds IS NULL AND call_end_time IS NOT NULL"#,
+            [],
+        )?;
+        
+        conn.execute(
+            "INSERT OR IGNORE INTO phone_hash_key (id, key) VALUES (1, ?1)",
+            params![uuid::Uuid::new_v4().to_string()],
+        )?;
+        let phone_hash_key = conn.query_row("SELECT key FROM phone_hash_key WHERE id = 1", [], |row| row.get(0))?;
+        
+        Ok(Database { conn, analytics_cache: Mutex::new(HashMap::new()), phone_hash_key, alert_stored: None })
     }
     
-    pub fn start_call_session(&self, metadata: &CallMetadata) -> Result<(), rusqlite::Error> {
-        self.conn.execute(
+    /// Record a call session. Returns `false` if the session already existed,
+    /// e.g. when the frontend retries after a dropped connection. With
+    /// `hash_phone` the customer's number is stored as a keyed hash.
+    pub fn start_call_session(&self, metadata: &CallMetadata, hash_phone: bool) -> Result<bool, rusqlite::Error> {
+        let inserted = self.conn.execute(
             r#"INSERT INTO calls (call_id, agent_id, agent_name, call_start_time, caller_timezone, 
-                is_dnc_listed, has_prior_consent, is_prerecorded, call_type) 
-               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)"#,
+                is_dnc_listed, has_prior_consent, is_prerecorded, call_type, consumer_state, metadata_source,
+                number_reassigned, customer_phone) 
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)
+               ON CONFLICT(call_id) DO NOTHING"#,
             params![
                 metadata.call_id,
                 metadata.agent_id,
@@ -125,24 +739,163 @@ impl Database {
                 metadata.has_prior_consent as i32,
                 metadata.is_prerecorded as i32,
                 metadata.call_type,
+                metadata.consumer_state,
+                metadata.metadata_source,
+                metadata.number_reassigned as i32,
+                self.stored_phone(metadata, hash_phone),
             ],
         )?;
-        Ok(())
+        if inserted > 0 {
+            self.invalidate_analytics_for_call(&metadata.call_id)?;
+        }
+        Ok(inserted > 0)
+    }
+    
+    /// Load the metadata a call session was started with
+    pub fn get_call_metadata(&self, call_id: &str) -> Result<Option<CallMetadata>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT call_id, agent_id, agent_name, call_start_time, caller_timezone, 
+             is_dnc_listed, has_prior_consent, is_prerecorded, call_type, consumer_state, metadata_source,
+             number_reassigned, customer_phone 
+             FROM calls WHERE call_id = ?1"
+        )?;
+        let mut rows = stmt.query_map(params![call_id], |row| {
+            Ok(CallMetadata {
+                call_id: row.get(0)?,
+                agent_id: row.get(1)?,
+                agent_name: row.get(2)?,
+                call_start_time: row.get(3)?,
+                caller_timezone: row.get(4)?,
+                customer_phone: row.get::<_, Option<String>>(12)?.filter(|p| !p.starts_with(PHONE_HASH_PREFIX)),
+                is_dnc_listed: row.get(5)?,
+                has_prior_consent: row.get(6)?,
+                is_prerecorded: row.get(7)?,
+                call_type: row.get(8)?,
+                number_reassigned: row.get(11)?,
+                consumer_state: row.get(9)?,
+                campaign_id: None,
+                company_name: None,
+                metadata_source: row.get(10)?,
+                tenant_id: None,
+            })
+        })?;
+        rows.next().transpose()
+    }
+    
+    /// Overwrite a call's metadata after a mid-call correction, e.g. the number
+    /// turned out to be DNC-listed. Returns `false` if the call doesn't exist.
+    pub fn update_call_metadata(&self, metadata: &CallMetadata, hash_phone: bool) -> Result<bool, rusqlite::Error> {
+        let updated = self.conn.execute(
+            r#"UPDATE calls SET agent_id = ?2, agent_name = ?3, call_start_time = ?4, caller_timezone = ?5,
+                is_dnc_listed = ?6, has_prior_consent = ?7, is_prerecorded = ?8, call_type = ?9, consumer_state = ?10,
+                metadata_source = ?11, number_reassigned = ?12, customer_phone = ?13
+               WHERE call_id = ?1"#,
+            params![
+                metadata.call_id,
+                metadata.agent_id,
+                metadata.agent_name,
+                metadata.call_start_time,
+                metadata.caller_timezone,
+                metadata.is_dnc_listed as i32,
+                metadata.has_prior_consent as i32,
+                metadata.is_prerecorded as i32,
+                metadata.call_type,
+                metadata.consumer_state,
+                metadata.metadata_source,
+                metadata.number_reassigned as i32,
+                self.stored_phone(metadata, hash_phone),
+            ],
+        )?;
+        // Analytics group by agent and call type
+        if updated > 0 {
+            self.invalidate_analytics_for_call(&metadata.call_id)?;
+        }
+        Ok(updated > 0)
     }
     
+    /// Mark a call ended and store its duration. Duration is measured from when
+    /// the session was recorded, so both ends use the database clock.
     pub fn end_call_session(&self, call_id: &str) -> Result<(), rusqlite::Error> {
         self.conn.execute(
-            "UPDATE calls SET call_end_time = CURRENT_TIMESTAMP WHERE call_id = ?1",
+            r#"UPDATE calls SET call_end_time = CURRENT_TIMESTAMP,
+                duration_seconds = MAX(0, CAST(ROUND((julianday(CURRENT_TIMESTAMP) - julianday(created_at)) * 86400) AS INTEGER))
+               WHERE call_id = ?1"#,
             params![call_id],
         )?;
+        self.invalidate_analytics_for_call(call_id)
+    }
+    
+    /// Store a call's end-of-call alerts, disclosure milestones and talk time
+    /// (for calls streamed as segments) and mark it ended
+    pub fn finish_call(
+        &self,
+        call_id: &str,
+        tenant_id: Option<String>,
+        alerts: &[Alert],
+        milestones: &[DisclosureMilestone],
+        talk_time: Option<&TalkTime>,
+        policy: &EscalationPolicy,
+    ) -> Result<(), rusqlite::Error> {
+        if let Some(mut metadata) = self.get_call_metadata(call_id)? {
+            metadata.tenant_id = tenant_id;
+            for alert in alerts {
+                self.insert_alert(alert, &metadata, policy)?;
+            }
+        }
+        self.insert_milestones(call_id, milestones)?;
+        if let Some(talk) = talk_time {
+            self.conn.execute(
+                r#"UPDATE calls SET agent_talk_ms = ?2, customer_talk_ms = ?3, agent_words = ?4, customer_words = ?5
+                   WHERE call_id = ?1"#,
+                params![call_id, talk.agent_talk_ms, talk.customer_talk_ms, talk.agent_words, talk.customer_words],
+            )?;
+        }
+        self.end_call_session(call_id)
+    }
+    
+    /// Flush the write-ahead log into the main database file. A no-op outside
+    /// WAL mode.
+    pub fn checkpoint(&self) -> Result<(), rusqlite::Error> {
+        self.conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+    }
+    
+    /// Store an alert, escalating it under `policy` if the agent has violated
+    /// the same rule on enough earlier calls. Returns whether it was escalated.
+    pub fn insert_alert(&self, alert: &Alert, metadata: &CallMetadata, policy: &EscalationPolicy) -> Result<bool, rusqlite::Error> {
+        let escalated = self.write_alert(alert, metadata, policy)?;
+        self.alert_written(&alert.id, metadata)?;
+        Ok(escalated)
+    }
+    
+    /// Tell the `on_alert_stored` hook, if any, about a newly written alert
+    fn alert_written(&self, alert_id: &str, metadata: &CallMetadata) -> Result<(), rusqlite::Error> {
+        if let Some(ref hook) = self.alert_stored {
+            if let Some(stored) = self.get_alert(alert_id)? {
+                hook(metadata.tenant_id.clone(), stored);
+            }
+        }
         Ok(())
     }
     
-    pub fn insert_alert(&self, alert: &Alert, metadata: &CallMetadata) -> Result<(), rusqlite::Error> {
+    fn write_alert(&self, alert: &Alert, metadata: &CallMetadata, policy: &EscalationPolicy) -> Result<bool, rusqlite::Error> {
+        let escalated = policy.enabled
+            && self.prior_violation_calls(&metadata.agent_id, &alert.rule_id, &metadata.call_id, policy.window_days)? >= policy.threshold;
+        let (severity, escalated_from) = if escalated {
+            (escalate_severity(&alert.severity), Some(alert.severity.as_str()))
+        } else {
+            (alert.severity.as_str(), None)
+        };
+        let captures = if alert.captures.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_string(&alert.captures).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?)
+        };
+        
         self.conn.execute(
             r#"INSERT INTO alerts (id, call_id, agent_id, agent_name, rule_id, title, severity, 
-                confidence, quote, start_char, end_char, why_it_matters, agent_fix_suggestion)
-               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)"#,
+                confidence, quote, start_char, end_char, why_it_matters, agent_fix_suggestion, segment_id, timestamp_ms,
+                escalated_from, metadata_source, captures)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)"#,
             params![
                 alert.id,
                 metadata.call_id,
@@ -150,102 +903,1083 @@ impl Database {
                 metadata.agent_name,
                 alert.rule_id,
                 alert.title,
-                alert.severity,
+                severity,
                 alert.confidence,
                 alert.evidence.quote,
                 alert.evidence.start_char,
                 alert.evidence.end_char,
                 alert.why_it_matters,
                 alert.agent_fix_suggestion,
+                alert.segment_id,
+                alert.timestamp_ms,
+                escalated_from,
+                alert.metadata_source,
+                captures,
             ],
         )?;
-        Ok(())
+        self.invalidate_analytics_for_call(&metadata.call_id)?;
+        Ok(escalated)
+    }
+    
+    /// `insert_alert` for a caller that may retry: if `key` was used in the last
+    /// `IDEMPOTENCY_KEY_TTL_HOURS`, nothing is inserted and the ID of the alert
+    /// stored under it is returned with `None`. Otherwise the alert is inserted
+    /// and returned with whether it was escalated.
+    pub fn insert_alert_once(
+        &self,
+        key: &str,
+        alert: &Alert,
+        metadata: &CallMetadata,
+        policy: &EscalationPolicy,
+    ) -> Result<(String, Option<bool>), rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM idempotency_keys WHERE created_at < datetime('now', ?1)",
+            params![format!("-{} hours", IDEMPOTENCY_KEY_TTL_HOURS)],
+        )?;
+        let existing: Option<String> = tx.query_row(
+            "SELECT alert_id FROM idempotency_keys WHERE key = ?1",
+            params![key],
+            |row| row.get(0),
+        ).optional()?;
+        if let Some(alert_id) = existing {
+            tx.commit()?;
+            return Ok((alert_id, None));
+        }
+        
+        let escalated = self.write_alert(alert, metadata, policy)?;
+        tx.execute(
+            "INSERT INTO idempotency_keys (key, alert_id) VALUES (?1, ?2)",
+            params![key, alert.id],
+        )?;
+        tx.commit()?;
+        self.alert_written(&alert.id, metadata)?;
+        Ok((alert.id.clone(), Some(escalated)))
+    }
+    
+    /// Number of other calls in the last `window_days` where the agent already
+    /// triggered `rule_id`
+    fn prior_violation_calls(&self, agent_id: &str, rule_id: &str, call_id: &str, window_days: u32) -> Result<u32, rusqlite::Error> {
+        self.conn.query_row(
+            r#"SELECT COUNT(DISTINCT call_id) FROM alerts
+               WHERE agent_id = ?1 AND rule_id = ?2 AND call_id != ?3
+                 AND created_at >= datetime('now', '-' || ?4 || ' days')"#,
+            params![agent_id, rule_id, call_id, window_days],
+            |row| row.get(0),
+        )
     }
     
     pub fn get_alerts(
         &self,
-        start_date: Option<String>,
-        end_date: Option<String>,
-        agent_id: Option<String>,
-        severity: Option<String>,
-        rule_id: Option<String>,
+        filters: &AlertQuery,
         limit: Option<u32>,
-        offset: Option<u32>,
+        after: Option<(String, String)>,
     ) -> Result<Vec<StoredAlert>, rusqlite::Error> {
-        let mut query = String::from(
-            "SELECT id, call_id, agent_id, agent_name, rule_id, title, severity, confidence, 
-             quote, start_char, end_char, why_it_matters, agent_fix_suggestion, created_at 
-             FROM alerts WHERE 1=1"
-        );
+        let mut query = format!("{} WHERE 1=1", stored_alert_select());
         
         let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
         
-        if let Some(ref sd) = start_date {
+        if let Some(ref sd) = filters.start_date {
             query.push_str(" AND created_at >= ?");
             params_vec.push(Box::new(sd.clone()));
         }
-        if let Some(ref ed) = end_date {
+        if let Some(ref ed) = filters.end_date {
             query.push_str(" AND created_at <= ?");
             params_vec.push(Box::new(ed.clone()));
         }
-        if let Some(ref aid) = agent_id {
+        if let Some(ref aid) = filters.agent_id {
             query.push_str(" AND agent_id = ?");
             params_vec.push(Box::new(aid.clone()));
         }
-        if let Some(ref sev) = severity {
+        if let Some(ref sev) = filters.severity {
             query.push_str(" AND severity = ?");
             params_vec.push(Box::new(sev.clone()));
         }
-        if let Some(ref rid) = rule_id {
+        if let Some(ref rid) = filters.rule_id {
             query.push_str(" AND rule_id = ?");
             params_vec.push(Box::new(rid.clone()));
         }
+        if let Some(ref t) = filters.tag {
+            query.push_str(" AND id IN (SELECT alert_id FROM alert_tags WHERE tag = ?)");
+            params_vec.push(Box::new(t.clone()));
+        }
+        // Keyset pagination: rows strictly after the last one already seen, so
+        // alerts stored between pages can't shift rows across the page boundary
+        if let Some((created_at, id)) = after {
+            query.push_str(" AND (created_at, id) < (?, ?)");
+            params_vec.push(Box::new(created_at));
+            params_vec.push(Box::new(id));
+        }
         
-        query.push_str(" ORDER BY created_at DESC");
+        query.push_str(" ORDER BY created_at DESC, id DESC");
         
         if let Some(l) = limit {
             query.push_str(&format!(" LIMIT {}", l));
         }
-        if let Some(o) = offset {
-            query.push_str(&format!(" OFFSET {}", o));
-        }
         
         let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
         
         let mut stmt = self.conn.prepare(&query)?;
-        let alerts = stmt.query_map(params_refs.as_slice(), |row| {
-            Ok(StoredAlert {
-                id: row.get(0)?,
-                call_id: row.get(1)?,
-                agent_id: row.get(2)?,
-                agent_name: row.get(3)?,
-                rule_id: row.get(4)?,
-                title: row.get(5)?,
-                severity: row.get(6)?,
-                confidence: row.get(7)?,
-                quote: row.get(8)?,
-                start_char: row.get(9)?,
-                end_char: row.get(10)?,
-                why_it_matters: row.get(11)?,
-                agent_fix_suggestion: row.get(12)?,
-                created_at: row.get(13)?,
-            })
-        })?.collect::<Result<Vec<_>, _>>()?;
+        let alerts = stmt.query_map(params_refs.as_slice(), stored_alert_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
         
         Ok(alerts)
     }
     
-    pub fn get_analytics(&self, start_date: &str, end_date: &str) -> Result<AnalyticsData, rusqlite::Error> {
-        // Total calls
-        let total_calls: u32 = self.conn.query_row(
-            "SELECT COUNT(*) FROM calls WHERE created_at >= ?1 AND created_at <= ?2",
-            params![start_date, end_date],
-            |row| row.get(0),
-        )?;
+    /// A single stored alert by ID
+    pub fn get_alert(&self, alert_id: &str) -> Result<Option<StoredAlert>, rusqlite::Error> {
+        self.conn.query_row(
+            &format!("{} WHERE id = ?1", stored_alert_select()),
+            params![alert_id],
+            stored_alert_from_row,
+        ).optional()
+    }
+    
+    /// Persist the enabled state for a set of rules
+    pub fn set_rule_overrides(&self, rule_ids: &[String], enabled: bool) -> Result<(), rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        for rule_id in rule_ids {
+            tx.execute(
+                r#"INSERT INTO rule_overrides (rule_id, enabled) VALUES (?1, ?2)
+                   ON CONFLICT(rule_id) DO UPDATE SET enabled = ?2, updated_at = CURRENT_TIMESTAMP"#,
+                params![rule_id, enabled as i32],
+            )?;
+        }
+        tx.commit()
+    }
+    
+    /// All persisted (rule_id, enabled) overrides
+    pub fn get_rule_overrides(&self) -> Result<Vec<(String, bool)>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare("SELECT rule_id, enabled FROM rule_overrides")?;
+        let overrides = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(overrides)
+    }
+    
+    /// Replace the approved script phrases for a campaign
+    pub fn set_script_allowlist(&self, campaign_id: &str, phrases: &[String]) -> Result<(), rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM script_allowlist WHERE campaign_id = ?1", params![campaign_id])?;
+        for phrase in phrases {
+            tx.execute(
+                "INSERT OR IGNORE INTO script_allowlist (campaign_id, phrase) VALUES (?1, ?2)",
+                params![campaign_id, phrase],
+            )?;
+        }
+        tx.commit()
+    }
+    
+    /// All persisted script allowlists, keyed by campaign
+    pub fn get_script_allowlists(&self) -> Result<HashMap<String, Vec<String>>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare("SELECT campaign_id, phrase FROM script_allowlist")?;
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))?;
         
-        // Total alerts
-        let total_alerts: u32 = self.conn.query_row(
-            "SELECT COUNT(*) FROM alerts WHERE created_at >= ?1 AND created_at <= ?2",
+        let mut allowlists: HashMap<String, Vec<String>> = HashMap::new();
+        for row in rows {
+            let (campaign_id, phrase) = row?;
+            allowlists.entry(campaign_id).or_default().push(phrase);
+        }
+        Ok(allowlists)
+    }
+    
+    /// Replace the stored DNC list of this type with `numbers` (already normalized)
+    pub fn replace_dnc_list(&self, list_type: &str, numbers: &[String]) -> Result<(), rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM dnc_numbers WHERE list_type = ?1", params![list_type])?;
+        {
+            let mut stmt = tx.prepare("INSERT OR IGNORE INTO dnc_numbers (phone, list_type) VALUES (?1, ?2)")?;
+            for number in numbers {
+                stmt.execute(params![number, list_type])?;
+            }
+        }
+        tx.commit()
+    }
+    
+    /// Which DNC lists (internal, national) a normalized number is on
+    pub fn dnc_lists_for(&self, phone: &str) -> Result<Vec<String>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare("SELECT list_type FROM dnc_numbers WHERE phone = ?1 ORDER BY list_type")?;
+        let lists = stmt.query_map(params![phone], |row| row.get(0))?;
+        lists.collect()
+    }
+    
+    /// Keep a copy of an activated ruleset under its version. The first copy
+    /// of a version wins, so a version always evaluates the way it did when it
+    /// went live. Returns whether this version was new.
+    pub fn retain_ruleset(&self, ruleset: &RuleSet) -> Result<bool, rusqlite::Error> {
+        let json = serde_json::to_string(ruleset).map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
+        let inserted = self.conn.execute(
+            "INSERT OR IGNORE INTO ruleset_versions (version, last_updated, rule_count, ruleset) VALUES (?1, ?2, ?3, ?4)",
+            params![ruleset.version, ruleset.last_updated, ruleset.rules.len() as u32, json],
+        )?;
+        Ok(inserted > 0)
+    }
+    
+    /// A retained ruleset by version, `None` if that version wasn't retained
+    pub fn get_ruleset_version(&self, version: &str) -> Result<Option<RuleSet>, rusqlite::Error> {
+        let json: Option<String> = self.conn.query_row(
+            "SELECT ruleset FROM ruleset_versions WHERE version = ?1",
+            params![version],
+            |row| row.get(0),
+        ).optional()?;
+        json.map(|json| serde_json::from_str(&json)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))))
+            .transpose()
+    }
+    
+    /// Retained ruleset versions, most recently activated first
+    pub fn list_ruleset_versions(&self) -> Result<Vec<RulesetVersion>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT version, last_updated, rule_count, retained_at FROM ruleset_versions ORDER BY retained_at DESC, version DESC",
+        )?;
+        let versions = stmt.query_map([], |row| Ok(RulesetVersion {
+            version: row.get(0)?,
+            last_updated: row.get(1)?,
+            rule_count: row.get(2)?,
+            retained_at: row.get(3)?,
+        }))?;
+        versions.collect()
+    }
+    
+    /// Set or clear (`None`) a rule's alert wording override
+    pub fn set_alert_text_override(&self, rule_id: &str, text: Option<&AlertTextOverride>) -> Result<(), rusqlite::Error> {
+        match text {
+            Some(text) => self.conn.execute(
+                r#"INSERT INTO alert_text_overrides (rule_id, why_it_matters, agent_fix_suggestion) VALUES (?1, ?2, ?3)
+                   ON CONFLICT(rule_id) DO UPDATE SET why_it_matters = ?2, agent_fix_suggestion = ?3,
+                   updated_at = CURRENT_TIMESTAMP"#,
+                params![rule_id, text.why_it_matters, text.agent_fix_suggestion],
+            )?,
+            None => self.conn.execute("DELETE FROM alert_text_overrides WHERE rule_id = ?1", params![rule_id])?,
+        };
+        Ok(())
+    }
+    
+    pub fn get_alert_text_overrides(&self) -> Result<HashMap<String, AlertTextOverride>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare("SELECT rule_id, why_it_matters, agent_fix_suggestion FROM alert_text_overrides")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, AlertTextOverride {
+                why_it_matters: row.get(1)?,
+                agent_fix_suggestion: row.get(2)?,
+            }))
+        })?;
+        rows.collect()
+    }
+    
+    /// Replace all rule overrides, script allowlists, alert wording overrides
+    /// and stored settings, and retain the ruleset, in one transaction. Used
+    /// when importing a configuration snapshot, so a failure writes nothing.
+    pub fn replace_config(
+        &self,
+        rule_overrides: &[(String, bool)],
+        script_allowlists: &HashMap<String, Vec<String>>,
+        alert_text_overrides: &HashMap<String, AlertTextOverride>,
+        settings: &[(&str, String)],
+        ruleset: &RuleSet,
+    ) -> Result<(), rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM rule_overrides", [])?;
+        for (rule_id, enabled) in rule_overrides {
+            tx.execute(
+                "INSERT OR REPLACE INTO rule_overrides (rule_id, enabled) VALUES (?1, ?2)",
+                params![rule_id, *enabled as i32],
+            )?;
+        }
+        tx.execute("DELETE FROM script_allowlist", [])?;
+        for (campaign_id, phrases) in script_allowlists {
+            for phrase in phrases {
+                tx.execute(
+                    "INSERT OR IGNORE INTO script_allowlist (campaign_id, phrase) VALUES (?1, ?2)",
+                    params![campaign_id, phrase],
+                )?;
+            }
+        }
+        tx.execute("DELETE FROM alert_text_overrides", [])?;
+        for (rule_id, text) in alert_text_overrides {
+            tx.execute(
+                "INSERT INTO alert_text_overrides (rule_id, why_it_matters, agent_fix_suggestion) VALUES (?1, ?2, ?3)",
+                params![rule_id, text.why_it_matters, text.agent_fix_suggestion],
+            )?;
+        }
+        for (key, value) in settings {
+            self.set_setting(key, value)?;
+        }
+        self.retain_ruleset(ruleset)?;
+        tx.commit()
+    }
+    
+    /// Replace the imported penalty table
+    pub fn set_penalty_table(&self, table: &HashMap<String, PenaltyRange>) -> Result<(), rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute("DELETE FROM penalty_table", [])?;
+        for (rule_id, range) in table {
+            tx.execute(
+                "INSERT INTO penalty_table (rule_id, min_amount, max_amount) VALUES (?1, ?2, ?3)",
+                params![rule_id, range.min, range.max],
+            )?;
+        }
+        tx.commit()
+    }
+    
+    /// The imported penalty table, keyed by rule
+    pub fn get_penalty_table(&self) -> Result<HashMap<String, PenaltyRange>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare("SELECT rule_id, min_amount, max_amount FROM penalty_table")?;
+        let table = stmt.query_map([], |row| {
+            Ok((row.get(0)?, PenaltyRange { min: row.get(1)?, max: row.get(2)? }))
+        })?.collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(table)
+    }
+    
+    /// A persisted app setting, `None` if it was never set
+    pub fn get_setting(&self, key: &str) -> Result<Option<String>, rusqlite::Error> {
+        self.conn.query_row("SELECT value FROM settings WHERE key = ?1", params![key], |row| row.get(0))
+            .optional()
+    }
+    
+    /// Persist an app setting so it survives a restart
+    pub fn set_setting(&self, key: &str, value: &str) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "INSERT INTO settings (key, value) VALUES (?1, ?2) ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+    
+    /// Record that an agent used a suggested next line, and the rule it
+    /// addressed when known
+    pub fn record_suggestion_usage(&self, call_id: &str, suggestion_text: &str, rule_id: Option<&str>) -> Result<(), rusqlite::Error> {
+        self.conn.execute(
+            "INSERT INTO suggestion_usage (call_id, suggestion_text, rule_id) VALUES (?1, ?2, ?3)",
+            params![call_id, suggestion_text, rule_id],
+        )?;
+        self.invalidate_analytics_for_call(call_id)
+    }
+    
+    /// Record that suggested lines for these rules were shown on a call, as
+    /// (rule ID, whether an alert for the rule prompted it). Only the first
+    /// showing per rule is kept.
+    pub fn record_suggestions_shown(&self, call_id: &str, shown: &[(String, bool)]) -> Result<(), rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        for (rule_id, prompted_by_alert) in shown {
+            tx.execute(
+                "INSERT OR IGNORE INTO suggestions_shown (call_id, rule_id, prompted_by_alert) VALUES (?1, ?2, ?3)",
+                params![call_id, rule_id, prompted_by_alert],
+            )?;
+        }
+        tx.commit()
+    }
+    
+    /// Whether showing `rule_id`'s suggested line headed off the violation, over
+    /// every call it was shown on. A call counts as repeated when, after the
+    /// line was first shown, the rule fired again or one of `follow_on_ids`
+    /// (rules that depend on it, like DNC-002 on DNC-001) fired. The alert
+    /// that prompted the line doesn't count against it.
+    pub fn get_suggestion_effectiveness(&self, rule_id: &str, follow_on_ids: &[String]) -> Result<SuggestionStats, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            r#"SELECT s.call_id, s.prompted_by_alert, s.shown_at,
+                      EXISTS (SELECT 1 FROM suggestion_usage u WHERE u.call_id = s.call_id AND u.rule_id = s.rule_id)
+               FROM suggestions_shown s
+               WHERE s.rule_id = ?1"#
+        )?;
+        let shown = stmt.query_map(params![rule_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?, row.get::<_, String>(2)?, row.get::<_, bool>(3)?))
+        })?.collect::<Result<Vec<_>, _>>()?;
+        
+        let mut alert_stmt = self.conn.prepare(
+            "SELECT rule_id, created_at FROM alerts WHERE call_id = ?1"
+        )?;
+        let mut stats = SuggestionStats {
+            rule_id: rule_id.to_string(),
+            ..Default::default()
+        };
+        for (call_id, prompted_by_alert, shown_at, used) in shown {
+            let alerts = alert_stmt.query_map(params![call_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?.collect::<Result<Vec<_>, _>>()?;
+            
+            let own_alerts = if prompted_by_alert {
+                // The prompting alert may be stored after the line was shown
+                alerts.iter().filter(|(id, _)| id == rule_id).count().saturating_sub(1)
+            } else {
+                alerts.iter().filter(|(id, at)| id == rule_id && *at >= shown_at).count()
+            };
+            let follow_on = alerts.iter().any(|(id, at)| follow_on_ids.contains(id) && *at >= shown_at);
+            let repeated = own_alerts > 0 || follow_on;
+            
+            stats.calls_shown += 1;
+            if repeated {
+                stats.calls_repeated += 1;
+            } else {
+                stats.calls_avoided += 1;
+            }
+            if used {
+                stats.calls_used += 1;
+                if !repeated {
+                    stats.calls_used_avoided += 1;
+                }
+            }
+        }
+        if stats.calls_shown > 0 {
+            stats.avoidance_rate = stats.calls_avoided as f64 / stats.calls_shown as f64;
+        }
+        Ok(stats)
+    }
+    
+    /// Alert counts in the range for each of `rule_ids`, rules that never fired
+    /// first, for finding dead rules
+    pub fn get_rule_coverage(&self, start_date: &str, end_date: &str, rule_ids: &[String]) -> Result<Vec<RuleCoverage>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT rule_id, COUNT(*), MAX(created_at) FROM alerts
+             WHERE created_at >= ?1 AND created_at <= ?2
+             GROUP BY rule_id"
+        )?;
+        let fired: HashMap<String, (u32, String)> = stmt.query_map(params![start_date, end_date], |row| {
+            Ok((row.get(0)?, (row.get(1)?, row.get(2)?)))
+        })?.collect::<Result<_, _>>()?;
+        
+        let mut coverage: Vec<RuleCoverage> = rule_ids.iter()
+            .map(|rule_id| {
+                let (count, last_fired) = match fired.get(rule_id) {
+                    Some((count, last)) => (*count, Some(last.clone())),
+                    None => (0, None),
+                };
+                RuleCoverage {
+                    rule_id: rule_id.clone(),
+                    count,
+                    last_fired,
+                }
+            })
+            .collect();
+        coverage.sort_by(|a, b| a.count.cmp(&b.count).then_with(|| a.rule_id.cmp(&b.rule_id)));
+        Ok(coverage)
+    }
+    
+    /// Most frequent evidence quotes for a rule in the range, normalized so the
+    /// same phrasing in different case or spacing counts once
+    pub fn top_quotes_for_rule(
+        &self,
+        rule_id: &str,
+        limit: u32,
+        start_date: &str,
+        end_date: &str,
+    ) -> Result<Vec<QuoteCount>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT quote FROM alerts WHERE rule_id = ?1 AND created_at >= ?2 AND created_at <= ?3"
+        )?;
+        let quotes = stmt.query_map(params![rule_id, start_date, end_date], |row| row.get::<_, String>(0))?;
+        
+        let mut counts: HashMap<String, u32> = HashMap::new();
+        for quote in quotes {
+            let normalized = quote?.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+            if !normalized.is_empty() {
+                *counts.entry(normalized).or_default() += 1;
+            }
+        }
+        
+        let mut top: Vec<QuoteCount> = counts.into_iter()
+            .map(|(quote, count)| QuoteCount { quote, count })
+            .collect();
+        top.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.quote.cmp(&b.quote)));
+        top.truncate(limit as usize);
+        Ok(top)
+    }
+    
+    /// Place or release a legal hold on a call. Returns `false` if the call doesn't exist.
+    pub fn set_legal_hold(&self, call_id: &str, on: bool) -> Result<bool, rusqlite::Error> {
+        let updated = self.conn.execute(
+            "UPDATE calls SET legal_hold = ?2 WHERE call_id = ?1",
+            params![call_id, on as i32],
+        )?;
+        Ok(updated > 0)
+    }
+    
+    /// All calls currently under legal hold, newest first
+    pub fn get_legal_holds(&self) -> Result<Vec<LegalHold>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            r#"SELECT c.call_id, c.agent_id, c.agent_name, c.call_start_time, c.call_end_time,
+                (SELECT COUNT(*) FROM alerts a WHERE a.call_id = c.call_id)
+               FROM calls c WHERE c.legal_hold = 1
+               ORDER BY c.created_at DESC"#
+        )?;
+        let holds = stmt.query_map([], |row| {
+            Ok(LegalHold {
+                call_id: row.get(0)?,
+                agent_id: row.get(1)?,
+                agent_name: row.get(2)?,
+                call_start_time: row.get(3)?,
+                call_end_time: row.get(4)?,
+                alert_count: row.get(5)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(holds)
+    }
+    
+    /// Delete alerts created more than `days` days ago, except those on calls
+    /// under legal hold. Returns the number of alerts deleted.
+    pub fn delete_alerts_older_than(&self, days: u32) -> Result<u32, rusqlite::Error> {
+        let deleted = self.conn.execute(
+            r#"DELETE FROM alerts
+               WHERE created_at < datetime('now', '-' || ?1 || ' days')
+                 AND call_id NOT IN (SELECT call_id FROM calls WHERE legal_hold = 1)"#,
+            params![days],
+        )?;
+        if deleted > 0 {
+            self.conn.execute("DELETE FROM alert_tags WHERE alert_id NOT IN (SELECT id FROM alerts)", [])?;
+            self.analytics_cache.lock().unwrap().clear();
+        }
+        Ok(deleted as u32)
+    }
+    
+    /// Retention purge: remove alerts, calls and suggestion usage older than
+    /// `days` days. Calls under legal hold and their alerts are always kept.
+    pub fn purge_old_data(&self, days: u32) -> Result<PurgeSummary, rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        
+        let held_calls_skipped: u32 = tx.query_row(
+            r#"SELECT COUNT(*) FROM calls
+               WHERE legal_hold = 1 AND created_at < datetime('now', '-' || ?1 || ' days')"#,
+            params![days],
+            |row| row.get(0),
+        )?;
+        
+        let alerts_deleted = tx.execute(
+            r#"DELETE FROM alerts
+               WHERE created_at < datetime('now', '-' || ?1 || ' days')
+                 AND call_id NOT IN (SELECT call_id FROM calls WHERE legal_hold = 1)"#,
+            params![days],
+        )?;
+        tx.execute("DELETE FROM alert_tags WHERE alert_id NOT IN (SELECT id FROM alerts)", [])?;
+        
+        // Only drop a call once none of its alerts are left, so a long call that
+        // straddles the cutoff keeps its metadata
+        let stale_calls = r#"SELECT call_id FROM calls
+               WHERE legal_hold = 0 AND created_at < datetime('now', '-' || ?1 || ' days')
+                 AND call_id NOT IN (SELECT call_id FROM alerts)"#;
+        tx.execute(
+            &format!("DELETE FROM suggestion_usage WHERE call_id IN ({})", stale_calls),
+            params![days],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM suggestions_shown WHERE call_id IN ({})", stale_calls),
+            params![days],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM call_milestones WHERE call_id IN ({})", stale_calls),
+            params![days],
+        )?;
+        let calls_deleted = tx.execute(
+            &format!("DELETE FROM calls WHERE call_id IN ({})", stale_calls),
+            params![days],
+        )?;
+        
+        tx.commit()?;
+        self.analytics_cache.lock().unwrap().clear();
+        Ok(PurgeSummary {
+            alerts_deleted: alerts_deleted as u32,
+            calls_deleted: calls_deleted as u32,
+            held_calls_skipped,
+        })
+    }
+    
+    /// Right-to-delete: remove every call to `phone` (normalized as by
+    /// `dnc::normalize_phone`) along with its alerts, tags, milestones and
+    /// suggestion usage. Calls under legal hold are kept and listed instead.
+    /// Numbers stored in plain text and hashed are both matched.
+    pub fn delete_consumer_data(&self, phone: &str) -> Result<DeletionReport, rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        let hashed = self.hash_phone(phone);
+        
+        let held_call_ids = {
+            let mut stmt = tx.prepare(
+                "SELECT call_id FROM calls WHERE customer_phone IN (?1, ?2) AND legal_hold = 1 ORDER BY created_at"
+            )?;
+            let ids = stmt.query_map(params![phone, hashed], |row| row.get(0))?.collect::<Result<Vec<String>, _>>()?;
+            ids
+        };
+        
+        let consumer_calls = "SELECT call_id FROM calls WHERE customer_phone IN (?1, ?2) AND legal_hold = 0";
+        let consumer_alerts = format!("SELECT id FROM alerts WHERE call_id IN ({})", consumer_calls);
+        tx.execute(
+            &format!("DELETE FROM alert_tags WHERE alert_id IN ({})", consumer_alerts),
+            params![phone, hashed],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM idempotency_keys WHERE alert_id IN ({})", consumer_alerts),
+            params![phone, hashed],
+        )?;
+        let alerts_deleted = tx.execute(
+            &format!("DELETE FROM alerts WHERE call_id IN ({})", consumer_calls),
+            params![phone, hashed],
+        )?;
+        let milestones_deleted = tx.execute(
+            &format!("DELETE FROM call_milestones WHERE call_id IN ({})", consumer_calls),
+            params![phone, hashed],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM suggestion_usage WHERE call_id IN ({})", consumer_calls),
+            params![phone, hashed],
+        )?;
+        tx.execute(
+            &format!("DELETE FROM suggestions_shown WHERE call_id IN ({})", consumer_calls),
+            params![phone, hashed],
+        )?;
+        let calls_deleted = tx.execute(
+            "DELETE FROM calls WHERE customer_phone IN (?1, ?2) AND legal_hold = 0",
+            params![phone, hashed],
+        )?;
+        
+        tx.commit()?;
+        self.analytics_cache.lock().unwrap().clear();
+        Ok(DeletionReport {
+            calls_deleted: calls_deleted as u32,
+            alerts_deleted: alerts_deleted as u32,
+            milestones_deleted: milestones_deleted as u32,
+            held_call_ids,
+        })
+    }
+    
+    /// The customer's number as stored on the call row: normalized to 10 digits
+    /// when it's a valid US number, so deletion requests match however it was
+    /// typed, and hashed when `hash_phone` is set
+    fn stored_phone(&self, metadata: &CallMetadata, hash_phone: bool) -> Option<String> {
+        let raw = metadata.customer_phone.as_deref()?;
+        let phone = crate::dnc::normalize_phone(raw).unwrap_or_else(|| raw.trim().to_string());
+        Some(if hash_phone { self.hash_phone(&phone) } else { phone })
+    }
+    
+    /// Keyed SHA-256 of a normalized phone number, hex-encoded with a prefix so
+    /// hashed and plain-text numbers can be told apart
+    fn hash_phone(&self, phone: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.phone_hash_key.as_bytes());
+        hasher.update(phone.as_bytes());
+        let hex: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+        format!("{}{}", PHONE_HASH_PREFIX, hex)
+    }
+    
+    /// Label a stored alert. Returns `false` if the alert doesn't exist; tagging
+    /// an alert twice with the same tag is a no-op.
+    pub fn add_alert_tag(&self, alert_id: &str, tag: &str) -> Result<bool, rusqlite::Error> {
+        let exists: bool = self.conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM alerts WHERE id = ?1)",
+            params![alert_id],
+            |row| row.get(0),
+        )?;
+        if exists {
+            self.conn.execute(
+                "INSERT OR IGNORE INTO alert_tags (alert_id, tag) VALUES (?1, ?2)",
+                params![alert_id, tag],
+            )?;
+        }
+        Ok(exists)
+    }
+    
+    /// Stamp an alert as acknowledged, with millisecond precision since SLAs are
+    /// seconds. A repeat acknowledgment keeps the first time. Returns `false`
+    /// if the alert doesn't exist.
+    pub fn acknowledge_alert(&self, alert_id: &str) -> Result<bool, rusqlite::Error> {
+        let updated = self.conn.execute(
+            "UPDATE alerts SET acknowledged_at = COALESCE(acknowledged_at, strftime('%Y-%m-%d %H:%M:%f', 'now'))
+             WHERE id = ?1",
+            params![alert_id],
+        )?;
+        Ok(updated > 0)
+    }
+    
+    /// Acknowledge every unacknowledged alert matching the filter in one
+    /// transaction, stamped like `acknowledge_alert`. Returns how many were
+    /// newly acknowledged.
+    pub fn acknowledge_alerts(&self, filter: &AlertFilter) -> Result<u32, rusqlite::Error> {
+        let mut query = "UPDATE alerts SET acknowledged_at = strftime('%Y-%m-%d %H:%M:%f', 'now') \
+                         WHERE acknowledged_at IS NULL".to_string();
+        let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+        
+        if let Some(ref cid) = filter.call_id {
+            query.push_str(" AND call_id = ?");
+            params_vec.push(Box::new(cid.clone()));
+        }
+        if let Some(ref aid) = filter.agent_id {
+            query.push_str(" AND agent_id = ?");
+            params_vec.push(Box::new(aid.clone()));
+        }
+        
+        let tx = self.conn.unchecked_transaction()?;
+        let mut acknowledged = 0;
+        match filter.alert_ids {
+            Some(ref ids) => {
+                query.push_str(" AND id = ?");
+                let mut stmt = tx.prepare(&query)?;
+                for id in ids {
+                    let mut params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+                    params_refs.push(id);
+                    acknowledged += stmt.execute(params_refs.as_slice())?;
+                }
+            }
+            None => {
+                let params_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter().map(|p| p.as_ref()).collect();
+                acknowledged += tx.execute(&query, params_refs.as_slice())?;
+            }
+        }
+        tx.commit()?;
+        Ok(acknowledged as u32)
+    }
+    
+    /// Per agent and severity, how many alerts in the range were acknowledged
+    /// and how many within the SLA for their severity
+    pub fn get_ack_sla_report(&self, start_date: &str, end_date: &str, sla: &AckSla) -> Result<Vec<AckSlaRow>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            r#"SELECT agent_id, agent_name, severity, sla, COUNT(*), COUNT(ack_secs),
+                SUM(CASE WHEN ack_secs <= sla THEN 1 ELSE 0 END), AVG(ack_secs)
+               FROM (
+                   SELECT agent_id, agent_name, severity,
+                       CASE severity WHEN 'high' THEN ?3 WHEN 'medium' THEN ?4 ELSE ?5 END AS sla,
+                       (julianday(acknowledged_at) - julianday(created_at)) * 86400 AS ack_secs
+                   FROM alerts
+                   WHERE created_at >= ?1 AND created_at <= ?2
+               )
+               GROUP BY agent_id, severity
+               ORDER BY agent_id, CASE severity WHEN 'high' THEN 0 WHEN 'medium' THEN 1 ELSE 2 END"#
+        )?;
+        let rows = stmt.query_map(
+            params![start_date, end_date, sla.high_secs, sla.medium_secs, sla.low_secs],
+            |row| {
+                let alerts: u32 = row.get(4)?;
+                let within_sla: u32 = row.get(6)?;
+                Ok(AckSlaRow {
+                    agent_id: row.get(0)?,
+                    agent_name: row.get(1)?,
+                    severity: row.get(2)?,
+                    sla_secs: row.get(3)?,
+                    alerts,
+                    acknowledged: row.get(5)?,
+                    within_sla,
+                    within_sla_pct: within_sla as f64 * 100.0 / alerts as f64,
+                    avg_ack_secs: row.get(7)?,
+                })
+            },
+        )?.collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+    
+    /// Remove a label from an alert. Returns `false` if the alert didn't have it.
+    pub fn remove_alert_tag(&self, alert_id: &str, tag: &str) -> Result<bool, rusqlite::Error> {
+        let removed = self.conn.execute(
+            "DELETE FROM alert_tags WHERE alert_id = ?1 AND tag = ?2",
+            params![alert_id, tag],
+        )?;
+        Ok(removed > 0)
+    }
+    
+    /// Every tag in use with how many alerts carry it, most used first
+    pub fn get_tag_counts(&self) -> Result<Vec<TagCount>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT tag, COUNT(*) as count FROM alert_tags GROUP BY tag ORDER BY count DESC, tag"
+        )?;
+        let counts = stmt.query_map([], |row| {
+            Ok(TagCount {
+                tag: row.get(0)?,
+                count: row.get(1)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        Ok(counts)
+    }
+    
+    /// Every call started in the range with its alerts, including calls that
+    /// raised none, oldest first
+    pub fn get_call_alert_summaries(&self, start_date: &str, end_date: &str) -> Result<Vec<CallAlertSummary>, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT call_id, agent_id, agent_name, call_start_time FROM calls
+             WHERE created_at >= ?1 AND created_at <= ?2 ORDER BY created_at, call_id"
+        )?;
+        let mut calls: Vec<CallAlertSummary> = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(CallAlertSummary {
+                call_id: row.get(0)?,
+                agent_id: row.get(1)?,
+                agent_name: row.get(2)?,
+                call_start_time: row.get(3)?,
+                alerts: Vec::new(),
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        
+        let mut stmt = self.conn.prepare(
+            "SELECT a.call_id, a.rule_id, a.severity FROM alerts a JOIN calls c ON c.call_id = a.call_id
+             WHERE c.created_at >= ?1 AND c.created_at <= ?2"
+        )?;
+        let mut alerts_by_call: HashMap<String, Vec<(String, String)>> = HashMap::new();
+        let rows = stmt.query_map(params![start_date, end_date], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })?;
+        for row in rows {
+            let (call_id, rule_id, severity) = row?;
+            alerts_by_call.entry(call_id).or_default().push((rule_id, severity));
+        }
+        for call in &mut calls {
+            call.alerts = alerts_by_call.remove(&call.call_id).unwrap_or_default();
+        }
+        Ok(calls)
+    }
+    
+    /// Store the disclosures heard during a call. Re-recording a disclosure keeps
+    /// the first one.
+    pub fn insert_milestones(&self, call_id: &str, milestones: &[DisclosureMilestone]) -> Result<(), rusqlite::Error> {
+        let tx = self.conn.unchecked_transaction()?;
+        for m in milestones {
+            tx.execute(
+                r#"INSERT OR IGNORE INTO call_milestones (call_id, rule_id, label, quote, start_char, end_char, timestamp_ms)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
+                params![call_id, m.rule_id, m.label, m.quote, m.start_char, m.end_char, m.timestamp_ms],
+            )?;
+        }
+        tx.commit()
+    }
+    
+    /// A call's alerts and disclosures in transcript order, between call start
+    /// and end anchors. Alerts with no transcript span go right after the start
+    /// if they come from `metadata_rule_ids`, otherwise right before the end
+    /// (end-of-call checks). Returns `None` if the call doesn't exist.
+    pub fn get_call_timeline(
+        &self,
+        call_id: &str,
+        metadata_rule_ids: &[String],
+    ) -> Result<Option<Vec<TimelineEvent>>, rusqlite::Error> {
+        let call: Option<(String, Option<String>)> = self.conn.query_row(
+            "SELECT call_start_time, call_end_time FROM calls WHERE call_id = ?1",
+            params![call_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?;
+        let Some((call_start_time, call_end_time)) = call else {
+            return Ok(None);
+        };
+        
+        // (group, start_char, timestamp_ms) orders events within the call
+        let mut events: Vec<((u8, usize, u64), TimelineEvent)> = Vec::new();
+        
+        let mut stmt = self.conn.prepare(
+            r#"SELECT id, rule_id, title, severity, quote, start_char, end_char, timestamp_ms, created_at
+               FROM alerts WHERE call_id = ?1"#,
+        )?;
+        let alerts = stmt.query_map(params![call_id], |row| {
+            let rule_id: String = row.get(1)?;
+            let (start_char, end_char): (usize, usize) = (row.get(5)?, row.get(6)?);
+            let timestamp_ms: Option<u64> = row.get(7)?;
+            let group = if end_char > start_char {
+                1
+            } else if metadata_rule_ids.contains(&rule_id) {
+                0
+            } else {
+                2
+            };
+            let event = TimelineEvent {
+                kind: "alert".to_string(),
+                title: row.get(2)?,
+                rule_id: Some(rule_id),
+                alert_id: row.get(0)?,
+                severity: row.get(3)?,
+                quote: row.get(4)?,
+                start_char: (end_char > start_char).then_some(start_char),
+                timestamp_ms,
+                at: row.get(8)?,
+            };
+            Ok(((group, start_char, timestamp_ms.unwrap_or_default()), event))
+        })?;
+        for alert in alerts {
+            events.push(alert?);
+        }
+        
+        let mut stmt = self.conn.prepare(
+            "SELECT rule_id, label, quote, start_char, timestamp_ms FROM call_milestones WHERE call_id = ?1",
+        )?;
+        let milestones = stmt.query_map(params![call_id], |row| {
+            let start_char: usize = row.get(3)?;
+            let timestamp_ms: Option<u64> = row.get(4)?;
+            let event = TimelineEvent {
+                kind: "disclosure".to_string(),
+                title: row.get(1)?,
+                rule_id: row.get(0)?,
+                alert_id: None,
+                severity: None,
+                quote: row.get(2)?,
+                start_char: Some(start_char),
+                timestamp_ms,
+                at: None,
+            };
+            Ok(((1, start_char, timestamp_ms.unwrap_or_default()), event))
+        })?;
+        for milestone in milestones {
+            events.push(milestone?);
+        }
+        events.sort_by_key(|(key, _)| *key);
+        
+        let anchor = |kind: &str, title: &str, at: String| TimelineEvent {
+            kind: kind.to_string(),
+            title: title.to_string(),
+            rule_id: None,
+            alert_id: None,
+            severity: None,
+            quote: None,
+            start_char: None,
+            timestamp_ms: None,
+            at: Some(at),
+        };
+        let mut timeline = vec![anchor("call_start", "Call started", call_start_time)];
+        timeline.extend(events.into_iter().map(|(_, event)| event));
+        if let Some(end) = call_end_time {
+            timeline.push(anchor("call_end", "Call ended", end));
+        }
+        Ok(Some(timeline))
+    }
+    
+    /// Audio position of an alert: `None` if the alert doesn't exist,
+    /// `Some(None)` if it was stored without segment timing
+    pub fn get_alert_timestamp(&self, alert_id: &str) -> Result<Option<Option<u64>>, rusqlite::Error> {
+        self.conn.query_row(
+            "SELECT timestamp_ms FROM alerts WHERE id = ?1",
+            params![alert_id],
+            |row| row.get(0),
+        ).optional()
+    }
+    
+    /// Analytics for a date range, served from the cache unless `refresh` is set.
+    /// Ranges that end in the past only change when old data is purged, so they
+    /// stay cached; ranges touching now are dropped whenever a call, alert or
+    /// suggestion use is recorded.
+    pub fn get_analytics(&self, start_date: &str, end_date: &str, refresh: bool) -> Result<AnalyticsData, rusqlite::Error> {
+        self.get_analytics_with_progress(start_date, end_date, refresh, |_| {})
+    }
+    
+    /// `get_analytics`, calling `progress` as each section finishes. A cached
+    /// result is reported once, as every section complete.
+    pub fn get_analytics_with_progress(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        refresh: bool,
+        mut progress: impl FnMut(AnalyticsProgress),
+    ) -> Result<AnalyticsData, rusqlite::Error> {
+        let key = (start_date.to_string(), end_date.to_string());
+        if !refresh {
+            if let Some(cached) = self.analytics_cache.lock().unwrap().get(&key) {
+                progress(AnalyticsProgress {
+                    section: "cached".to_string(),
+                    completed: ANALYTICS_SECTIONS.len() as u32,
+                    total: ANALYTICS_SECTIONS.len() as u32,
+                    partial: cached.clone(),
+                });
+                return Ok(cached.clone());
+            }
+        }
+        
+        let analytics = self.compute_analytics(start_date, end_date, &mut progress)?;
+        let mut cache = self.analytics_cache.lock().unwrap();
+        if cache.len() >= ANALYTICS_CACHE_CAPACITY && !cache.contains_key(&key) {
+            cache.clear();
+        }
+        cache.insert(key, analytics.clone());
+        Ok(analytics)
+    }
+    
+    /// Drop cached ranges that a write to this call could change: any range
+    /// ending at or after the call started. Stats such as call duration and
+    /// suggestion follow-up are attributed to the call's start, so a write now
+    /// can still affect a range that ended a few minutes ago.
+    fn invalidate_analytics_for_call(&self, call_id: &str) -> Result<(), rusqlite::Error> {
+        let mut cache = self.analytics_cache.lock().unwrap();
+        if cache.is_empty() {
+            return Ok(());
+        }
+        let since: String = self.conn.query_row(
+            "SELECT COALESCE((SELECT MIN(created_at) FROM calls WHERE call_id = ?1), CURRENT_TIMESTAMP)",
+            params![call_id],
+            |row| row.get(0),
+        )?;
+        cache.retain(|(_, end_date), _| end_date.as_str() < since.as_str());
+        Ok(())
+    }
+    
+    /// Days in the range whose alert count exceeds the mean of the preceding
+    /// `ANOMALY_TRAILING_DAYS` by more than `ANOMALY_STD_DEVS` standard
+    /// deviations. Days with no alerts count as zero; days before the first
+    /// alert on record don't count as history.
+    pub fn get_anomalies(&self, start_date: &str, end_date: &str) -> Result<Vec<AnomalyDay>, rusqlite::Error> {
+        let (history_start, first_day, last_day): (String, Option<String>, Option<String>) = self.conn.query_row(
+            "SELECT datetime(?1, ?3), DATE(?1), DATE(?2)",
+            params![start_date, end_date, format!("-{} days", ANOMALY_TRAILING_DAYS)],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        )?;
+        let parse = |date: Option<String>| date.and_then(|d| chrono::NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok());
+        let (Some(first_day), Some(last_day)) = (parse(first_day), parse(last_day)) else {
+            return Ok(Vec::new());
+        };
+        
+        let mut stmt = self.conn.prepare(
+            "SELECT DATE(created_at), COUNT(*) FROM alerts 
+             WHERE created_at >= ?1 AND created_at <= ?2 
+             GROUP BY DATE(created_at)"
+        )?;
+        let counts: HashMap<String, u32> = stmt.query_map(params![history_start, end_date], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?.collect::<Result<_, _>>()?;
+        let Some(earliest) = counts.keys().min().cloned() else {
+            return Ok(Vec::new());
+        };
+        
+        let count_on = |day: chrono::NaiveDate| counts.get(&day.format("%Y-%m-%d").to_string()).copied().unwrap_or(0);
+        let mut anomalies = Vec::new();
+        for day in first_day.iter_days().take_while(|d| *d <= last_day) {
+            let trailing: Vec<f64> = (1..=ANOMALY_TRAILING_DAYS)
+                .map(|back| day - chrono::Duration::days(back))
+                .filter(|d| d.format("%Y-%m-%d").to_string() >= earliest)
+                .map(|d| count_on(d) as f64)
+                .collect();
+            if trailing.len() < ANOMALY_MIN_HISTORY_DAYS {
+                continue;
+            }
+            
+            let n = trailing.len() as f64;
+            let mean = trailing.iter().sum::<f64>() / n;
+            let std_dev = (trailing.iter().map(|c| (c - mean).powi(2)).sum::<f64>() / n).sqrt();
+            let count = count_on(day);
+            let z_score = (count as f64 - mean) / std_dev.max(ANOMALY_MIN_STD_DEV);
+            if z_score > ANOMALY_STD_DEVS {
+                anomalies.push(AnomalyDay {
+                    date: day.format("%Y-%m-%d").to_string(),
+                    count,
+                    trailing_mean: mean,
+                    trailing_std_dev: std_dev,
+                    z_score,
+                });
+            }
+        }
+        Ok(anomalies)
+    }
+    
+    fn compute_analytics(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        progress: &mut dyn FnMut(AnalyticsProgress),
+    ) -> Result<AnalyticsData, rusqlite::Error> {
+        let mut analytics = AnalyticsData::default();
+        let mut completed = 0;
+        let mut report = |section: &str, analytics: &AnalyticsData| {
+            completed += 1;
+            progress(AnalyticsProgress {
+                section: section.to_string(),
+                completed,
+                total: ANALYTICS_SECTIONS.len() as u32,
+                partial: analytics.clone(),
+            });
+        };
+        
+        // Total calls
+        analytics.total_calls = self.conn.query_row(
+            "SELECT COUNT(*) FROM calls WHERE created_at >= ?1 AND created_at <= ?2",
+            params![start_date, end_date],
+            |row| row.get(0),
+        )?;
+        
+        // Total alerts
+        analytics.total_alerts = self.conn.query_row(
+            "SELECT COUNT(*) FROM alerts WHERE created_at >= ?1 AND created_at <= ?2",
             params![start_date, end_date],
             |row| row.get(0),
         )?;
@@ -268,6 +2002,8 @@ impl Database {
             params![start_date, end_date],
             |row| row.get(0),
         ).unwrap_or(0);
+        analytics.alerts_by_severity = AlertsBySeverity { high, medium, low };
+        report("totals", &analytics);
         
         // Alerts by rule
         let mut stmt = self.conn.prepare(
@@ -275,12 +2011,13 @@ impl Database {
              WHERE created_at >= ?1 AND created_at <= ?2 
              GROUP BY rule_id ORDER BY count DESC"
         )?;
-        let alerts_by_rule: Vec<RuleAlertCount> = stmt.query_map(params![start_date, end_date], |row| {
+        analytics.alerts_by_rule = stmt.query_map(params![start_date, end_date], |row| {
             Ok(RuleAlertCount {
                 rule_id: row.get(0)?,
                 count: row.get(1)?,
             })
         })?.collect::<Result<Vec<_>, _>>()?;
+        report("rules", &analytics);
         
         // Alerts by agent
         let mut stmt = self.conn.prepare(
@@ -288,13 +2025,14 @@ impl Database {
              WHERE created_at >= ?1 AND created_at <= ?2 
              GROUP BY agent_id ORDER BY count DESC"
         )?;
-        let alerts_by_agent: Vec<AgentAlertCount> = stmt.query_map(params![start_date, end_date], |row| {
+        analytics.alerts_by_agent = stmt.query_map(params![start_date, end_date], |row| {
             Ok(AgentAlertCount {
                 agent_id: row.get(0)?,
                 agent_name: row.get(1)?,
                 count: row.get(2)?,
             })
         })?.collect::<Result<Vec<_>, _>>()?;
+        report("agents", &analytics);
         
         // Daily trend
         let mut stmt = self.conn.prepare(
@@ -302,20 +2040,283 @@ impl Database {
              WHERE created_at >= ?1 AND created_at <= ?2 
              GROUP BY DATE(created_at) ORDER BY date"
         )?;
-        let daily_trend: Vec<DailyAlertCount> = stmt.query_map(params![start_date, end_date], |row| {
+        analytics.daily_trend = stmt.query_map(params![start_date, end_date], |row| {
             Ok(DailyAlertCount {
                 date: row.get(0)?,
                 count: row.get(1)?,
             })
         })?.collect::<Result<Vec<_>, _>>()?;
+        report("daily", &analytics);
+        
+        // Agent baselines: window rate vs the trailing period before the window
+        let baseline_start: String = self.conn.query_row(
+            "SELECT datetime(?1, ?2)",
+            params![start_date, format!("-{} days", BASELINE_DAYS)],
+            |row| row.get(0),
+        )?;
+        let window_rates = self.agent_alert_rates(start_date, end_date, true)?;
+        let baseline_rates = self.agent_alert_rates(&baseline_start, start_date, false)?;
+        
+        let mut agent_baselines: Vec<AgentBaselineComparison> = window_rates.into_iter()
+            .map(|(agent_id, (agent_name, window_alert_rate))| {
+                let baseline_alert_rate = baseline_rates.get(&agent_id).map(|(_, rate)| *rate);
+                let delta = baseline_alert_rate.map(|b| window_alert_rate - b);
+                let direction = match delta {
+                    None => "no_baseline",
+                    Some(d) if d < -BASELINE_TOLERANCE => "improving",
+                    Some(d) if d > BASELINE_TOLERANCE => "regressing",
+                    Some(_) => "steady",
+                }.to_string();
+                AgentBaselineComparison {
+                    agent_id,
+                    agent_name,
+                    window_alert_rate,
+                    baseline_alert_rate,
+                    delta,
+                    direction,
+                }
+            })
+            .collect();
+        agent_baselines.sort_by(|a, b| a.agent_id.cmp(&b.agent_id));
+        analytics.agent_baselines = agent_baselines;
+        report("baselines", &analytics);
+        
+        // Suggestion usage, with alerts raised on the same call after each use
+        let mut stmt = self.conn.prepare(
+            "SELECT s.suggestion_text, COUNT(*) AS uses, COUNT(DISTINCT s.call_id) AS calls,
+                SUM((SELECT COUNT(*) FROM alerts a
+                     WHERE a.call_id = s.call_id AND a.created_at > s.created_at)) AS alerts_after
+             FROM suggestion_usage s
+             WHERE s.created_at >= ?1 AND s.created_at <= ?2
+             GROUP BY s.suggestion_text ORDER BY uses DESC"
+        )?;
+        analytics.suggestion_usage = stmt.query_map(params![start_date, end_date], |row| {
+            let uses: u32 = row.get(1)?;
+            let alerts_after: u32 = row.get(3)?;
+            Ok(SuggestionUsageCount {
+                suggestion_text: row.get(0)?,
+                uses,
+                calls: row.get(2)?,
+                alerts_after_per_use: alerts_after as f64 / uses as f64,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        
+        report("suggestions", &analytics);
+        
+        analytics.call_durations = self.call_duration_stats(start_date, end_date)?;
+        report("durations", &analytics);
+        
+        // Repeat violations escalated at store time
+        let mut stmt = self.conn.prepare(
+            "SELECT agent_id, agent_name, rule_id, COUNT(*) as count FROM alerts 
+             WHERE escalated_from IS NOT NULL AND created_at >= ?1 AND created_at <= ?2 
+             GROUP BY agent_id, rule_id ORDER BY count DESC"
+        )?;
+        analytics.escalations = stmt.query_map(params![start_date, end_date], |row| {
+            Ok(EscalationCount {
+                agent_id: row.get(0)?,
+                agent_name: row.get(1)?,
+                rule_id: row.get(2)?,
+                count: row.get(3)?,
+            })
+        })?.collect::<Result<Vec<_>, _>>()?;
+        
+        report("escalations", &analytics);
+        
+        analytics.talk_time = self.talk_time_stats(start_date, end_date)?;
+        report("talk_time", &analytics);
+        
+        Ok(analytics)
+    }
+    
+    /// Agents' share of talk time for calls started in the range, overall and
+    /// per agent. Calls with no attributed speech are left out.
+    fn talk_time_stats(&self, start_date: &str, end_date: &str) -> Result<TalkTimeStats, rusqlite::Error> {
+        let mut stmt = self.conn.prepare(
+            "SELECT agent_id, agent_name, agent_talk_ms, customer_talk_ms FROM calls
+             WHERE agent_talk_ms IS NOT NULL AND agent_talk_ms + customer_talk_ms > 0
+               AND created_at >= ?1 AND created_at <= ?2
+             ORDER BY agent_id"
+        )?;
+        let calls: Vec<(String, String, f64)> = stmt.query_map(params![start_date, end_date], |row| {
+            let (agent_ms, customer_ms): (u64, u64) = (row.get(2)?, row.get(3)?);
+            Ok((row.get(0)?, row.get(1)?, agent_ms as f64 / (agent_ms + customer_ms) as f64))
+        })?.collect::<Result<Vec<_>, _>>()?;
         
-        Ok(AnalyticsData {
-            total_calls,
-            total_alerts,
-            alerts_by_severity: AlertsBySeverity { high, medium, low },
-            alerts_by_rule,
-            alerts_by_agent,
-            daily_trend,
+        let mut by_agent: Vec<AgentTalkTime> = Vec::new();
+        for (agent_id, agent_name, ratio) in &calls {
+            if by_agent.last().map_or(true, |a| &a.agent_id != agent_id) {
+                by_agent.push(AgentTalkTime {
+                    agent_id: agent_id.clone(),
+                    agent_name: agent_name.clone(),
+                    calls: 0,
+                    avg_agent_ratio: 0.0,
+                    agent_dominant_calls: 0,
+                });
+            }
+            let agent = by_agent.last_mut().unwrap();
+            agent.calls += 1;
+            agent.avg_agent_ratio += ratio;
+            agent.agent_dominant_calls += (*ratio >= AGENT_DOMINANT_RATIO) as u32;
+        }
+        for agent in &mut by_agent {
+            agent.avg_agent_ratio /= agent.calls as f64;
+        }
+        
+        Ok(TalkTimeStats {
+            calls: calls.len() as u32,
+            avg_agent_ratio: (!calls.is_empty())
+                .then(|| calls.iter().map(|(_, _, ratio)| ratio).sum::<f64>() / calls.len() as f64),
+            agent_dominant_calls: calls.iter().filter(|(_, _, ratio)| *ratio >= AGENT_DOMINANT_RATIO).count() as u32,
+            by_agent,
+        })
+    }
+    
+    /// Duration percentiles and alert rates by call length for calls started in
+    /// the range. Calls without a duration are counted as open, never as zero.
+    fn call_duration_stats(&self, start_date: &str, end_date: &str) -> Result<CallDurationStats, rusqlite::Error> {
+        let open_calls: u32 = self.conn.query_row(
+            "SELECT COUNT(*) FROM calls WHERE duration_seconds IS NULL AND created_at >= ?1 AND created_at <= ?2",
+            params![start_date, end_date],
+            |row| row.get(0),
+        )?;
+        
+        let mut stmt = self.conn.prepare(
+            "SELECT c.duration_seconds, (SELECT COUNT(*) FROM alerts a WHERE a.call_id = c.call_id)
+             FROM calls c
+             WHERE c.duration_seconds IS NOT NULL AND c.created_at >= ?1 AND c.created_at <= ?2
+             ORDER BY c.duration_seconds"
+        )?;
+        let calls: Vec<(u64, u32)> = stmt.query_map(params![start_date, end_date], |row| {
+            Ok((row.get(0)?, row.get(1)?))
+        })?.collect::<Result<Vec<_>, _>>()?;
+        
+        // Nearest-rank percentile over the sorted durations
+        let percentile = |p: f64| -> Option<u64> {
+            let rank = ((p * calls.len() as f64).ceil() as usize).max(1);
+            calls.get(rank - 1).map(|(secs, _)| *secs)
+        };
+        let avg_seconds = (!calls.is_empty())
+            .then(|| calls.iter().map(|(secs, _)| *secs as f64).sum::<f64>() / calls.len() as f64);
+        
+        let mut lower = 0;
+        let by_length = DURATION_BUCKETS.iter()
+            .map(|&(upper, label)| {
+                let in_bucket: Vec<&(u64, u32)> = calls.iter()
+                    .filter(|(secs, _)| *secs >= lower && *secs < upper)
+                    .collect();
+                lower = upper;
+                let alerts: u32 = in_bucket.iter().map(|(_, alerts)| alerts).sum();
+                DurationBucket {
+                    label: label.to_string(),
+                    calls: in_bucket.len() as u32,
+                    alerts_per_call: if in_bucket.is_empty() { 0.0 } else { alerts as f64 / in_bucket.len() as f64 },
+                }
+            })
+            .collect();
+        
+        Ok(CallDurationStats {
+            completed_calls: calls.len() as u32,
+            open_calls,
+            avg_seconds,
+            p50_seconds: percentile(0.5),
+            p90_seconds: percentile(0.9),
+            by_length,
         })
     }
+    
+    /// Alerts per call for each agent with calls in the range, keyed by agent_id
+    fn agent_alert_rates(
+        &self,
+        start_date: &str,
+        end_date: &str,
+        end_inclusive: bool,
+    ) -> Result<HashMap<String, (String, f64)>, rusqlite::Error> {
+        let end_op = if end_inclusive { "<=" } else { "<" };
+        let query = format!(
+            "SELECT c.agent_id, c.agent_name, COUNT(*) AS calls,
+                (SELECT COUNT(*) FROM alerts a WHERE a.agent_id = c.agent_id
+                 AND a.created_at >= ?1 AND a.created_at {op} ?2) AS alerts
+             FROM calls c
+             WHERE c.created_at >= ?1 AND c.created_at {op} ?2
+             GROUP BY c.agent_id",
+            op = end_op
+        );
+        
+        let mut stmt = self.conn.prepare(&query)?;
+        let rates = stmt.query_map(params![start_date, end_date], |row| {
+            let calls: u32 = row.get(2)?;
+            let alerts: u32 = row.get(3)?;
+            Ok((row.get::<_, String>(0)?, (row.get::<_, String>(1)?, alerts as f64 / calls as f64)))
+        })?.collect::<Result<HashMap<_, _>, _>>()?;
+        
+        Ok(rates)
+    }
+}
+
+/// One severity level up; High stays High
+fn escalate_severity(severity: &str) -> &'static str {
+    match severity {
+        "low" => "medium",
+        _ => "high",
+    }
+}
+
+/// SELECT over `alerts` with the columns `stored_alert_from_row` reads
+fn stored_alert_select() -> String {
+    format!(
+        "SELECT id, call_id, agent_id, agent_name, rule_id, title, severity, confidence, 
+         quote, start_char, end_char, why_it_matters, agent_fix_suggestion, segment_id, timestamp_ms, created_at,
+         (SELECT GROUP_CONCAT(tag, char({})) FROM alert_tags WHERE alert_tags.alert_id = alerts.id),
+         escalated_from, acknowledged_at, metadata_source, captures
+         FROM alerts",
+        TAG_SEPARATOR as u32
+    )
+}
+
+fn stored_alert_from_row(row: &rusqlite::Row) -> Result<StoredAlert, rusqlite::Error> {
+    Ok(StoredAlert {
+        id: row.get(0)?,
+        call_id: row.get(1)?,
+        agent_id: row.get(2)?,
+        agent_name: row.get(3)?,
+        rule_id: row.get(4)?,
+        title: row.get(5)?,
+        severity: row.get(6)?,
+        confidence: row.get(7)?,
+        quote: row.get(8)?,
+        start_char: row.get(9)?,
+        end_char: row.get(10)?,
+        why_it_matters: row.get(11)?,
+        agent_fix_suggestion: row.get(12)?,
+        segment_id: row.get(13)?,
+        timestamp_ms: row.get(14)?,
+        created_at: row.get(15)?,
+        tags: row.get::<_, Option<String>>(16)?
+            .map(|tags| tags.split(TAG_SEPARATOR).map(str::to_string).collect())
+            .unwrap_or_default(),
+        escalated_from: row.get(17)?,
+        acknowledged_at: row.get(18)?,
+        metadata_source: row.get(19)?,
+        captures: row.get::<_, Option<String>>(20)?
+            .map(|json| serde_json::from_str(&json)
+                .map_err(|e| rusqlite::Error::FromSqlConversionFailure(20, rusqlite::types::Type::Text, Box::new(e))))
+            .transpose()?
+            .unwrap_or_default(),
+    })
+}
+
+/// Add a column to an existing table if an older database predates it
+fn ensure_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<(), rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .any(|name| name == column);
+    
+    if !exists {
+        conn.execute(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition), [])?;
+    }
+    Ok(())
 }
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// Layout of a DNC list file, which is also the list it's stored as
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DncListFormat {
+    /// The company's own do-not-call list: one number per line, in any common
+    /// format, optionally as the first column of a CSV
+    Internal,
+    /// National DNC Registry download: "area code,7-digit number" per line
+    National,
+}
+
+impl DncListFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DncListFormat::Internal => "internal",
+            DncListFormat::National => "national",
+        }
+    }
+}
+
+/// Outcome of loading a DNC list file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DncLoadResult {
+    pub format: DncListFormat,
+    /// Distinct numbers now on the list
+    pub loaded: usize,
+    /// Non-blank lines that weren't a valid US number, including any header row
+    pub skipped: usize,
+}
+
+/// Parse a DNC list into normalized numbers, and count the lines skipped
+pub fn parse_dnc_list(content: &str, format: DncListFormat) -> (Vec<String>, usize) {
+    let mut numbers = Vec::new();
+    let mut skipped = 0;
+    for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let raw = match format {
+            // The registry splits the area code into its own column
+            DncListFormat::National => line.split(',').take(2).collect::<String>(),
+            DncListFormat::Internal => line.split(',').next().unwrap_or_default().to_string(),
+        };
+        match normalize_phone(&raw) {
+            Some(number) => numbers.push(number),
+            None => skipped += 1,
+        }
+    }
+    numbers.sort();
+    numbers.dedup();
+    (numbers, skipped)
+}
+
+/// Reduce a US phone number to its 10 digits, dropping punctuation and a
+/// leading country code. `None` if it isn't a 10-digit number.
+pub fn normalize_phone(raw: &str) -> Option<String> {
+    let digits: String = raw.chars().filter(|c| c.is_ascii_digit()).collect();
+    let digits = match digits.len() {
+        11 if digits.starts_with('1') => &digits[1..],
+        10 => &digits[..],
+        _ => return None,
+    };
+    Some(digits.to_string())
+}
@@ -1,9 +1,17 @@
-use regex::Regex;
+use chrono::{DateTime, NaiveDateTime, Timelike, Utc};
+use chrono_tz::Tz;
+use regex::{Captures, Regex};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::sync::Mutex;
-use crate::{CallMetadata, RuleSet, rules::{Rule, Severity}};
+use std::time::{Duration, Instant};
+use crate::{CallMetadata, RuleSet, TranscriptSegment, phonetic, rules::{Rule, Severity}, transcript};
 
-/// Evidence for an alert
+/// Evidence for an alert.
+///
+/// `start_char`/`end_char` are offsets in UTF-16 code units into the evaluated
+/// transcript, the same units JavaScript uses for string indexing, so the UI can
+/// highlight with `transcript.slice(start_char, end_char)`. Metadata alerts use 0..0.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Evidence {
     pub quote: String,
@@ -22,13 +30,72 @@ pub struct Alert {
     pub evidence: Evidence,
     pub why_it_matters: String,
     pub agent_fix_suggestion: String,
+    /// Transcript segment the evidence fell within, when evaluated by segment
+    #[serde(default)]
+    pub segment_id: Option<String>,
+    /// 1 = high, 2 = medium, 3 = low; derived from severity, and from
+    /// confidence too when a `PriorityPolicy` is enabled
+    #[serde(default)]
+    pub priority: u8,
+    /// Position within the result after sorting by priority and confidence
+    #[serde(default)]
+    pub display_order: u32,
+    /// Approximate audio position of the evidence, from its segment's timestamp
+    #[serde(default)]
+    pub timestamp_ms: Option<u64>,
+    /// Engines that raised this alert ("rules", "llm"); more than one after dedup
+    #[serde(default)]
+    pub sources: Vec<String>,
+    /// Extended explanation attached in training mode
+    #[serde(default)]
+    pub coaching: Option<AlertCoaching>,
+    /// For metadata-rule alerts, where the metadata came from (see
+    /// `CallMetadata::metadata_source`), so reviewers can judge the flag
+    #[serde(default)]
+    pub metadata_source: Option<String>,
+    /// Values of the matched pattern's named capture groups, e.g. the promised
+    /// `amount` for MISREP-001. Empty for patterns without named groups.
+    #[serde(default)]
+    pub captures: HashMap<String, String>,
 }
 
+/// Full rule context shown inline to agents practicing in training mode
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertCoaching {
+    pub description: String,
+    pub legal_reference: String,
+}
+
+/// A required disclosure first heard during the call, for the review timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisclosureMilestone {
+    pub rule_id: String,
+    pub label: String,
+    pub quote: String,
+    /// UTF-16 offsets into the transcript, like `Evidence`
+    pub start_char: usize,
+    pub end_char: usize,
+    pub timestamp_ms: Option<u64>,
+}
+
+/// `Alert::sources` value for the regex/keyword evaluator
+pub const SOURCE_RULES: &str = "rules";
+
+/// `Alert::sources` value for the LLM evaluator
+pub const SOURCE_LLM: &str = "llm";
+
+/// `Alert::sources` value for the embedding-based semantic matcher
+pub const SOURCE_SEMANTIC: &str = "semantic";
+
 /// Suggested next line for the agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SuggestedLine {
     pub text: String,
     pub confidence: u8,
+    /// Rule the line is meant to head off, used to track whether showing it
+    /// helped. `None` for free-form LLM suggestions.
+    #[serde(default)]
+    pub rule_id: Option<String>,
 }
 
 /// Result of evaluation
@@ -38,6 +105,252 @@ pub struct EvaluationOutput {
     pub suggested_next_lines: Vec<SuggestedLine>,
 }
 
+/// Rolling risk level for a call in progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveRisk {
+    pub call_id: String,
+    /// "green", "amber" or "red"
+    pub level: String,
+    /// Sum of each fired alert's severity weight, decayed by how long ago it fired
+    pub score: f64,
+    /// Rules still contributing to the score, largest contribution first
+    pub contributing_rules: Vec<String>,
+}
+
+/// Where a required disclosure stands on a call in progress
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChecklistStatus {
+    Done,
+    Pending,
+    /// The rule has raised an alert on this call, e.g. payment taken before the cost was stated
+    Violated,
+}
+
+/// One line of the agent's live compliance checklist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChecklistItem {
+    pub rule_id: String,
+    pub label: String,
+    pub status: ChecklistStatus,
+}
+
+/// Disclosures on the live checklist, in the order an agent would normally
+/// make them, with the call types they're required on (`None` for all)
+const CHECKLIST_DISCLOSURES: &[(&str, Option<&str>)] = &[
+    ("DISC-001", Some("outbound_sales")),
+    ("DISC-002", Some("outbound_sales")),
+    ("DISC-003", Some("outbound_sales")),
+    ("PAY-001", Some("outbound_sales")),
+    ("REC-001", None),
+    ("IDENT-001", None),
+    ("PREC-002", None),
+];
+
+/// Live risk score at or above which the gauge turns amber / red. One fresh
+/// high alert is red on its own; a fresh medium or two lows is amber.
+const RISK_AMBER_THRESHOLD: f64 = 1.5;
+const RISK_RED_THRESHOLD: f64 = 3.0;
+
+/// An alert's contribution to live risk halves over this period
+const RISK_HALF_LIFE: Duration = Duration::from_secs(120);
+
+/// Contributions below this are considered spent and no longer listed
+const RISK_MIN_CONTRIBUTION: f64 = 0.1;
+
+/// Default transcript length (in characters) below which only High-severity
+/// rules run, so greetings in the first seconds of a call don't raise noise
+pub const DEFAULT_MIN_TRANSCRIPT_CHARS: usize = 100;
+
+/// Permitted calling hours in the consumer's local time, as whole hours
+/// (`start_hour` inclusive, `end_hour` exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CallingWindow {
+    pub start_hour: u32,
+    pub end_hour: u32,
+    /// Whether prior express consent downgrades a violation to Medium. True for
+    /// telemarketing, where consent lifts the TCPA hours but not the TSR's.
+    #[serde(default)]
+    pub consent_downgrades: bool,
+}
+
+impl CallingWindow {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.start_hour >= self.end_hour || self.end_hour > 24 {
+            return Err(format!(
+                "Invalid calling window {}:00-{}:00; hours must satisfy start < end <= 24",
+                self.start_hour, self.end_hour
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Telemarketing hours (8am to 9pm), used for call types without an entry
+const TELEMARKETING_WINDOW: CallingWindow = CallingWindow { start_hour: 8, end_hour: 21, consent_downgrades: true };
+
+/// Default TIME-001 windows by `call_type`. `None` skips the check: inbound
+/// calls are placed by the consumer, and service callbacks aren't telephone
+/// solicitations. Collections follow FDCPA § 805(a)(1), which consent to
+/// marketing calls doesn't affect.
+pub fn default_calling_windows() -> HashMap<String, Option<CallingWindow>> {
+    HashMap::from([
+        ("outbound_sales".to_string(), Some(TELEMARKETING_WINDOW)),
+        ("collections".to_string(), Some(CallingWindow { start_hour: 8, end_hour: 21, consent_downgrades: false })),
+        ("service".to_string(), None),
+        ("inbound".to_string(), None),
+    ])
+}
+
+/// Default words-per-minute above which a disclosure counts as rushed
+pub const DEFAULT_MAX_DISCLOSURE_WPM: u32 = 200;
+
+/// Default length of agent speech after an acknowledgment-needing turn, with no
+/// customer turn, at which DELIV-002 fires
+pub const DEFAULT_MAX_MONOLOGUE_SECS: u64 = 30;
+
+/// Default length of the opening stretch of a call, in characters, in which
+/// DISC-001/DISC-002 can't be late or missing. The rules only require the
+/// disclosures promptly, not in the first breath.
+pub const DEFAULT_DISCLOSURE_GRACE_CHARS: usize = 200;
+
+/// Default diarization confidence below which a segment's speaker label isn't
+/// trusted, and the segment is checked against rules for either speaker
+pub const DEFAULT_MIN_DIARIZATION_CONFIDENCE: f64 = 0.6;
+
+/// Consecutive agent turns (including the disclosure) that also count as steamrolling
+const MAX_MONOLOGUE_TURNS: usize = 4;
+
+/// Longest sentence used as a quote; past this (e.g. unpunctuated ASR output)
+/// the fixed-width context window is used instead
+const MAX_SENTENCE_QUOTE_BYTES: usize = 300;
+
+/// Disclosure turns shorter than this are too brief to time reliably
+const MIN_TIMED_DISCLOSURE_WORDS: usize = 8;
+
+/// A customer turn this long or longer is a substantive statement for REC-002;
+/// shorter ones are greetings like "Hello?" or "Yes, speaking"
+const SUBSTANTIVE_STATEMENT_WORDS: usize = 4;
+
+/// How many turns after a confused customer statement to look for an agent push
+const CONFUSION_FOLLOW_UP_TURNS: usize = 3;
+
+/// How many agent turns either side of a DELIV-003 phrase to look for the
+/// disclosure it undercuts
+const UNDERCUT_DISCLOSURE_TURNS: usize = 1;
+
+/// How many agent turns either side of a DISC-004 vague affiliation to look
+/// for the company name
+const VAGUE_SELLER_TURNS: usize = 1;
+
+/// DISC-004 confidence when the call's metadata has no company name to look
+/// for, so the vague affiliation alone is the evidence
+const VAGUE_SELLER_UNNAMED_CONFIDENCE: u8 = 60;
+
+/// Hedging that turns a MISREP-001 figure into a qualified claim
+const CLAIM_QUALIFIER_PATTERN: &str = r"(?i)\b(may|might|could|up\s+to|as\s+much\s+as|results\s+(may\s+)?vary|(on\s+)?average|typically|estimated?|depending\s+on|not\s+guaranteed|individual\s+results)\b";
+
+/// Confidence taken off metadata-rule alerts when the metadata was entered by
+/// the agent rather than from a verified source
+const AGENT_ENTERED_CONFIDENCE_PENALTY: u8 = 20;
+
+/// Pricing or enrollment language in an agent line that marks the start of the
+/// sales pitch. Seller and sales-purpose disclosures first made after it count as late.
+const PITCH_PATTERN: &str = r"(?i)(\bprice\b|\bcosts?\b|per\s+month|a\s+month|sign\s+(you\s+)?up|get\s+you\s+(started|enrolled)|\$\s*\d)";
+
+/// Agent wording that describes what is being sold, for PAY-001
+const PRODUCT_DESCRIPTION_PATTERN: &str = r"(?i)(calling\s+(about|with|regarding)|tell\s+you\s+about|offer\s+(on|for)|our\s+(\w+\s+){0,2}(plan|service|program|product|policy|coverage|warranty|panels|package|membership|subscription))";
+
+/// Agent wording that states a cost, for PAY-001
+const COST_DISCLOSURE_PATTERN: &str = r"(?i)(\$\s*\d|\d+\s+dollars|per\s+(month|year|week)|a\s+(month|year)|total\s+(cost|price|of)|one[-\s]time\s+(fee|payment))";
+
+/// Consent topics for CONS-003 and the lowercase phrases that name them
+const CONSENT_TOPICS: &[(&str, &[&str])] = &[
+    ("recording", &["record"]),
+    ("contact", &["call you", "calling you", "calls", "text you", "texts", "contact you", "reach you", "messages"]),
+    ("purchase", &["sign you up", "sign up", "enroll", "order", "purchase", "charge", "subscription", "credit card", "payment"]),
+];
+
+/// How confidence shifts an alert's display priority away from the one its
+/// severity gives. An alert at or above `escalate_at` confidence moves up
+/// `levels` priority levels, and one below `demote_below` moves down as many,
+/// within High..Low. Only `priority` and display order change; `severity` is
+/// left as the rule set it, so stored alerts and reports are unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PriorityPolicy {
+    pub enabled: bool,
+    pub escalate_at: u8,
+    pub demote_below: u8,
+    pub levels: u8,
+}
+
+impl PriorityPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.escalate_at > 100 || self.demote_below >= self.escalate_at {
+            return Err("Priority policy needs demote_below < escalate_at <= 100".to_string());
+        }
+        if !(1..=2).contains(&self.levels) {
+            return Err("Priority policy levels must be 1 or 2".to_string());
+        }
+        Ok(())
+    }
+    
+    /// Display priority for an alert of this severity and confidence
+    pub fn display_priority(&self, severity: &str, confidence: u8) -> u8 {
+        let base = severity_priority(severity);
+        if !self.enabled {
+            base
+        } else if confidence >= self.escalate_at {
+            base.saturating_sub(self.levels).max(1)
+        } else if confidence < self.demote_below {
+            (base + self.levels).min(3)
+        } else {
+            base
+        }
+    }
+}
+
+impl Default for PriorityPolicy {
+    fn default() -> Self {
+        PriorityPolicy {
+            enabled: false,
+            escalate_at: 95,
+            demote_below: 50,
+            levels: 1,
+        }
+    }
+}
+
+/// Tunable evaluator behaviour, adjustable at runtime
+#[derive(Debug, Clone)]
+struct EvaluatorSettings {
+    min_transcript_chars: usize,
+    max_disclosure_wpm: u32,
+    max_monologue_secs: u64,
+    disclosure_grace_chars: usize,
+    min_diarization_confidence: f64,
+    /// Extend quotes to whole sentences instead of a fixed-width window
+    sentence_quotes: bool,
+    /// TIME-001 calling window per `call_type`; `None` exempts the call type
+    calling_windows: HashMap<String, Option<CallingWindow>>,
+    priority_policy: PriorityPolicy,
+}
+
+impl Default for EvaluatorSettings {
+    fn default() -> Self {
+        EvaluatorSettings {
+            min_transcript_chars: DEFAULT_MIN_TRANSCRIPT_CHARS,
+            max_disclosure_wpm: DEFAULT_MAX_DISCLOSURE_WPM,
+            max_monologue_secs: DEFAULT_MAX_MONOLOGUE_SECS,
+            disclosure_grace_chars: DEFAULT_DISCLOSURE_GRACE_CHARS,
+            min_diarization_confidence: DEFAULT_MIN_DIARIZATION_CONFIDENCE,
+            sentence_quotes: false,
+            calling_windows: default_calling_windows(),
+            priority_policy: PriorityPolicy::default(),
+        }
+    }
+}
+
 /// State tracking for multi-turn detection
 #[derive(Debug, Clone, Default)]
 struct ConversationState {
@@ -45,6 +358,36 @@ struct ConversationState {
     consent_revoked: bool,
     disclosures: DisclosureState,
     seen_alerts: Vec<String>,
+    /// (rule_id, severity, when) for each alert raised, feeding the live risk gauge
+    fired_alerts: Vec<(String, String, Instant)>,
+    /// Rules silenced for the rest of the session by a superseding alert
+    suppressed_rules: HashSet<String>,
+    /// Disclosures in the order they were first heard
+    milestones: Vec<DisclosureMilestone>,
+    /// Byte offset into the transcript where the sales pitch began
+    pitch_start: Option<usize>,
+    /// Byte and UTF-16 offset within the full transcript of the text being
+    /// evaluated, so the offsets kept here stay put as a capped window slides
+    window_start: (usize, usize),
+    /// Byte offset where the disclosure grace window ends; `None` while the
+    /// transcript is still inside it
+    grace_end: Option<usize>,
+    /// Whether the metadata's `company_name` has been heard in the transcript
+    company_named: bool,
+    /// Customer agreements and denials on a consent topic, one per turn
+    consent_statements: Vec<ConsentStatement>,
+    /// Metadata from the most recent evaluation, used by end-of-call checks
+    metadata: Option<CallMetadata>,
+}
+
+/// A customer turn agreeing to or denying something. `topic` is None for a
+/// denial that doesn't say what it's about ("I never agreed to this").
+#[derive(Debug, Clone)]
+struct ConsentStatement {
+    segment_id: String,
+    timestamp_ms: u64,
+    topic: Option<&'static str>,
+    affirmed: bool,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -52,26 +395,455 @@ struct DisclosureState {
     seller_identified: bool,
     sales_purpose_stated: bool,
     product_described: bool,
+    /// PAY-001's product description and cost, both stated
+    payment_terms_stated: bool,
     callback_provided: bool,
     recording_disclosed: bool,
+    opt_out_provided: bool,
+    /// Rules whose disclosure was first made after the pitch began
+    made_late: HashSet<String>,
+}
+
+/// An organization's approved alert wording for one rule. A field left unset
+/// falls back to the rule's own text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertTextOverride {
+    pub why_it_matters: Option<String>,
+    pub agent_fix_suggestion: Option<String>,
+}
+
+/// (tenant ID, call ID)
+pub(crate) type CallKey = (Option<String>, String);
+
+pub(crate) fn call_key(tenant_id: Option<&str>, call_id: &str) -> CallKey {
+    (tenant_id.map(str::to_string), call_id.to_string())
 }
 
 /// Compliance evaluator using regex-based rules (fallback mode)
 pub struct ComplianceEvaluator {
-    state: Mutex<ConversationState>,
+    /// Conversation state per call, keyed by tenant and `call_id` so tenants
+    /// that reuse a call ID don't share state
+    calls: Mutex<HashMap<CallKey, ConversationState>>,
+    settings: Mutex<EvaluatorSettings>,
+    /// Approved script phrases per campaign, stored lowercased
+    script_allowlists: Mutex<HashMap<String, Vec<String>>>,
+    /// Approved alert wording per rule, keyed by rule ID
+    alert_text_overrides: Mutex<HashMap<String, AlertTextOverride>>,
 }
 
 impl ComplianceEvaluator {
     pub fn new() -> Self {
         ComplianceEvaluator {
-            state: Mutex::new(ConversationState::default()),
+            calls: Mutex::new(HashMap::new()),
+            settings: Mutex::new(EvaluatorSettings::default()),
+            script_allowlists: Mutex::new(HashMap::new()),
+            alert_text_overrides: Mutex::new(HashMap::new()),
+        }
+    }
+    
+    /// Replace a campaign's allowlist of approved script phrases. Alerts whose
+    /// evidence falls entirely inside a verbatim match are suppressed.
+    pub fn set_script_allowlist(&self, campaign_id: &str, phrases: &[String]) {
+        let phrases: Vec<String> = phrases
+            .iter()
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty())
+            .collect();
+        
+        let mut allowlists = self.script_allowlists.lock().unwrap();
+        if phrases.is_empty() {
+            allowlists.remove(campaign_id);
+        } else {
+            allowlists.insert(campaign_id.to_string(), phrases);
+        }
+    }
+    
+    /// Set the transcript length below which only High-severity rules run
+    /// (default: `DEFAULT_MIN_TRANSCRIPT_CHARS`)
+    pub fn set_min_transcript_chars(&self, chars: usize) {
+        self.settings.lock().unwrap().min_transcript_chars = chars;
+    }
+    
+    /// Get the current minimum transcript length gate
+    pub fn min_transcript_chars(&self) -> usize {
+        self.settings.lock().unwrap().min_transcript_chars
+    }
+    
+    /// Set the speaking rate above which a disclosure is flagged as rushed
+    /// (default: `DEFAULT_MAX_DISCLOSURE_WPM`)
+    pub fn set_max_disclosure_wpm(&self, wpm: u32) {
+        self.settings.lock().unwrap().max_disclosure_wpm = wpm;
+    }
+    
+    /// Set how long the agent may keep talking after a disclosure needing a
+    /// response (default: `DEFAULT_MAX_MONOLOGUE_SECS`)
+    pub fn set_max_monologue_secs(&self, secs: u64) {
+        self.settings.lock().unwrap().max_monologue_secs = secs;
+    }
+    
+    /// Get the current no-pause limit in seconds
+    pub fn max_monologue_secs(&self) -> u64 {
+        self.settings.lock().unwrap().max_monologue_secs
+    }
+    
+    /// Set the opening stretch of a call, in characters, in which seller-identity
+    /// and sales-purpose disclosures are never late or missing
+    /// (default: `DEFAULT_DISCLOSURE_GRACE_CHARS`)
+    pub fn set_disclosure_grace_chars(&self, chars: usize) {
+        self.settings.lock().unwrap().disclosure_grace_chars = chars;
+    }
+    
+    /// Get the current disclosure grace window in characters
+    pub fn disclosure_grace_chars(&self) -> usize {
+        self.settings.lock().unwrap().disclosure_grace_chars
+    }
+    
+    /// Set the diarization confidence below which a segment is matched against
+    /// both agent and customer rules (default: `DEFAULT_MIN_DIARIZATION_CONFIDENCE`)
+    pub fn set_min_diarization_confidence(&self, confidence: f64) {
+        self.settings.lock().unwrap().min_diarization_confidence = confidence;
+    }
+    
+    /// Get the current speaker-label confidence threshold
+    pub fn min_diarization_confidence(&self) -> f64 {
+        self.settings.lock().unwrap().min_diarization_confidence
+    }
+    
+    /// Quote the whole sentence around a match rather than a fixed-width window.
+    /// Evidence offsets still cover just the match.
+    pub fn set_sentence_quotes(&self, enabled: bool) {
+        self.settings.lock().unwrap().sentence_quotes = enabled;
+    }
+    
+    /// Whether quotes are extended to sentence boundaries
+    pub fn sentence_quotes(&self) -> bool {
+        self.settings.lock().unwrap().sentence_quotes
+    }
+    
+    /// Set the TIME-001 window for a call type, or exempt it with `None`
+    pub fn set_calling_window(&self, call_type: &str, window: Option<CallingWindow>) {
+        self.settings.lock().unwrap().calling_windows.insert(call_type.to_string(), window);
+    }
+    
+    /// Replace the whole call type → calling window table
+    pub fn replace_calling_windows(&self, windows: HashMap<String, Option<CallingWindow>>) {
+        self.settings.lock().unwrap().calling_windows = windows;
+    }
+    
+    /// Current call type → calling window table
+    pub fn calling_windows(&self) -> HashMap<String, Option<CallingWindow>> {
+        self.settings.lock().unwrap().calling_windows.clone()
+    }
+    
+    /// Window that applies to a call type; unlisted types get telemarketing hours
+    fn calling_window_for(&self, call_type: &str) -> Option<CallingWindow> {
+        self.settings.lock().unwrap().calling_windows
+            .get(call_type)
+            .copied()
+            .unwrap_or(Some(TELEMARKETING_WINDOW))
+    }
+    
+    /// Get the current rushed-disclosure WPM limit
+    pub fn max_disclosure_wpm(&self) -> u32 {
+        self.settings.lock().unwrap().max_disclosure_wpm
+    }
+    
+    /// Replace every campaign's script allowlist at once
+    pub fn replace_script_allowlists(&self, allowlists: &HashMap<String, Vec<String>>) {
+        self.script_allowlists.lock().unwrap().clear();
+        for (campaign_id, phrases) in allowlists {
+            self.set_script_allowlist(campaign_id, phrases);
+        }
+    }
+    
+    /// All campaign script allowlists, keyed by campaign
+    pub fn script_allowlists(&self) -> HashMap<String, Vec<String>> {
+        self.script_allowlists.lock().unwrap().clone()
+    }
+    
+    /// Set or clear (`None`) a rule's alert wording override
+    pub fn set_alert_text_override(&self, rule_id: &str, text: Option<AlertTextOverride>) {
+        let mut overrides = self.alert_text_overrides.lock().unwrap();
+        match text {
+            Some(text) => overrides.insert(rule_id.to_string(), text),
+            None => overrides.remove(rule_id),
+        };
+    }
+    
+    /// Replace every alert wording override at once
+    pub fn replace_alert_text_overrides(&self, overrides: HashMap<String, AlertTextOverride>) {
+        *self.alert_text_overrides.lock().unwrap() = overrides;
+    }
+    
+    /// All alert wording overrides, keyed by rule ID
+    pub fn alert_text_overrides(&self) -> HashMap<String, AlertTextOverride> {
+        self.alert_text_overrides.lock().unwrap().clone()
+    }
+    
+    /// Set how confidence adjusts display priority (see `PriorityPolicy`)
+    pub fn set_priority_policy(&self, policy: PriorityPolicy) {
+        self.settings.lock().unwrap().priority_policy = policy;
+    }
+    
+    /// Get the current priority policy
+    pub fn priority_policy(&self) -> PriorityPolicy {
+        self.settings.lock().unwrap().priority_policy
+    }
+    
+    /// Set each alert's display priority under the priority policy, then sort
+    /// and number the alerts for display
+    pub fn order_alerts(&self, alerts: &mut [Alert]) {
+        let policy = self.priority_policy();
+        for alert in alerts.iter_mut() {
+            alert.priority = policy.display_priority(&alert.severity, alert.confidence);
+        }
+        assign_display_order(alerts);
+    }
+    
+    /// Substitute approved wording into alerts from either the rules or the LLM
+    pub fn apply_alert_text_overrides(&self, alerts: &mut [Alert]) {
+        let overrides = self.alert_text_overrides.lock().unwrap();
+        for alert in alerts {
+            let Some(text) = overrides.get(&alert.rule_id) else {
+                continue;
+            };
+            if let Some(ref why) = text.why_it_matters {
+                alert.why_it_matters = why.clone();
+            }
+            if let Some(ref fix) = text.agent_fix_suggestion {
+                alert.agent_fix_suggestion = fix.clone();
+            }
         }
     }
     
-    /// Reset state for new call
+    /// Reset state for all calls
     pub fn reset(&self) {
-        let mut state = self.state.lock().unwrap();
-        *state = ConversationState::default();
+        self.calls.lock().unwrap().clear();
+    }
+    
+    /// Reset state for a single call, leaving other concurrent calls intact
+    pub fn reset_call(&self, tenant_id: Option<&str>, call_id: &str) {
+        self.calls.lock().unwrap().remove(&call_key(tenant_id, call_id));
+    }
+    
+    /// Whether a rule has already alerted in this call, or been suppressed
+    pub fn has_raised(&self, tenant_id: Option<&str>, call_id: &str, rule_id: &str) -> bool {
+        self.calls.lock().unwrap().get(&call_key(tenant_id, call_id)).is_some_and(|state| {
+            state.seen_alerts.iter().any(|id| id == rule_id) || state.suppressed_rules.contains(rule_id)
+        })
+    }
+    
+    /// Filter alerts raised outside the rules engine (e.g. by semantic matching)
+    /// the way the engine filters its own: drop rules already raised,
+    /// suppressed, or waiting on a prerequisite. The rest are recorded as raised
+    /// so later evaluations don't repeat them and dependent rules can fire.
+    pub fn record_external_alerts(&self, tenant_id: Option<&str>, call_id: &str, alerts: &mut Vec<Alert>, rules: &RuleSet) {
+        let mut calls = self.calls.lock().unwrap();
+        let state = calls.entry(call_key(tenant_id, call_id)).or_default();
+        
+        alerts.retain(|alert| {
+            let Some(rule) = rules.get_rule(&alert.rule_id) else {
+                return false;
+            };
+            let waiting = rule.depends_on.as_ref().is_some_and(|p| !state.seen_alerts.contains(p));
+            if waiting || state.seen_alerts.contains(&rule.id) || state.suppressed_rules.contains(&rule.id) {
+                return false;
+            }
+            
+            match rule.id.as_str() {
+                "DNC-001" => state.dnc_requested = true,
+                "CONS-001" => state.consent_revoked = true,
+                _ => {}
+            }
+            state.seen_alerts.push(rule.id.clone());
+            state.fired_alerts.push((rule.id.clone(), alert.severity.clone(), Instant::now()));
+            suppress_superseded(rule, alert, rules, state);
+            true
+        });
+        self.apply_alert_text_overrides(alerts);
+    }
+    
+    /// Metadata from the call's most recent evaluation
+    pub fn call_metadata(&self, tenant_id: Option<&str>, call_id: &str) -> Option<CallMetadata> {
+        self.calls.lock().unwrap().get(&call_key(tenant_id, call_id)).and_then(|state| state.metadata.clone())
+    }
+    
+    /// Disclosures heard so far in a call, in the order they were first made
+    pub fn disclosure_milestones(&self, tenant_id: Option<&str>, call_id: &str) -> Vec<DisclosureMilestone> {
+        self.calls.lock().unwrap()
+            .get(&call_key(tenant_id, call_id))
+            .map(|state| state.milestones.clone())
+            .unwrap_or_default()
+    }
+    
+    /// The required disclosures for a call and whether each has been made, for
+    /// a live "still to disclose" panel. Only enabled rules that apply to the
+    /// call are listed; PREC-002 only on prerecorded calls. Empty until the
+    /// call has been evaluated.
+    pub fn checklist(&self, tenant_id: Option<&str>, call_id: &str, rules: &RuleSet) -> Vec<ChecklistItem> {
+        let calls = self.calls.lock().unwrap();
+        let Some(state) = calls.get(&call_key(tenant_id, call_id)) else {
+            return Vec::new();
+        };
+        let Some(ref metadata) = state.metadata else {
+            return Vec::new();
+        };
+        
+        let disclosures = &state.disclosures;
+        CHECKLIST_DISCLOSURES.iter()
+            .filter(|(_, call_type)| call_type.map_or(true, |t| t == metadata.call_type))
+            .filter(|(rule_id, _)| *rule_id != "PREC-002" || metadata.is_prerecorded)
+            .filter_map(|(rule_id, _)| rules.get_rule(rule_id))
+            .filter(|rule| rule.enabled && rule.applies_in(metadata.consumer_state.as_deref()))
+            .map(|rule| {
+                let made = match rule.id.as_str() {
+                    "DISC-001" => disclosures.seller_identified,
+                    "DISC-002" => disclosures.sales_purpose_stated,
+                    "DISC-003" => disclosures.product_described,
+                    "PAY-001" => disclosures.payment_terms_stated,
+                    "REC-001" => disclosures.recording_disclosed,
+                    "IDENT-001" => disclosures.callback_provided,
+                    "PREC-002" => disclosures.opt_out_provided,
+                    _ => false,
+                };
+                let status = if state.seen_alerts.contains(&rule.id) {
+                    ChecklistStatus::Violated
+                } else if made {
+                    ChecklistStatus::Done
+                } else {
+                    ChecklistStatus::Pending
+                };
+                ChecklistItem {
+                    rule_id: rule.id.clone(),
+                    label: disclosure_label(rule).to_string(),
+                    status,
+                }
+            })
+            .collect()
+    }
+    
+    /// Combine the call's fired alerts, weighted by severity and decayed by age,
+    /// into a green/amber/red gauge
+    pub fn live_risk(&self, tenant_id: Option<&str>, call_id: &str) -> LiveRisk {
+        let calls = self.calls.lock().unwrap();
+        let mut contributions: Vec<(String, f64)> = calls
+            .get(&call_key(tenant_id, call_id))
+            .map(|state| {
+                state.fired_alerts.iter()
+                    .map(|(rule_id, severity, fired_at)| {
+                        let age = fired_at.elapsed().as_secs_f64() / RISK_HALF_LIFE.as_secs_f64();
+                        let weight = match severity.as_str() {
+                            "high" => 3.0,
+                            "medium" => 1.5,
+                            _ => 0.75,
+                        };
+                        (rule_id.clone(), weight * 0.5f64.powf(age))
+                    })
+                    .filter(|(_, contribution)| *contribution >= RISK_MIN_CONTRIBUTION)
+                    .collect()
+            })
+            .unwrap_or_default();
+        contributions.sort_by(|a, b| b.1.total_cmp(&a.1));
+        
+        let score: f64 = contributions.iter().map(|(_, c)| c).sum();
+        let level = if score >= RISK_RED_THRESHOLD {
+            "red"
+        } else if score >= RISK_AMBER_THRESHOLD {
+            "amber"
+        } else {
+            "green"
+        };
+        
+        LiveRisk {
+            call_id: call_id.to_string(),
+            level: level.to_string(),
+            score,
+            contributing_rules: contributions.into_iter().map(|(rule_id, _)| rule_id).collect(),
+        }
+    }
+    
+    /// Run only the metadata rules (calling time, DNC list, prerecorded consent)
+    /// so risk knowable before dialing can be shown up front. Doesn't touch the
+    /// call's conversation state, so these rules still fire during the call.
+    pub fn precheck(&self, metadata: &CallMetadata, rules: &RuleSet) -> Result<Vec<Alert>, String> {
+        let mut alerts = Vec::new();
+        for rule in rules.get_enabled_rules() {
+            if !rule.requires_metadata || !rule.applies_in(metadata.consumer_state.as_deref()) {
+                continue;
+            }
+            if let Some(alert) = self.check_metadata_rule(metadata, rule)? {
+                alerts.push(alert);
+            }
+        }
+        
+        self.apply_alert_text_overrides(&mut alerts);
+        self.order_alerts(&mut alerts);
+        Ok(alerts)
+    }
+    
+    /// Apply corrected metadata to a call in progress and re-run the metadata
+    /// rules against it, returning only alerts not already raised this session
+    pub fn update_metadata(&self, metadata: &CallMetadata, rules: &RuleSet) -> Result<Vec<Alert>, String> {
+        let mut calls = self.calls.lock().unwrap();
+        let state = calls.entry(call_key(metadata.tenant_id.as_deref(), &metadata.call_id)).or_default();
+        state.metadata = Some(metadata.clone());
+        
+        let mut alerts = Vec::new();
+        for rule in rules.get_enabled_rules() {
+            if !rule.requires_metadata
+                || !rule.applies_in(metadata.consumer_state.as_deref())
+                || state.seen_alerts.contains(&rule.id)
+                || state.suppressed_rules.contains(&rule.id)
+            {
+                continue;
+            }
+            if let Some(alert) = self.check_metadata_rule(metadata, rule)? {
+                state.seen_alerts.push(alert.rule_id.clone());
+                state.fired_alerts.push((alert.rule_id.clone(), alert.severity.clone(), Instant::now()));
+                suppress_superseded(rule, &alert, rules, state);
+                alerts.push(alert);
+            }
+        }
+        
+        self.apply_alert_text_overrides(&mut alerts);
+        self.order_alerts(&mut alerts);
+        Ok(alerts)
+    }
+    
+    /// Evaluate a sequence of speaker-labelled segments, e.g. from an uploaded
+    /// transcript file. Segment offsets are rewritten to match the joined text.
+    pub fn evaluate_segments(
+        &self,
+        metadata: &CallMetadata,
+        segments: &mut [TranscriptSegment],
+        rules: &RuleSet,
+    ) -> Result<EvaluationOutput, String> {
+        let transcript = transcript::assemble_transcript(segments);
+        let mut output = self.evaluate_call(metadata, &transcript, 0, Some(segments), rules)?;
+        
+        for alert in &mut output.alerts {
+            if let Some(seg) = segment_for_span(segments, alert.evidence.start_char, alert.evidence.end_char) {
+                alert.segment_id = Some(seg.id.clone());
+                alert.timestamp_ms = Some(seg.timestamp_ms);
+            }
+        }
+        
+        // Matching works in byte offsets; the frontend contract is UTF-16
+        convert_offsets_to_utf16(&transcript, &mut output.alerts);
+        for segment in segments.iter_mut() {
+            segment.start_char = byte_to_utf16(&transcript, segment.start_char);
+            segment.end_char = byte_to_utf16(&transcript, segment.end_char);
+        }
+        
+        // Milestones are recorded in UTF-16 offsets, so attribute them now
+        if let Some(state) = self.calls.lock().unwrap().get_mut(&call_key(metadata.tenant_id.as_deref(), &metadata.call_id)) {
+            for milestone in state.milestones.iter_mut().filter(|m| m.timestamp_ms.is_none()) {
+                milestone.timestamp_ms = segment_for_span(segments, milestone.start_char, milestone.end_char)
+                    .map(|seg| seg.timestamp_ms);
+            }
+        }
+        
+        Ok(output)
     }
     
     /// Evaluate transcript for compliance issues
@@ -80,51 +852,143 @@ impl ComplianceEvaluator {
         metadata: &CallMetadata,
         transcript: &str,
         rules: &RuleSet,
+    ) -> Result<EvaluationOutput, String> {
+        self.evaluate_window(metadata, transcript, 0, rules)
+    }
+    
+    /// `evaluate` on only the text from byte offset `window_start` on, for a
+    /// transcript too long to score whole. Evidence offsets are still into the
+    /// full transcript.
+    pub fn evaluate_window(
+        &self,
+        metadata: &CallMetadata,
+        transcript: &str,
+        window_start: usize,
+        rules: &RuleSet,
+    ) -> Result<EvaluationOutput, String> {
+        let mut output = self.evaluate_call(metadata, transcript, window_start, None, rules)?;
+        convert_offsets_to_utf16(&transcript[window_start..], &mut output.alerts);
+        let shift = transcript[..window_start].encode_utf16().count();
+        for alert in &mut output.alerts {
+            alert.evidence.start_char += shift;
+            alert.evidence.end_char += shift;
+        }
+        Ok(output)
+    }
+    
+    /// Shared evaluation loop over the transcript from byte offset
+    /// `window_start` on. Speaker-aware rules only run when segments are
+    /// available, since a flat transcript can't tell who said what. Evidence
+    /// offsets are byte offsets into the window here; the public entry points
+    /// convert them.
+    fn evaluate_call(
+        &self,
+        metadata: &CallMetadata,
+        full_transcript: &str,
+        window_start: usize,
+        segments: Option<&[TranscriptSegment]>,
+        rules: &RuleSet,
     ) -> Result<EvaluationOutput, String> {
         let mut alerts = Vec::new();
         let mut suggestions = Vec::new();
         
-        let mut state = self.state.lock().unwrap();
+        let mut calls = self.calls.lock().unwrap();
+        let state = calls.entry(call_key(metadata.tenant_id.as_deref(), &metadata.call_id)).or_default();
+        state.metadata = Some(metadata.clone());
+        let transcript = &full_transcript[window_start..];
         let transcript_lower = transcript.to_lowercase();
+        state.window_start = (window_start, full_transcript[..window_start].encode_utf16().count());
+        
+        if state.grace_end.is_none() {
+            state.grace_end = full_transcript.char_indices()
+                .nth(self.disclosure_grace_chars())
+                .map(|(i, _)| i);
+        }
+        if state.pitch_start.is_none() {
+            state.pitch_start = Regex::new(PITCH_PATTERN).ok()
+                .and_then(|re| re.find_iter(&transcript_lower).find(|m| said_by(&transcript_lower, m.start(), "agent")))
+                .map(|m| window_start + m.start());
+        }
+        if !state.company_named {
+            if let Some(ref company_name) = metadata.company_name {
+                state.company_named = phonetic::mentions_name(&lines_by(transcript, "agent"), company_name);
+            }
+        }
+        
+        // Early in the call only High-severity rules may fire
+        let past_min_length = full_transcript.chars().count() >= self.min_transcript_chars();
         
         // Get enabled rules
-        let enabled_rules: Vec<&Rule> = rules.rules.iter().filter(|r| r.enabled).collect();
+        let consumer_state = metadata.consumer_state.as_deref();
+        let enabled_rules: Vec<&Rule> = rules.rules.iter()
+            .filter(|r| r.enabled)
+            .filter(|r| r.applies_in(consumer_state))
+            .filter(|r| past_min_length || r.severity == Severity::High)
+            .collect();
         
         // Process each rule
-        for rule in enabled_rules {
-            // Skip if already alerted for this rule in this session
-            if state.seen_alerts.contains(&rule.id) {
+        for rule in &enabled_rules {
+            // Skip if already alerted for this rule in this session, or superseded
+            if state.seen_alerts.contains(&rule.id) || state.suppressed_rules.contains(&rule.id) {
                 continue;
             }
             
-            if let Some(alert) = self.check_rule(metadata, transcript, &transcript_lower, rule, &mut state)? {
-                state.seen_alerts.push(alert.rule_id.clone());
-                
-                // Add suggestion based on alert
-                if !rule.recommended_fix.is_empty() {
-                    suggestions.push(SuggestedLine {
-                        text: rule.recommended_fix.clone(),
-                        confidence: 85,
-                    });
+            // Prerequisite rule must have fired earlier in this session
+            if let Some(ref prerequisite) = rule.depends_on {
+                if !state.seen_alerts.contains(prerequisite) {
+                    continue;
                 }
-                
+            }
+            
+            let alert = if is_segment_rule(rule) {
+                match segments {
+                    Some(segments) => self.check_segment_rule(metadata, segments, rule, state)?,
+                    None => None,
+                }
+            } else {
+                self.check_rule(metadata, transcript, &transcript_lower, rule, state)?
+            };
+            let alert = alert.filter(|a| !self.is_allowlisted(metadata, &transcript_lower, a));
+            
+            if let Some(alert) = alert {
+                state.seen_alerts.push(alert.rule_id.clone());
+                state.fired_alerts.push((alert.rule_id.clone(), alert.severity.clone(), Instant::now()));
+                suppress_superseded(rule, &alert, rules, state);
                 alerts.push(alert);
             }
         }
         
+        // A rule can fire earlier in the pass than the rule that supersedes it
+        alerts.retain(|a| !state.suppressed_rules.contains(&a.rule_id));
+        state.fired_alerts.retain(|(rule_id, _, _)| !state.suppressed_rules.contains(rule_id));
+        self.apply_alert_text_overrides(&mut alerts);
+        
+        // Add suggestions based on alerts, in the approved wording if overridden
+        for alert in &alerts {
+            if !alert.agent_fix_suggestion.is_empty() {
+                suggestions.push(SuggestedLine {
+                    text: alert.agent_fix_suggestion.clone(),
+                    confidence: 85,
+                    rule_id: Some(alert.rule_id.clone()),
+                });
+            }
+        }
+        
         // Add contextual suggestions for missing disclosures
-        if metadata.call_type == "outbound_sales" && transcript.len() > 100 {
+        if metadata.call_type == "outbound_sales" && past_min_length {
             if !state.disclosures.seller_identified {
                 suggestions.push(SuggestedLine {
                     text: "Identify yourself and your company: 'Hi, my name is [Name] calling from [Company Name].'".to_string(),
                     confidence: 80,
+                    rule_id: Some("DISC-001".to_string()),
                 });
             }
             
-            if !state.disclosures.sales_purpose_stated {
+            if !state.disclosures.sales_purpose_stated && !state.suppressed_rules.contains("DISC-002") {
                 suggestions.push(SuggestedLine {
                     text: "Disclose the sales purpose: 'I'm calling today with a special offer for you.'".to_string(),
                     confidence: 80,
+                    rule_id: Some("DISC-002".to_string()),
                 });
             }
         }
@@ -132,41 +996,196 @@ impl ComplianceEvaluator {
         // Limit suggestions
         suggestions.truncate(3);
         
+        self.order_alerts(&mut alerts);
+        
         Ok(EvaluationOutput {
             alerts,
             suggested_next_lines: suggestions,
         })
     }
     
-    fn check_rule(
-        &self,
-        metadata: &CallMetadata,
-        transcript: &str,
-        transcript_lower: &str,
-        rule: &Rule,
-        state: &mut ConversationState,
-    ) -> Result<Option<Alert>, String> {
-        // Handle metadata-based rules first
-        if rule.requires_metadata {
-            return self.check_metadata_rule(metadata, rule);
+    /// Whether the alert's evidence sits inside an approved script phrase for the
+    /// call's campaign. Metadata alerts have no transcript span and never match.
+    fn is_allowlisted(&self, metadata: &CallMetadata, transcript_lower: &str, alert: &Alert) -> bool {
+        let Some(ref campaign_id) = metadata.campaign_id else {
+            return false;
+        };
+        let evidence = &alert.evidence;
+        if evidence.end_char <= evidence.start_char {
+            return false;
         }
         
-        // Check trigger phrases
-        for trigger in &rule.triggers {
-            let trigger_lower = trigger.to_lowercase();
-            if let Some(pos) = transcript_lower.find(&trigger_lower) {
-                let end_pos = pos + trigger.len();
-                let context_end = (end_pos + 30).min(transcript.len());
-                let quote = transcript[pos..context_end].trim().to_string();
-                
-                // Handle DNC detection
-                if rule.id == "DNC-001" {
-                    state.dnc_requested = true;
-                }
+        let allowlists = self.script_allowlists.lock().unwrap();
+        let Some(phrases) = allowlists.get(campaign_id) else {
+            return false;
+        };
+        
+        phrases.iter().any(|phrase| {
+            transcript_lower.match_indices(phrase.as_str()).any(|(start, matched)| {
+                start <= evidence.start_char && evidence.end_char <= start + matched.len()
+            })
+        })
+    }
+    
+    /// Run end-of-call checks for requirements that can only be judged once the
+    /// call is over, e.g. a disclosure that was never made
+    pub fn finalize_call(&self, tenant_id: Option<&str>, call_id: &str, rules: &RuleSet) -> Vec<Alert> {
+        let mut calls = self.calls.lock().unwrap();
+        let Some(state) = calls.get_mut(&call_key(tenant_id, call_id)) else {
+            return Vec::new();
+        };
+        let Some(metadata) = state.metadata.clone() else {
+            return Vec::new();
+        };
+        
+        let mut alerts = Vec::new();
+        for rule in rules.get_enabled_rules() {
+            if state.seen_alerts.contains(&rule.id)
+                || state.suppressed_rules.contains(&rule.id)
+                || !rule.applies_in(metadata.consumer_state.as_deref())
+            {
+                continue;
+            }
+            if let Some(alert) = self.check_end_of_call_rule(&metadata, rule, state) {
+                state.seen_alerts.push(alert.rule_id.clone());
+                state.fired_alerts.push((alert.rule_id.clone(), alert.severity.clone(), Instant::now()));
+                alerts.push(alert);
+            }
+        }
+        
+        self.apply_alert_text_overrides(&mut alerts);
+        self.order_alerts(&mut alerts);
+        alerts
+    }
+    
+    fn check_end_of_call_rule(
+        &self,
+        metadata: &CallMetadata,
+        rule: &Rule,
+        state: &ConversationState,
+    ) -> Option<Alert> {
+        let quote = match rule.id.as_str() {
+            "PREC-002" if metadata.is_prerecorded && !state.disclosures.opt_out_provided => {
+                "Prerecorded message ended without opt-out instructions"
+            }
+            "DISC-001" if state.disclosures.seller_identified && !state.company_named
+                && metadata.company_name.as_deref().is_some_and(|n| !n.trim().is_empty()) => {
+                return self.check_company_name(metadata, rule, state);
+            }
+            "DISC-001" | "DISC-002" if metadata.call_type == "outbound_sales"
+                && state.pitch_start.is_some()
+                && state.grace_end.is_some() => {
+                return self.check_pitch_disclosure(rule, state);
+            }
+            _ => return None,
+        };
+        
+        Some(build_alert(rule, 90, Evidence {
+            quote: quote.to_string(),
+            start_char: 0,
+            end_char: 0,
+        }))
+    }
+    
+    /// DISC-001 with an expected `company_name`: the agent used seller-identity
+    /// phrasing but the company name was never heard, even allowing for
+    /// transcription errors. The identity phrasing is the evidence.
+    fn check_company_name(&self, metadata: &CallMetadata, rule: &Rule, state: &ConversationState) -> Option<Alert> {
+        let company_name = metadata.company_name.as_deref()?.trim();
+        let milestone = state.milestones.iter().find(|m| m.rule_id == rule.id)?;
+        let mut alert = build_alert(rule, 75, Evidence {
+            quote: milestone.quote.clone(),
+            start_char: milestone.start_char,
+            end_char: milestone.end_char,
+        });
+        alert.title = format!("Seller Name \"{}\" Not Stated", company_name);
+        alert.timestamp_ms = milestone.timestamp_ms;
+        Some(alert)
+    }
+    
+    /// DISC-001/DISC-002 must come before the pitch. Never made is an alert at
+    /// the rule's severity; made after the pitch is downgraded to Low, with the
+    /// late disclosure as evidence.
+    fn check_pitch_disclosure(&self, rule: &Rule, state: &ConversationState) -> Option<Alert> {
+        let (made, never_made, made_late) = match rule.id.as_str() {
+            "DISC-001" => (state.disclosures.seller_identified, "Seller identity was never disclosed", "Seller Identity Disclosure Made Late"),
+            "DISC-002" => (state.disclosures.sales_purpose_stated, "Sales purpose was never disclosed", "Sales Purpose Disclosure Made Late"),
+            _ => return None,
+        };
+        
+        if !made {
+            return Some(build_alert(rule, 80, Evidence {
+                quote: never_made.to_string(),
+                start_char: 0,
+                end_char: 0,
+            }));
+        }
+        if !state.disclosures.made_late.contains(&rule.id) {
+            return None;
+        }
+        
+        // Milestone offsets are already UTF-16, like the rest of finalize's output
+        let milestone = state.milestones.iter().find(|m| m.rule_id == rule.id)?;
+        let mut alert = build_alert(rule, 80, Evidence {
+            quote: milestone.quote.clone(),
+            start_char: milestone.start_char,
+            end_char: milestone.end_char,
+        });
+        alert.title = made_late.to_string();
+        alert.timestamp_ms = milestone.timestamp_ms;
+        downgrade_alert(&mut alert, Severity::Low);
+        Some(alert)
+    }
+    
+    fn check_rule(
+        &self,
+        metadata: &CallMetadata,
+        transcript: &str,
+        transcript_lower: &str,
+        rule: &Rule,
+        state: &mut ConversationState,
+    ) -> Result<Option<Alert>, String> {
+        // Handle metadata-based rules first
+        if rule.requires_metadata {
+            return self.check_metadata_rule(metadata, rule);
+        }
+        
+        // Safe-harbor phrases satisfy a disclosure rule without a regex match,
+        // but only when the agent says them
+        let phrase_match = rule.satisfying_phrases.iter().find_map(|p| {
+            let phrase = p.to_lowercase();
+            transcript_lower.match_indices(&phrase)
+                .map(|(pos, _)| (floor_char_boundary(transcript, pos), floor_char_boundary(transcript, pos + phrase.len())))
+                .find(|&(start, _)| said_by(transcript, start, "agent"))
+        });
+        if let Some((start, end)) = phrase_match {
+            if record_disclosure(&rule.id, start, state) {
+                record_milestone(rule, transcript, start, end, state);
+                return Ok(None);
+            }
+        }
+        
+        let sentence_quotes = self.sentence_quotes();
+        let in_speaker_line = |pos: usize| {
+            rule_speaker(&rule.id).map_or(true, |speaker| said_by(transcript, floor_char_boundary(transcript, pos), speaker))
+        };
+        
+        // Check trigger phrases
+        for trigger in &rule.triggers {
+            let trigger_lower = trigger.to_lowercase();
+            let found = transcript_lower.match_indices(&trigger_lower)
+                .map(|(pos, _)| pos)
+                .find(|&pos| in_speaker_line(pos));
+            if let Some(pos) = found {
+                // Lowercasing can shift byte lengths for some non-ASCII text, so
+                // keep every slice of the original on a char boundary
+                let pos = floor_char_boundary(transcript, pos);
+                let end_pos = floor_char_boundary(transcript, pos + trigger.len());
+                let quote = match_quote(transcript, pos, end_pos, 30, sentence_quotes);
                 
-                // DNC-002 only triggers after DNC-001
-                if rule.id == "DNC-002" && !state.dnc_requested {
-                    return Ok(None);
+                // Handle DNC detection
+                if rule.id == "DNC-001" {
+                    state.dnc_requested = true;
                 }
                 
                 // Consent revocation
@@ -174,84 +1193,45 @@ impl ComplianceEvaluator {
                     state.consent_revoked = true;
                 }
                 
-                return Ok(Some(Alert {
-                    id: uuid::Uuid::new_v4().to_string(),
-                    rule_id: rule.id.clone(),
-                    title: rule.title.clone(),
-                    severity: severity_to_string(&rule.severity),
-                    confidence: 90,
-                    evidence: Evidence {
-                        quote,
-                        start_char: pos,
-                        end_char: end_pos,
-                    },
-                    why_it_matters: rule.why_it_matters.clone(),
-                    agent_fix_suggestion: rule.recommended_fix.clone(),
-                }));
+                return Ok(Some(build_alert(rule, 90, Evidence {
+                    quote,
+                    start_char: pos,
+                    end_char: end_pos,
+                })));
             }
         }
         
         // Check regex patterns
         for pattern in &rule.regex_patterns {
             if let Ok(re) = Regex::new(pattern) {
-                if let Some(m) = re.find(transcript_lower) {
-                    let start = m.start();
-                    let end = m.end();
-                    let context_end = (end + 20).min(transcript.len());
-                    let quote = transcript[start..context_end].trim().to_string();
+                if let Some(caps) = re.captures_iter(transcript_lower).find(|c| in_speaker_line(c.get(0).map_or(0, |m| m.start()))) {
+                    let m = caps.get(0).expect("group 0 is the whole match");
+                    let start = floor_char_boundary(transcript, m.start());
+                    let end = floor_char_boundary(transcript, m.end());
+                    let quote = match_quote(transcript, start, end, 20, sentence_quotes);
                     
                     // Update state for DNC rules
                     if rule.id == "DNC-001" {
                         state.dnc_requested = true;
                     }
                     
-                    if rule.id == "DNC-002" && !state.dnc_requested {
-                        return Ok(None);
-                    }
-                    
                     if rule.id == "CONS-001" {
                         state.consent_revoked = true;
                     }
                     
                     // For disclosure rules - these are positive detections
-                    match rule.id.as_str() {
-                        "DISC-001" => {
-                            state.disclosures.seller_identified = true;
-                            return Ok(None); // Don't alert on positive match
-                        }
-                        "DISC-002" => {
-                            state.disclosures.sales_purpose_stated = true;
-                            return Ok(None);
-                        }
-                        "DISC-003" => {
-                            state.disclosures.product_described = true;
-                            return Ok(None);
-                        }
-                        "IDENT-001" => {
-                            state.disclosures.callback_provided = true;
-                            return Ok(None);
-                        }
-                        "REC-001" => {
-                            state.disclosures.recording_disclosed = true;
-                            return Ok(None);
-                        }
-                        _ => {}
+                    if record_disclosure(&rule.id, start, state) {
+                        record_milestone(rule, transcript, start, end, state);
+                        return Ok(None); // Don't alert on positive match
                     }
                     
-                    return Ok(Some(Alert {
-                        id: uuid::Uuid::new_v4().to_string(),
-                        rule_id: rule.id.clone(),
-                        title: rule.title.clone(),
-                        severity: severity_to_string(&rule.severity),
-                        confidence: 85,
-                        evidence: Evidence {
-                            quote,
-                            start_char: start,
-                            end_char: end,
-                        },
-                        why_it_matters: rule.why_it_matters.clone(),
-                        agent_fix_suggestion: rule.recommended_fix.clone(),
-                    }));
+                    let mut alert = build_alert(rule, 85, Evidence {
+                        quote,
+                        start_char: start,
+                        end_char: end,
+                    });
+                    alert.captures = named_captures(&re, &caps, transcript);
+                    return Ok(Some(alert));
                 }
             }
         }
@@ -259,52 +1239,458 @@ impl ComplianceEvaluator {
         Ok(None)
     }
     
+    /// Multi-turn rules that need speaker labels and turn order
+    fn check_segment_rule(
+        &self,
+        metadata: &CallMetadata,
+        segments: &[TranscriptSegment],
+        rule: &Rule,
+        state: &mut ConversationState,
+    ) -> Result<Option<Alert>, String> {
+        match rule.id.as_str() {
+            "CONS-002" => Ok(self.check_confused_consent(segments, rule)),
+            "CONS-003" => Ok(self.check_consent_contradiction(segments, rule, state)),
+            "PAY-001" if metadata.call_type == "outbound_sales" => Ok(self.check_payment_before_terms(segments, rule, state)),
+            "REC-002" if metadata.call_type == "collections" => Ok(self.check_late_recording_notice(segments, rule, state)),
+            "DELIV-001" => Ok(self.check_rushed_disclosure(segments, rule)),
+            "DELIV-002" => Ok(self.check_no_pause(segments, rule)),
+            "DELIV-003" => Ok(self.check_undercut_disclosure(segments, rule)),
+            "MISREP-001" => Ok(self.check_unqualified_claim(segments, rule)),
+            "DISC-004" if metadata.call_type == "outbound_sales" => Ok(self.check_vague_seller(metadata, segments, rule)),
+            _ => Ok(None),
+        }
+    }
+    
+    /// CONS-002: the customer signals confusion (rule triggers) and the agent
+    /// then pushes for a yes (rule regex patterns) within the next few turns
+    fn check_confused_consent(&self, segments: &[TranscriptSegment], rule: &Rule) -> Option<Alert> {
+        let min_confidence = self.min_diarization_confidence();
+        let push_patterns: Vec<Regex> = rule.regex_patterns.iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
+        
+        for (i, segment) in segments.iter().enumerate() {
+            if !spoken_by(segment, "customer", min_confidence) {
+                continue;
+            }
+            let text_lower = segment.text.to_lowercase();
+            if !rule.triggers.iter().any(|t| text_lower.contains(&t.to_lowercase())) {
+                continue;
+            }
+            
+            let agent_pushed = segments[i + 1..].iter()
+                .take(CONFUSION_FOLLOW_UP_TURNS)
+                .filter(|s| spoken_by(s, "agent", min_confidence))
+                .any(|s| push_patterns.iter().any(|re| re.is_match(&s.text)));
+            
+            if agent_pushed {
+                return Some(build_alert(rule, 80, Evidence {
+                    quote: segment.text.clone(),
+                    start_char: segment.start_char,
+                    end_char: segment.end_char,
+                }));
+            }
+        }
+        
+        None
+    }
+    
+    /// CONS-003: a customer denial (rule triggers) after an earlier customer
+    /// agreement (rule regex patterns) on the same topic. A turn's topic comes
+    /// from its own words, or else the agent turn it answers; a denial with no
+    /// topic contradicts any earlier agreement. Statements are kept in the
+    /// conversation state so each turn is classified once.
+    fn check_consent_contradiction(
+        &self,
+        segments: &[TranscriptSegment],
+        rule: &Rule,
+        state: &mut ConversationState,
+    ) -> Option<Alert> {
+        let min_confidence = self.min_diarization_confidence();
+        let agree_patterns: Vec<Regex> = rule.regex_patterns.iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
+        
+        for (i, segment) in segments.iter().enumerate() {
+            if !spoken_by(segment, "customer", min_confidence) || state.consent_statements.iter().any(|s| s.segment_id == segment.id) {
+                continue;
+            }
+            let text_lower = segment.text.to_lowercase();
+            let topic = consent_topic(&text_lower).or_else(|| {
+                segments[..i].iter()
+                    .rev()
+                    .find(|s| spoken_by(s, "agent", min_confidence))
+                    .and_then(|s| consent_topic(&s.text.to_lowercase()))
+            });
+            
+            let affirmed = if rule.triggers.iter().any(|t| text_lower.contains(&t.to_lowercase())) {
+                false
+            } else if topic.is_some() && agree_patterns.iter().any(|re| re.is_match(&segment.text)) {
+                true
+            } else {
+                continue;
+            };
+            state.consent_statements.push(ConsentStatement {
+                segment_id: segment.id.clone(),
+                timestamp_ms: segment.timestamp_ms,
+                topic,
+                affirmed,
+            });
+        }
+        
+        let statements = &state.consent_statements;
+        let denial = statements.iter().find(|denial| {
+            !denial.affirmed && statements.iter().any(|s| {
+                s.affirmed
+                    && s.timestamp_ms < denial.timestamp_ms
+                    && (denial.topic.is_none() || s.topic == denial.topic)
+            })
+        })?;
+        let segment = segments.iter().find(|s| s.id == denial.segment_id)?;
+        
+        Some(build_alert(rule, 80, Evidence {
+            quote: segment.text.clone(),
+            start_char: segment.start_char,
+            end_char: segment.end_char,
+        }))
+    }
+    
+    /// PAY-001: an agent turn asking for payment (rule triggers or regex
+    /// patterns) before earlier agent turns have both described the product and
+    /// stated its cost. Once both are made, PAY-001's terms are recorded as
+    /// stated in the conversation state.
+    fn check_payment_before_terms(
+        &self,
+        segments: &[TranscriptSegment],
+        rule: &Rule,
+        state: &mut ConversationState,
+    ) -> Option<Alert> {
+        let payment_patterns: Vec<Regex> = rule.regex_patterns.iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
+        let product_re = Regex::new(PRODUCT_DESCRIPTION_PATTERN).ok()?;
+        let cost_re = Regex::new(COST_DISCLOSURE_PATTERN).ok()?;
+        
+        let min_confidence = self.min_diarization_confidence();
+        let (mut product_described, mut cost_disclosed) = (false, false);
+        for segment in segments.iter().filter(|s| spoken_by(s, "agent", min_confidence)) {
+            let text_lower = segment.text.to_lowercase();
+            let asks_payment = rule.triggers.iter().any(|t| text_lower.contains(&t.to_lowercase()))
+                || payment_patterns.iter().any(|re| re.is_match(&segment.text));
+            if asks_payment && !(product_described && cost_disclosed) {
+                return Some(build_alert(rule, 85, Evidence {
+                    quote: segment.text.clone(),
+                    start_char: segment.start_char,
+                    end_char: segment.end_char,
+                }));
+            }
+            
+            product_described |= product_re.is_match(&segment.text);
+            cost_disclosed |= cost_re.is_match(&segment.text);
+            state.disclosures.payment_terms_stated |= product_described && cost_disclosed;
+        }
+        
+        None
+    }
+    
+    /// REC-002: the agent's first recording notice (rule triggers or regex
+    /// patterns) comes after the customer's first substantive statement. No
+    /// notice at all is REC-001's concern.
+    fn check_late_recording_notice(
+        &self,
+        segments: &[TranscriptSegment],
+        rule: &Rule,
+        state: &mut ConversationState,
+    ) -> Option<Alert> {
+        let min_confidence = self.min_diarization_confidence();
+        let notice_patterns: Vec<Regex> = rule.regex_patterns.iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
+        
+        let notice = segments.iter().position(|s| {
+            let text_lower = s.text.to_lowercase();
+            spoken_by(s, "agent", min_confidence)
+                && (rule.triggers.iter().any(|t| text_lower.contains(&t.to_lowercase()))
+                    || notice_patterns.iter().any(|re| re.is_match(&s.text)))
+        })?;
+        state.disclosures.recording_disclosed = true;
+        
+        let statement = segments.iter().position(|s| {
+            spoken_by(s, "customer", min_confidence) && s.text.split_whitespace().count() >= SUBSTANTIVE_STATEMENT_WORDS
+        })?;
+        // A notice in an unlabeled turn can't come after a statement in the same turn
+        if statement >= notice {
+            return None;
+        }
+        
+        let segment = &segments[notice];
+        Some(build_alert(rule, 80, Evidence {
+            quote: segment.text.clone(),
+            start_char: segment.start_char,
+            end_char: segment.end_char,
+        }))
+    }
+    
+    /// DELIV-001: an agent turn containing a disclosure (rule regex patterns)
+    /// spoken faster than the WPM limit. Segments only carry start times, so a
+    /// turn runs until the next one starts; pauses make this err towards not firing.
+    fn check_rushed_disclosure(&self, segments: &[TranscriptSegment], rule: &Rule) -> Option<Alert> {
+        let max_wpm = self.settings.lock().unwrap().max_disclosure_wpm as f64;
+        let min_confidence = self.min_diarization_confidence();
+        let disclosure_patterns: Vec<Regex> = rule.regex_patterns.iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
+        
+        for pair in segments.windows(2) {
+            let (segment, next) = (&pair[0], &pair[1]);
+            if !spoken_by(segment, "agent", min_confidence) || !disclosure_patterns.iter().any(|re| re.is_match(&segment.text)) {
+                continue;
+            }
+            
+            let words = segment.text.split_whitespace().count();
+            let duration_ms = next.timestamp_ms.saturating_sub(segment.timestamp_ms);
+            if words < MIN_TIMED_DISCLOSURE_WORDS || duration_ms == 0 {
+                continue;
+            }
+            
+            let wpm = words as f64 * 60_000.0 / duration_ms as f64;
+            if wpm > max_wpm {
+                return Some(build_alert(rule, 70, Evidence {
+                    quote: segment.text.clone(),
+                    start_char: segment.start_char,
+                    end_char: segment.end_char,
+                }));
+            }
+        }
+        
+        None
+    }
+    
+    /// DELIV-002: after an agent turn that needs acknowledgment (rule regex
+    /// patterns), the agent keeps the floor past the time or turn limit before the
+    /// customer speaks. Medium at twice the time limit, Low otherwise.
+    fn check_no_pause(&self, segments: &[TranscriptSegment], rule: &Rule) -> Option<Alert> {
+        let limit_ms = self.max_monologue_secs() * 1000;
+        let min_confidence = self.min_diarization_confidence();
+        let ack_patterns: Vec<Regex> = rule.regex_patterns.iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
+        
+        for (i, segment) in segments.iter().enumerate() {
+            if !spoken_by(segment, "agent", min_confidence) || !ack_patterns.iter().any(|re| re.is_match(&segment.text)) {
+                continue;
+            }
+            
+            let agent_turns = segments[i..].iter()
+                .take_while(|s| spoken_by(s, "agent", min_confidence))
+                .count();
+            if agent_turns == 0 {
+                continue;
+            }
+            // Time runs until the customer speaks, or to the last agent turn if they never do
+            let floor_until = segments.get(i + agent_turns)
+                .unwrap_or_else(|| &segments[i + agent_turns - 1])
+                .timestamp_ms;
+            let held_ms = floor_until.saturating_sub(segment.timestamp_ms);
+            
+            if held_ms > limit_ms || agent_turns >= MAX_MONOLOGUE_TURNS {
+                let mut alert = build_alert(rule, 70, Evidence {
+                    quote: segment.text.clone(),
+                    start_char: segment.start_char,
+                    end_char: segment.end_char,
+                });
+                if held_ms < limit_ms * 2 {
+                    downgrade_alert(&mut alert, Severity::Low);
+                }
+                return Some(alert);
+            }
+        }
+        
+        None
+    }
+    
+    /// DELIV-003: an agent turn that distances the agent from a disclosure (rule
+    /// triggers), with a disclosure (rule regex patterns) in that turn or an
+    /// adjacent agent turn. Without a disclosure nearby, "I have to say this"
+    /// is just talk.
+    fn check_undercut_disclosure(&self, segments: &[TranscriptSegment], rule: &Rule) -> Option<Alert> {
+        let disclosure_patterns: Vec<Regex> = rule.regex_patterns.iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
+        let min_confidence = self.min_diarization_confidence();
+        let agent_turns: Vec<&TranscriptSegment> = segments.iter()
+            .filter(|s| spoken_by(s, "agent", min_confidence))
+            .collect();
+        
+        for (i, segment) in agent_turns.iter().enumerate() {
+            let text_lower = segment.text.to_lowercase();
+            if !rule.triggers.iter().any(|t| text_lower.contains(&t.to_lowercase())) {
+                continue;
+            }
+            
+            let first = i.saturating_sub(UNDERCUT_DISCLOSURE_TURNS);
+            let last = (i + UNDERCUT_DISCLOSURE_TURNS).min(agent_turns.len() - 1);
+            let nearby = &agent_turns[first..=last];
+            if nearby.iter().any(|s| disclosure_patterns.iter().any(|re| re.is_match(&s.text))) {
+                return Some(build_alert(rule, 80, Evidence {
+                    quote: segment.text.clone(),
+                    start_char: segment.start_char,
+                    end_char: segment.end_char,
+                }));
+            }
+        }
+        
+        None
+    }
+    
+    /// MISREP-001: an agent turn naming a specific savings or earnings figure
+    /// (rule regex patterns) with no hedge like "up to" or "results vary". The
+    /// quote is the matched claim, so it carries the figure.
+    fn check_unqualified_claim(&self, segments: &[TranscriptSegment], rule: &Rule) -> Option<Alert> {
+        let claim_patterns: Vec<Regex> = rule.regex_patterns.iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
+        let qualifier = Regex::new(CLAIM_QUALIFIER_PATTERN).ok()?;
+        let min_confidence = self.min_diarization_confidence();
+        
+        for segment in segments.iter().filter(|s| spoken_by(s, "agent", min_confidence)) {
+            let text_lower = segment.text.to_lowercase();
+            if !rule.triggers.iter().any(|t| text_lower.contains(&t.to_lowercase())) {
+                continue;
+            }
+            if qualifier.is_match(&segment.text) {
+                continue;
+            }
+            
+            let Some((re, caps)) = claim_patterns.iter().find_map(|re| re.captures(&segment.text).map(|c| (re, c))) else {
+                continue;
+            };
+            let mut alert = build_alert(rule, 85, match_evidence(segment, caps.get(0)?));
+            alert.captures = named_captures(re, &caps, &segment.text);
+            return Some(alert);
+        }
+        
+        None
+    }
+    
+    /// DISC-004: an agent turn naming only a vague affiliation (rule regex
+    /// patterns) with the metadata company name in neither that turn nor an
+    /// adjacent agent turn. Without a company name in the metadata the
+    /// affiliation alone fires, at lower confidence.
+    fn check_vague_seller(&self, metadata: &CallMetadata, segments: &[TranscriptSegment], rule: &Rule) -> Option<Alert> {
+        let vague_patterns: Vec<Regex> = rule.regex_patterns.iter()
+            .filter_map(|p| Regex::new(p).ok())
+            .collect();
+        let company_name = metadata.company_name.as_deref().filter(|n| !n.trim().is_empty());
+        let min_confidence = self.min_diarization_confidence();
+        let agent_turns: Vec<&TranscriptSegment> = segments.iter()
+            .filter(|s| spoken_by(s, "agent", min_confidence))
+            .collect();
+        
+        for (i, segment) in agent_turns.iter().enumerate() {
+            let Some(vague) = vague_patterns.iter().find_map(|re| re.find(&segment.text)) else {
+                continue;
+            };
+            let confidence = match company_name {
+                Some(name) => {
+                    let first = i.saturating_sub(VAGUE_SELLER_TURNS);
+                    let last = (i + VAGUE_SELLER_TURNS).min(agent_turns.len() - 1);
+                    if agent_turns[first..=last].iter().any(|s| phonetic::mentions_name(&s.text, name)) {
+                        continue;
+                    }
+                    80
+                }
+                None => VAGUE_SELLER_UNNAMED_CONFIDENCE,
+            };
+            return Some(build_alert(rule, confidence, match_evidence(segment, vague)));
+        }
+        
+        None
+    }
+    
+    /// Metadata-based rules, with the alert tagged with where the metadata came
+    /// from. Flags an agent typed in are less trustworthy than a verified DNC
+    /// scrub, so their alerts carry lower confidence.
     fn check_metadata_rule(
         &self,
         metadata: &CallMetadata,
         rule: &Rule,
+    ) -> Result<Option<Alert>, String> {
+        let alert = self.check_metadata_flags(metadata, rule)?;
+        Ok(alert.map(|mut alert| {
+            if metadata.metadata_source == "agent_entered" {
+                alert.confidence = alert.confidence.saturating_sub(AGENT_ENTERED_CONFIDENCE_PENALTY);
+            }
+            alert.metadata_source = Some(metadata.metadata_source.clone());
+            alert
+        }))
+    }
+    
+    fn check_metadata_flags(
+        &self,
+        metadata: &CallMetadata,
+        rule: &Rule,
     ) -> Result<Option<Alert>, String> {
         match rule.id.as_str() {
             "TIME-001" => {
-                // Would need actual time parsing - placeholder
-                Ok(None)
+                let Some(window) = self.calling_window_for(&metadata.call_type) else {
+                    return Ok(None);
+                };
+                let Some(local_time) = local_call_time(metadata) else {
+                    return Ok(None);
+                };
+                if (window.start_hour..window.end_hour).contains(&local_time.hour()) {
+                    return Ok(None);
+                }
+                
+                let mut alert = build_alert(rule, 90, Evidence {
+                    quote: format!(
+                        "Call placed at {} consumer local time ({})",
+                        local_time.format("%H:%M"),
+                        local_time.timezone()
+                    ),
+                    start_char: 0,
+                    end_char: 0,
+                });
+                
+                // Consent exempts the call from the TCPA's solicitation hours but not the TSR's
+                if metadata.has_prior_consent && window.consent_downgrades {
+                    downgrade_alert(&mut alert, Severity::Medium);
+                    alert.confidence = 70;
+                    alert.evidence.quote.push_str(" - prior express consent on file");
+                }
+                Ok(Some(alert))
             }
             "DNC-003" => {
                 if metadata.is_dnc_listed && !metadata.has_prior_consent {
-                    Ok(Some(Alert {
-                        id: uuid::Uuid::new_v4().to_string(),
-                        rule_id: rule.id.clone(),
-                        title: rule.title.clone(),
-                        severity: severity_to_string(&rule.severity),
-                        confidence: 95,
-                        evidence: Evidence {
-                            quote: "Number is on National DNC Registry (metadata flag)".to_string(),
-                            start_char: 0,
-                            end_char: 0,
-                        },
-                        why_it_matters: rule.why_it_matters.clone(),
-                        agent_fix_suggestion: rule.recommended_fix.clone(),
-                    }))
+                    Ok(Some(build_alert(rule, 95, Evidence {
+                        quote: "Number is on National DNC Registry (metadata flag)".to_string(),
+                        start_char: 0,
+                        end_char: 0,
+                    })))
+                } else {
+                    Ok(None)
+                }
+            }
+            "CONS-004" => {
+                // Consent on file was given by the number's previous subscriber, so it doesn't exempt the call
+                if metadata.number_reassigned && metadata.call_type == "outbound_sales" {
+                    Ok(Some(build_alert(rule, 95, Evidence {
+                        quote: "Number has been reassigned since consent was given (metadata flag)".to_string(),
+                        start_char: 0,
+                        end_char: 0,
+                    })))
                 } else {
                     Ok(None)
                 }
             }
             "PREC-001" => {
                 if metadata.is_prerecorded && !metadata.has_prior_consent {
-                    Ok(Some(Alert {
-                        id: uuid::Uuid::new_v4().to_string(),
-                        rule_id: rule.id.clone(),
-                        title: rule.title.clone(),
-                        severity: severity_to_string(&rule.severity),
-                        confidence: 95,
-                        evidence: Evidence {
-                            quote: "Using prerecorded/artificial voice without consent (metadata flag)".to_string(),
-                            start_char: 0,
-                            end_char: 0,
-                        },
-                        why_it_matters: rule.why_it_matters.clone(),
-                        agent_fix_suggestion: rule.recommended_fix.clone(),
-                    }))
+                    Ok(Some(build_alert(rule, 95, Evidence {
+                        quote: "Using prerecorded/artificial voice without consent (metadata flag)".to_string(),
+                        start_char: 0,
+                        end_char: 0,
+                    })))
                 } else {
                     Ok(None)
                 }
@@ -314,6 +1700,262 @@ impl ComplianceEvaluator {
     }
 }
 
+/// Build an alert for a rule with the rule's standard explanation and fix
+pub fn build_alert(rule: &Rule, confidence: u8, evidence: Evidence) -> Alert {
+    let severity = severity_to_string(&rule.severity);
+    Alert {
+        id: uuid::Uuid::new_v4().to_string(),
+        rule_id: rule.id.clone(),
+        title: rule.title.clone(),
+        priority: severity_priority(&severity),
+        severity,
+        confidence,
+        evidence,
+        why_it_matters: rule.why_it_matters.clone(),
+        agent_fix_suggestion: rule.recommended_fix.clone(),
+        segment_id: None,
+        display_order: 0,
+        timestamp_ms: None,
+        sources: vec![SOURCE_RULES.to_string()],
+        coaching: None,
+        metadata_source: None,
+        captures: HashMap::new(),
+    }
+}
+
+/// Attach each alert's full rule description and legal reference for training mode
+pub fn attach_coaching(alerts: &mut [Alert], rules: &RuleSet) {
+    for alert in alerts {
+        if let Some(rule) = rules.get_rule(&alert.rule_id) {
+            alert.coaching = Some(AlertCoaching {
+                description: rule.description.clone(),
+                legal_reference: rule.legal_reference.clone(),
+            });
+        }
+    }
+}
+
+/// Lower an alert's severity (and priority) without touching its rule
+fn downgrade_alert(alert: &mut Alert, severity: Severity) {
+    alert.severity = severity_to_string(&severity);
+    alert.priority = severity_priority(&alert.severity);
+}
+
+/// Convert the call start time into the consumer's local time zone. Returns
+/// `None` when either the start time or the time zone can't be interpreted.
+fn local_call_time(metadata: &CallMetadata) -> Option<DateTime<Tz>> {
+    let tz: Tz = metadata.caller_timezone.as_deref()?.parse().ok()?;
+    let utc = parse_call_start_time(&metadata.call_start_time)?;
+    Some(utc.with_timezone(&tz))
+}
+
+/// Parse `call_start_time` as RFC 3339, or `YYYY-MM-DD HH:MM:SS` taken as UTC
+pub fn parse_call_start_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|t| t.with_timezone(&Utc))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S").map(|t| t.and_utc()))
+        .ok()
+}
+
+/// Silence the rules `rule` supersedes for the rest of the session. A rule is
+/// only silenced if it is less severe than the alert just raised, so a
+/// misconfigured `suppresses` list can never hide an equally serious issue.
+fn suppress_superseded(rule: &Rule, alert: &Alert, rules: &RuleSet, state: &mut ConversationState) {
+    for suppressed_id in &rule.suppresses {
+        let less_severe = rules.rules.iter()
+            .find(|r| &r.id == suppressed_id)
+            .is_some_and(|r| severity_priority(&severity_to_string(&r.severity)) > alert.priority);
+        if less_severe {
+            state.suppressed_rules.insert(suppressed_id.clone());
+        }
+    }
+}
+
+/// Display priority for a severity: 1 (high) is shown first, 3 (low) last
+pub fn severity_priority(severity: &str) -> u8 {
+    match severity {
+        "high" => 1,
+        "medium" => 2,
+        _ => 3,
+    }
+}
+
+/// Sort alerts by priority, then confidence, then rule ID, and number them so
+/// every client renders them in the same order
+pub fn assign_display_order(alerts: &mut [Alert]) {
+    alerts.sort_by(|a, b| {
+        a.priority.cmp(&b.priority)
+            .then(b.confidence.cmp(&a.confidence))
+            .then(a.rule_id.cmp(&b.rule_id))
+    });
+    for (i, alert) in alerts.iter_mut().enumerate() {
+        alert.display_order = i as u32;
+    }
+}
+
+/// An alert raised under both rulesets with a different severity, confidence or title
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertChange {
+    pub before: Alert,
+    pub after: Alert,
+}
+
+/// How one call's alerts differ between the current and a candidate ruleset
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallAlertDiff {
+    pub call_id: String,
+    /// Raised only under the candidate ruleset
+    pub added: Vec<Alert>,
+    /// Raised only under the current ruleset
+    pub removed: Vec<Alert>,
+    pub changed: Vec<AlertChange>,
+    /// Alerts raised identically under both
+    pub unchanged: u32,
+}
+
+/// Compare a call's alerts under two rulesets. Alerts are paired by rule and
+/// evidence span, so the same rule firing on different text counts as one
+/// removed and one added.
+pub fn diff_alerts(call_id: &str, before: Vec<Alert>, after: Vec<Alert>) -> CallAlertDiff {
+    let mut diff = CallAlertDiff {
+        call_id: call_id.to_string(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+        unchanged: 0,
+    };
+    let mut after: Vec<Option<Alert>> = after.into_iter().map(Some).collect();
+    
+    for old in before {
+        let paired = after.iter_mut().find(|candidate| candidate.as_ref().is_some_and(|new| {
+            new.rule_id == old.rule_id
+                && new.evidence.start_char == old.evidence.start_char
+                && new.evidence.end_char == old.evidence.end_char
+        }));
+        match paired.and_then(Option::take) {
+            Some(new) if new.severity == old.severity && new.confidence == old.confidence && new.title == old.title => {
+                diff.unchanged += 1;
+            }
+            Some(new) => diff.changed.push(AlertChange { before: old, after: new }),
+            None => diff.removed.push(old),
+        }
+    }
+    diff.added = after.into_iter().flatten().collect();
+    diff
+}
+
+/// Collapse alerts for the same rule with overlapping evidence (e.g. one hit from
+/// each engine) into one, keeping the higher-confidence alert and the union of
+/// their sources. Metadata alerts have empty spans and merge per rule.
+pub fn dedup_alerts(alerts: &mut Vec<Alert>) {
+    let mut merged: Vec<Alert> = Vec::with_capacity(alerts.len());
+    
+    for alert in alerts.drain(..) {
+        let existing = merged.iter_mut().find(|m| {
+            m.rule_id == alert.rule_id && spans_overlap(&m.evidence, &alert.evidence)
+        });
+        match existing {
+            Some(existing) => {
+                let mut sources = std::mem::take(&mut existing.sources);
+                for source in &alert.sources {
+                    if !sources.contains(source) {
+                        sources.push(source.clone());
+                    }
+                }
+                if alert.confidence > existing.confidence {
+                    *existing = alert;
+                }
+                existing.sources = sources;
+            }
+            None => merged.push(alert),
+        }
+    }
+    
+    *alerts = merged;
+    assign_display_order(alerts);
+}
+
+fn spans_overlap(a: &Evidence, b: &Evidence) -> bool {
+    let a_empty = a.end_char <= a.start_char;
+    let b_empty = b.end_char <= b.start_char;
+    if a_empty || b_empty {
+        return a_empty && b_empty;
+    }
+    a.start_char < b.end_char && b.start_char < a.end_char
+}
+
+/// Rules about what one party said, matched only in that party's lines
+fn rule_speaker(rule_id: &str) -> Option<&'static str> {
+    match rule_id {
+        "VULN-001" => Some("customer"),
+        _ => None,
+    }
+}
+
+/// Whether the transcript line containing byte offset `pos` may be
+/// `speaker`'s: labelled as theirs, or not labelled at all
+fn said_by(transcript: &str, pos: usize, speaker: &str) -> bool {
+    let line_start = transcript[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = transcript[pos..].find('\n').map_or(transcript.len(), |i| pos + i);
+    let (label, _) = transcript::split_speaker_prefix(&transcript[line_start..line_end]);
+    label == speaker || label == "unknown"
+}
+
+/// The transcript lines that may be `speaker`'s (see `said_by`), joined
+fn lines_by(transcript: &str, speaker: &str) -> String {
+    transcript.lines()
+        .filter(|line| {
+            let (label, _) = transcript::split_speaker_prefix(line);
+            label == speaker || label == "unknown"
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Rules evaluated turn-by-turn from segments rather than the flat transcript
+fn is_segment_rule(rule: &Rule) -> bool {
+    matches!(rule.id.as_str(), "PAY-001" | "CONS-002" | "CONS-003" | "REC-002" | "DELIV-001" | "DELIV-002" | "DELIV-003" | "DISC-004" | "MISREP-001")
+}
+
+/// Evidence for a regex match within a segment's text. The segment's span
+/// covers its "speaker: " prefix, so the text is the end of it.
+fn match_evidence(segment: &TranscriptSegment, m: regex::Match) -> Evidence {
+    let text_start = segment.end_char.saturating_sub(segment.text.len());
+    Evidence {
+        quote: m.as_str().to_string(),
+        start_char: text_start + m.start(),
+        end_char: text_start + m.end(),
+    }
+}
+
+/// Named capture groups that took part in a match, keyed by group name.
+/// `source` is the text the offsets index (the original for a lowercased
+/// haystack), so values keep their original case.
+fn named_captures(re: &Regex, caps: &Captures, source: &str) -> HashMap<String, String> {
+    re.capture_names()
+        .flatten()
+        .filter_map(|name| {
+            let group = caps.name(name)?;
+            let (start, end) = (floor_char_boundary(source, group.start()), floor_char_boundary(source, group.end()));
+            Some((name.to_string(), source[start..end].trim().to_string()))
+        })
+        .collect()
+}
+
+/// The first consent topic named in lowercase text
+fn consent_topic(text_lower: &str) -> Option<&'static str> {
+    CONSENT_TOPICS.iter()
+        .find(|(_, phrases)| phrases.iter().any(|p| text_lower.contains(p)))
+        .map(|(topic, _)| *topic)
+}
+
+/// Whether a segment counts as `speaker`'s for rule matching. A segment whose
+/// diarization confidence is below `min_confidence` may be mislabeled, so it
+/// counts as either speaker's.
+fn spoken_by(segment: &TranscriptSegment, speaker: &str, min_confidence: f64) -> bool {
+    segment.speaker == speaker || segment.diarization_confidence.is_some_and(|c| c < min_confidence)
+}
+
 fn severity_to_string(severity: &Severity) -> String {
     match severity {
         Severity::Low => "low".to_string(),
@@ -321,3 +1963,228 @@ fn severity_to_string(severity: &Severity) -> String {
         Severity::High => "high".to_string(),
     }
 }
+
+/// Note where a disclosure was first heard. `start..end` are byte offsets into
+/// the evaluated window.
+fn record_milestone(rule: &Rule, transcript: &str, start: usize, end: usize, state: &mut ConversationState) {
+    if state.milestones.iter().any(|m| m.rule_id == rule.id) {
+        return;
+    }
+    let (start, end) = (floor_char_boundary(transcript, start), floor_char_boundary(transcript, end));
+    state.milestones.push(DisclosureMilestone {
+        rule_id: rule.id.clone(),
+        label: disclosure_label(rule).to_string(),
+        quote: transcript[start..end].trim().to_string(),
+        start_char: state.window_start.1 + byte_to_utf16(transcript, start),
+        end_char: state.window_start.1 + byte_to_utf16(transcript, end),
+        timestamp_ms: None,
+    });
+}
+
+/// Short name for the disclosure a rule looks for, as shown on the timeline
+/// and checklist
+fn disclosure_label(rule: &Rule) -> &str {
+    match rule.id.as_str() {
+        "DISC-001" => "Seller identified",
+        "DISC-002" => "Sales purpose stated",
+        "DISC-003" => "Product described",
+        "PAY-001" => "Product and cost stated",
+        "IDENT-001" => "Callback number provided",
+        "REC-001" => "Recording disclosed",
+        "PREC-002" => "Opt-out instructions given",
+        _ => rule.title.as_str(),
+    }
+}
+
+/// Mark the disclosure a positive-detection rule looks for as made, noting
+/// whether it was first made (at byte offset `start` into the evaluated
+/// window) after the pitch began.
+/// A disclosure inside the grace window is never late.
+/// Returns `false` for rules that alert on a match rather than record a disclosure.
+fn record_disclosure(rule_id: &str, start: usize, state: &mut ConversationState) -> bool {
+    let start = state.window_start.0 + start;
+    let past_grace = state.grace_end.is_some_and(|end| start >= end);
+    let after_pitch = past_grace && state.pitch_start.is_some_and(|pitch| pitch < start);
+    let disclosures = &mut state.disclosures;
+    let flag = match rule_id {
+        "DISC-001" => &mut disclosures.seller_identified,
+        "DISC-002" => &mut disclosures.sales_purpose_stated,
+        "DISC-003" => &mut disclosures.product_described,
+        "IDENT-001" => &mut disclosures.callback_provided,
+        "REC-001" => &mut disclosures.recording_disclosed,
+        "PREC-002" => &mut disclosures.opt_out_provided,
+        _ => return false,
+    };
+    if !*flag {
+        *flag = true;
+        if after_pitch {
+            disclosures.made_late.insert(rule_id.to_string());
+        }
+    }
+    true
+}
+
+/// Quote for a match at `start..end`: the match plus `context` bytes after it,
+/// or with `sentence_quotes`, the full sentence(s) containing it
+fn match_quote(transcript: &str, start: usize, end: usize, context: usize, sentence_quotes: bool) -> String {
+    if sentence_quotes {
+        let (sentence_start, sentence_end) = sentence_bounds(transcript, start, end);
+        if sentence_end - sentence_start <= MAX_SENTENCE_QUOTE_BYTES {
+            return transcript[sentence_start..sentence_end].trim().to_string();
+        }
+    }
+    let context_end = floor_char_boundary(transcript, end + context);
+    transcript[start..context_end].trim().to_string()
+}
+
+/// Byte range of the sentence(s) covering `start..end`. Sentences end at
+/// `.`, `!`, `?` or a line break; a leading `speaker:` label is left out.
+fn sentence_bounds(transcript: &str, start: usize, end: usize) -> (usize, usize) {
+    let is_boundary = |c: char| matches!(c, '.' | '!' | '?' | '\n');
+    
+    let line_start = transcript[..start].rfind('\n').map_or(0, |i| i + 1);
+    let mut sentence_start = transcript[..start]
+        .rfind(is_boundary)
+        .map_or(0, |i| i + 1)
+        .max(line_start);
+    if sentence_start == line_start {
+        if let Some(colon) = transcript[line_start..start].find(": ") {
+            sentence_start = line_start + colon + 2;
+        }
+    }
+    
+    let sentence_end = match transcript[end..].find(is_boundary) {
+        Some(i) if transcript[end + i..].starts_with('\n') => end + i,
+        Some(i) => end + i + 1,
+        None => transcript.len(),
+    };
+    (sentence_start, sentence_end)
+}
+
+/// Largest char boundary of `text` at or below `index`, clamped to its length
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Convert a byte offset into `text` to a UTF-16 code unit offset
+pub fn byte_to_utf16(text: &str, byte: usize) -> usize {
+    text[..floor_char_boundary(text, byte)].encode_utf16().count()
+}
+
+/// Convert a UTF-16 code unit offset into `text` to a byte offset, clamped to the text
+fn utf16_to_byte(text: &str, unit: usize) -> usize {
+    let mut units = 0;
+    for (byte, c) in text.char_indices() {
+        if units >= unit {
+            return byte;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+/// Rewrite the byte-offset evidence produced by matching into UTF-16 offsets
+fn convert_offsets_to_utf16(transcript: &str, alerts: &mut [Alert]) {
+    for alert in alerts {
+        alert.evidence.start_char = byte_to_utf16(transcript, alert.evidence.start_char);
+        alert.evidence.end_char = byte_to_utf16(transcript, alert.evidence.end_char);
+    }
+}
+
+/// Make LLM-reported evidence offsets valid UTF-16 offsets into the transcript.
+/// Models are unreliable at counting, so the quote is located in the transcript
+/// when possible; otherwise the reported offsets are clamped into range.
+pub fn align_llm_evidence(transcript: &str, evidence: &mut Evidence) {
+    let quote = evidence.quote.trim();
+    let found = if quote.is_empty() {
+        None
+    } else {
+        transcript.find(quote).or_else(|| {
+            let (transcript_lower, quote_lower) = (transcript.to_lowercase(), quote.to_lowercase());
+            // Lowercase offsets only map back when lowercasing kept byte lengths
+            (transcript_lower.len() == transcript.len())
+                .then(|| transcript_lower.find(&quote_lower))
+                .flatten()
+        })
+    };
+    
+    if let Some(byte) = found {
+        evidence.start_char = byte_to_utf16(transcript, byte);
+        evidence.end_char = byte_to_utf16(transcript, byte + quote.len());
+        return;
+    }
+    
+    let len = transcript.encode_utf16().count();
+    let start = evidence.start_char.min(len);
+    let end = evidence.end_char.clamp(start, len);
+    // Snap to code point boundaries so a highlight never splits a surrogate pair
+    evidence.start_char = byte_to_utf16(transcript, utf16_to_byte(transcript, start));
+    evidence.end_char = byte_to_utf16(transcript, utf16_to_byte(transcript, end));
+}
+
+/// Find the segment containing a matched span. Metadata alerts carry an empty
+/// span and are not attributed to any segment.
+fn segment_for_span(segments: &[TranscriptSegment], start: usize, end: usize) -> Option<&TranscriptSegment> {
+    if end <= start {
+        return None;
+    }
+    segments.iter().find(|seg| start >= seg.start_char && start < seg.end_char)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(id: &str, speaker: &str, text: &str, timestamp_ms: u64) -> TranscriptSegment {
+        TranscriptSegment {
+            id: id.to_string(),
+            speaker: speaker.to_string(),
+            text: text.to_string(),
+            timestamp_ms,
+            start_char: 0,
+            end_char: 0,
+            diarization_confidence: None,
+        }
+    }
+
+    #[test]
+    fn segment_evidence_after_non_ascii_text() {
+        let metadata = CallMetadata {
+            call_id: "call-1".to_string(),
+            agent_id: "agent-1".to_string(),
+            agent_name: "Sam".to_string(),
+            call_start_time: "2026-01-16T15:00:00Z".to_string(),
+            caller_timezone: None,
+            customer_phone: None,
+            is_dnc_listed: false,
+            has_prior_consent: true,
+            is_prerecorded: false,
+            call_type: "outbound_sales".to_string(),
+            number_reassigned: false,
+            consumer_state: None,
+            campaign_id: None,
+            company_name: None,
+            metadata_source: "unknown".to_string(),
+            tenant_id: None,
+        };
+        let mut segments = vec![
+            segment("s1", "customer", "Who’s this? It’s €20 a month already.", 0),
+            segment("s2", "agent", "Hi, I’m calling on behalf of your current provider about your bill…", 1_000),
+            segment("s3", "agent", "Honestly you’ll save $500 a month, that’s guaranteed.", 2_000),
+        ];
+        let rules = RuleSet::load_default().unwrap();
+        let output = ComplianceEvaluator::new().evaluate_segments(&metadata, &mut segments, &rules).unwrap();
+        
+        let transcript: Vec<u16> = transcript::assemble_transcript(&mut segments.clone()).encode_utf16().collect();
+        for rule_id in ["MISREP-001", "DISC-004"] {
+            let alert = output.alerts.iter().find(|a| a.rule_id == rule_id)
+                .unwrap_or_else(|| panic!("{} did not fire", rule_id));
+            let quoted = String::from_utf16(&transcript[alert.evidence.start_char..alert.evidence.end_char]).unwrap();
+            assert_eq!(quoted, alert.evidence.quote, "{} evidence offsets", rule_id);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::database::RuleAlertCount;
+use crate::locale::csv_fields;
+use crate::rules::RuleSet;
+
+/// Penalty per violation, in US dollars
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PenaltyRange {
+    pub min: f64,
+    pub max: f64,
+}
+
+/// TCPA private right of action: $500 per violation, trebled to $1,500 if willful
+const TCPA_DAMAGES: PenaltyRange = PenaltyRange { min: 500.0, max: 1_500.0 };
+
+/// TSR civil penalty cap per violation (FTC, as adjusted for inflation in 2025)
+const TSR_CIVIL_PENALTY: PenaltyRange = PenaltyRange { min: 0.0, max: 53_088.0 };
+
+/// Two-party consent statutes, e.g. Cal. Penal Code § 637.2
+const RECORDING_DAMAGES: PenaltyRange = PenaltyRange { min: 0.0, max: 5_000.0 };
+
+const NO_PENALTY: PenaltyRange = PenaltyRange { min: 0.0, max: 0.0 };
+
+/// Statutory default range for a rule. Custom rules have no default and count
+/// for nothing unless a penalty table lists them.
+pub fn statutory_penalty(rule_id: &str) -> PenaltyRange {
+    match rule_id {
+        "TIME-001" | "DNC-001" | "DNC-002" | "DNC-003" | "CONS-001" | "CONS-004" | "PREC-001" | "PREC-002" => TCPA_DAMAGES,
+        "DISC-001" | "DISC-002" | "DISC-003" | "DISC-004" | "PAY-001" | "CONS-002" | "CONS-003" | "IDENT-001" | "DELIV-001" | "DELIV-002" | "DELIV-003" | "MISREP-001" => TSR_CIVIL_PENALTY,
+        "REC-001" | "REC-002" => RECORDING_DAMAGES,
+        _ => NO_PENALTY,
+    }
+}
+
+/// One rule's row in the effective penalty table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PenaltyTableEntry {
+    pub rule_id: String,
+    pub min: f64,
+    pub max: f64,
+    /// Whether the range comes from an imported table rather than the statutory default
+    pub custom: bool,
+}
+
+/// Every rule's penalty range: imported values where present, statutory
+/// defaults otherwise. Imported rules missing from the ruleset are included.
+pub fn effective_table(rules: &RuleSet, custom: &HashMap<String, PenaltyRange>) -> Vec<PenaltyTableEntry> {
+    let mut entries: Vec<PenaltyTableEntry> = rules.rules.iter()
+        .map(|r| r.id.as_str())
+        .chain(custom.keys().map(|id| id.as_str()).filter(|id| !rules.rules.iter().any(|r| r.id == *id)))
+        .map(|rule_id| {
+            let (range, is_custom) = match custom.get(rule_id) {
+                Some(range) => (*range, true),
+                None => (statutory_penalty(rule_id), false),
+            };
+            PenaltyTableEntry {
+                rule_id: rule_id.to_string(),
+                min: range.min,
+                max: range.max,
+                custom: is_custom,
+            }
+        })
+        .collect();
+    entries.sort_by(|a, b| a.rule_id.cmp(&b.rule_id));
+    entries
+}
+
+/// Estimated exposure for one rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleExposure {
+    pub rule_id: String,
+    pub violations: u32,
+    pub min: f64,
+    pub max: f64,
+    pub custom: bool,
+}
+
+/// Estimated financial exposure, counting each alert as one violation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposureEstimate {
+    pub min_total: f64,
+    pub max_total: f64,
+    pub by_rule: Vec<RuleExposure>,
+}
+
+/// Multiply alert counts by each rule's penalty range
+pub fn estimate_exposure(alerts_by_rule: &[RuleAlertCount], custom: &HashMap<String, PenaltyRange>) -> ExposureEstimate {
+    let by_rule: Vec<RuleExposure> = alerts_by_rule.iter()
+        .map(|count| {
+            let (range, is_custom) = match custom.get(&count.rule_id) {
+                Some(range) => (*range, true),
+                None => (statutory_penalty(&count.rule_id), false),
+            };
+            RuleExposure {
+                rule_id: count.rule_id.clone(),
+                violations: count.count,
+                min: range.min * count.count as f64,
+                max: range.max * count.count as f64,
+                custom: is_custom,
+            }
+        })
+        .collect();
+
+    ExposureEstimate {
+        min_total: by_rule.iter().map(|r| r.min).sum(),
+        max_total: by_rule.iter().map(|r| r.max).sum(),
+        by_rule,
+    }
+}
+
+/// Parse a penalty table read from `path`, as `.csv` (`rule_id,min,max` rows, header optional;
+/// amounts with a thousands separator must be quoted, e.g. `"$1,500"`) or `.json` (`{"DNC-001": {"min": 500, "max": 1500}}`)
+pub fn parse_penalty_table(path: &Path, content: &str) -> Result<HashMap<String, PenaltyRange>, String> {
+    let is_json = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("json"));
+
+    let table = if is_json {
+        serde_json::from_str::<HashMap<String, PenaltyRange>>(content)
+            .map_err(|e| format!("Invalid penalty table JSON: {}", e))?
+    } else {
+        parse_csv(content)?
+    };
+
+    for (rule_id, range) in &table {
+        validate_range(rule_id, range)?;
+    }
+    Ok(table)
+}
+
+fn parse_csv(content: &str) -> Result<HashMap<String, PenaltyRange>, String> {
+    let mut table = HashMap::new();
+
+    for (i, line) in content.trim_start_matches('\u{feff}').lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<String> = csv_fields(line, ',').iter().map(|f| f.trim().to_string()).collect();
+        if i == 0 && fields.first().is_some_and(|f| f.eq_ignore_ascii_case("rule_id")) {
+            continue;
+        }
+        let [rule_id, min, max] = &fields[..] else {
+            return Err(format!("Penalty table line {}: expected rule_id,min,max", i + 1));
+        };
+        let parse = |value: &str| {
+            value.replace(['$', ','], "").parse::<f64>()
+                .map_err(|_| format!("Penalty table line {}: invalid amount '{}'", i + 1, value))
+        };
+        table.insert(rule_id.clone(), PenaltyRange { min: parse(min)?, max: parse(max)? });
+    }
+
+    Ok(table)
+}
+
+fn validate_range(rule_id: &str, range: &PenaltyRange) -> Result<(), String> {
+    if rule_id.trim().is_empty() {
+        return Err("Penalty table has an entry with no rule_id".to_string());
+    }
+    if !range.min.is_finite() || !range.max.is_finite() || range.min < 0.0 || range.max < 0.0 {
+        return Err(format!("Penalty for {} must be a non-negative amount", rule_id));
+    }
+    if range.min > range.max {
+        return Err(format!("Penalty for {}: min {} is greater than max {}", rule_id, range.min, range.max));
+    }
+    Ok(())
+}
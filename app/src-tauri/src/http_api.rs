@@ -0,0 +1,192 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use crate::{AppState, CallMetadata, EvaluateOptions};
+
+/// Environment variable overriding the address the HTTP API listens on
+pub const HTTP_ADDR_ENV: &str = "WHISPERWIRE_HTTP_ADDR";
+
+/// Localhost only unless configured otherwise; the API has no authentication
+pub const DEFAULT_HTTP_ADDR: &str = "127.0.0.1:7878";
+
+/// Requests with larger headers are rejected
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+
+/// Requests with larger bodies are rejected; a long call transcript is well under this
+const MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+
+/// A client that hasn't sent its whole request by then is answered with 408,
+/// so a stalled connection can't hold its task open forever
+const REQUEST_READ_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Body of `POST /evaluate`: the same inputs as the `evaluate_transcript` command
+#[derive(Debug, Deserialize)]
+struct EvaluateRequest {
+    metadata: CallMetadata,
+    transcript: String,
+    #[serde(default)]
+    use_llm: bool,
+    #[serde(flatten)]
+    options: EvaluateOptions,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+/// Address from `WHISPERWIRE_HTTP_ADDR`, or localhost on the default port
+pub fn bind_addr() -> Result<SocketAddr, String> {
+    let addr = std::env::var(HTTP_ADDR_ENV).unwrap_or_else(|_| DEFAULT_HTTP_ADDR.to_string());
+    addr.parse().map_err(|e| format!("Invalid {} '{}': {}", HTTP_ADDR_ENV, addr, e))
+}
+
+/// Serve the HTTP API until the app exits. Each connection carries one
+/// request, answered with JSON and closed.
+pub async fn serve(app: AppHandle, addr: SocketAddr) {
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("Failed to start HTTP API on {}: {}", addr, e);
+            return;
+        }
+    };
+    log::info!("HTTP API listening on {}", addr);
+    
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let app = app.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(app, stream).await {
+                        log::warn!("HTTP API connection from {} failed: {}", peer, e);
+                    }
+                });
+            }
+            Err(e) => log::warn!("HTTP API accept failed: {}", e),
+        }
+    }
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream) -> std::io::Result<()> {
+    let request = tokio::time::timeout(REQUEST_READ_TIMEOUT, read_request(&mut stream))
+        .await
+        .unwrap_or(Ok(Err(408)))?;
+    let (status, body) = match request {
+        Ok((method, path, body)) => route(&app, &method, &path, &body).await,
+        Err(status) => (status, error_json(status_reason(status))),
+    };
+    
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        status_reason(status),
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+async fn route(app: &AppHandle, method: &str, path: &str, body: &[u8]) -> (u16, String) {
+    match (method, path) {
+        ("POST", "/evaluate") => {
+            let request: EvaluateRequest = match serde_json::from_slice(body) {
+                Ok(request) => request,
+                Err(e) => return (400, error_json(&format!("Invalid request body: {}", e))),
+            };
+            let result = crate::evaluate_transcript(
+                app.clone(),
+                app.state::<AppState>(),
+                request.metadata,
+                request.transcript,
+                request.use_llm,
+                Some(request.options),
+            ).await;
+            match result {
+                Ok(result) => (200, serde_json::to_string(&result).unwrap_or_default()),
+                Err(e) => (422, error_json(&e)),
+            }
+        }
+        (_, "/evaluate") => (405, error_json(status_reason(405))),
+        _ => (404, error_json(status_reason(404))),
+    }
+}
+
+/// Read one request: method, path and body. Malformed or oversized requests
+/// give the status to answer with instead. Bodies must come with a
+/// `Content-Length`; chunked transfer encoding isn't supported.
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Result<(String, String, Vec<u8>), u16>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 8192];
+    let header_end = loop {
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return Ok(Err(431));
+        }
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(Err(400));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+    
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (Some(method), Some(path)) = (request_line.next(), request_line.next()) else {
+        return Ok(Err(400));
+    };
+    let headers: Vec<(&str, &str)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+    if headers.iter().any(|(name, _)| name.eq_ignore_ascii_case("transfer-encoding")) {
+        return Ok(Err(501));
+    }
+    let content_length = headers.iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .map(|(_, value)| value.parse::<usize>());
+    let content_length = match content_length {
+        Some(Ok(len)) if len > MAX_BODY_BYTES => return Ok(Err(413)),
+        Some(Ok(len)) => len,
+        Some(Err(_)) => return Ok(Err(400)),
+        None => 0,
+    };
+    
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(Err(400));
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+    
+    Ok(Ok((method.to_string(), path.to_string(), body)))
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::to_string(&ErrorBody { error: message.to_string() }).unwrap_or_default()
+}
+
+fn status_reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        408 => "Request Timeout",
+        413 => "Payload Too Large",
+        422 => "Unprocessable Entity",
+        431 => "Request Header Fields Too Large",
+        501 => "Not Implemented",
+        _ => "Error",
+    }
+}
@@ -2,24 +2,336 @@ mod database;
 mod rules;
 mod evaluator;
 mod llm;
+mod transcript;
+mod exposure;
+mod locale;
+mod remote_rules;
+mod wfm;
+mod phonetic;
+mod scenarios;
+mod corpus;
+mod dnc;
+mod semantic;
+#[cfg(feature = "http-api")]
+mod http_api;
 
 use serde::{Deserialize, Serialize};
-use tauri::State;
-use std::sync::Mutex;
+use tauri::{ipc::Channel, AppHandle, Emitter, Manager, RunEvent, State};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, RwLock as StdRwLock};
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use evaluator::{align_llm_evidence, attach_coaching, call_key, CallKey, dedup_alerts, severity_priority, SOURCE_LLM};
+use locale::ExportLocale;
 
-pub use database::Database;
+pub use database::{Database, TenantDatabases, TimelineEvent};
+pub use transcript::SegmentBuffer;
 pub use rules::{RuleSet, Rule, RuleCategory};
-pub use evaluator::{ComplianceEvaluator, Alert, Evidence, SuggestedLine, EvaluationOutput};
-pub use llm::LlmClient;
+pub use evaluator::{ComplianceEvaluator, Alert, AlertTextOverride, CallingWindow, AlertChange, CallAlertDiff, ChecklistItem, ChecklistStatus, DisclosureMilestone, Evidence, PriorityPolicy, SuggestedLine, EvaluationOutput, LiveRisk};
+pub use exposure::{ExposureEstimate, PenaltyRange, PenaltyTableEntry};
+pub use wfm::{WfmColumn, WfmFormat};
+pub use corpus::{CorpusCase, ModelCorpusReport, RuleScore};
+pub use dnc::{DncListFormat, DncLoadResult};
+pub use llm::{ConnectionStatus, LlmClient, LlmOptions, LlmStabilityReport, LlmWarmup, PromptPreview};
 
 /// Application state managed by Tauri
 pub struct AppState {
-    pub db: Mutex<Database>,
-    pub rules: RuleSet,
+    pub db: TenantDatabases,
+    pub rules: StdRwLock<RuleSet>,
     pub evaluator: ComplianceEvaluator,
     pub llm: RwLock<LlmClient>,
     pub llm_enabled: Mutex<bool>,
+    /// Debounce state per call and evaluation options, for chatty frontends
+    pub recent_evaluations: Mutex<HashMap<EvaluationKey, RecentEvaluation>>,
+    pub evaluation_debounce: Mutex<Duration>,
+    /// Store customer phone numbers as keyed hashes instead of plain text
+    pub hash_phone_numbers: Mutex<bool>,
+    pub semantic: semantic::SemanticMatcher,
+    /// Longer transcripts are cut to their most recent text for the regex
+    /// pass and refused by the LLM
+    pub max_transcript_chars: Mutex<usize>,
+    pub llm_fallback_policy: Mutex<LlmFallbackPolicy>,
+    /// Set after an LLM failure so the degradation event is only emitted once
+    pub llm_degraded: Mutex<bool>,
+    /// Practice calls run in training mode, with when each was last evaluated;
+    /// nothing about them is written to the database
+    pub training_calls: Mutex<HashMap<CallKey, Instant>>,
+    /// When the Ollama connection was last re-checked after going away
+    pub llm_last_reconnect: Mutex<Option<Instant>>,
+    /// Imported per-rule penalty ranges; rules not listed use statutory defaults
+    pub penalty_table: Mutex<HashMap<String, PenaltyRange>>,
+    /// Streamed segments per call, reordered by timestamp before evaluation
+    pub segment_buffers: Mutex<HashMap<CallKey, SegmentBuffer>>,
+    pub reorder_window: Mutex<Duration>,
+    pub escalation_policy: Mutex<EscalationPolicy>,
+    pub ack_sla: Mutex<AckSla>,
+    /// WFM export column mapping; `None` uses the default layout
+    pub wfm_columns: Mutex<Option<Vec<WfmColumn>>>,
+    /// Calls with a session in progress, ended on shutdown
+    pub open_calls: Mutex<HashSet<CallKey>>,
+}
+
+impl AppState {
+    fn is_training_call(&self, tenant_id: Option<&str>, call_id: &str) -> bool {
+        self.training_calls.lock().unwrap().contains_key(&call_key(tenant_id, call_id))
+    }
+    
+    /// Mark a call as a training call, returning whether it wasn't one already.
+    /// Training calls evaluated without a session are never ended, so ones left
+    /// idle for `TRAINING_CALL_IDLE` are forgotten here.
+    fn mark_training_call(&self, tenant_id: Option<&str>, call_id: &str) -> bool {
+        let open_calls = self.open_calls.lock().unwrap();
+        let mut training_calls = self.training_calls.lock().unwrap();
+        training_calls.retain(|key, last_used| {
+            last_used.elapsed() < TRAINING_CALL_IDLE || open_calls.contains(key)
+        });
+        training_calls.insert(call_key(tenant_id, call_id), Instant::now()).is_none()
+    }
+}
+
+/// How long a training call evaluated without a session is remembered after
+/// its last evaluation
+const TRAINING_CALL_IDLE: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// `settings` table key for whether customer phone numbers are stored hashed
+const HASH_PHONE_NUMBERS_SETTING: &str = "hash_phone_numbers";
+
+/// Default window within which repeated evaluations of a call are collapsed
+pub const DEFAULT_EVALUATION_DEBOUNCE_MS: u64 = 250;
+
+/// Default cap on the transcript `evaluate_transcript` scores, roughly three
+/// hours of speech. Longer input is usually a runaway STT feed.
+pub const DEFAULT_MAX_TRANSCRIPT_CHARS: usize = 200_000;
+
+/// What `evaluate_transcript` does when an LLM evaluation fails
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmFallbackPolicy {
+    /// Fall back to rules-only evaluation straight away
+    #[default]
+    Silent,
+    /// Retry the LLM once, then fall back to rules-only evaluation
+    RetryOnce,
+}
+
+/// When an agent's repeat violations of a rule are escalated as they're stored.
+/// An alert is escalated one severity level (High stays High) and flagged
+/// once the agent already has `threshold` earlier calls with the same rule
+/// within `window_days`. Off by default.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EscalationPolicy {
+    pub enabled: bool,
+    pub threshold: u32,
+    pub window_days: u32,
+}
+
+impl EscalationPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.threshold == 0 || self.window_days == 0 {
+            return Err("Escalation threshold and window_days must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for EscalationPolicy {
+    fn default() -> Self {
+        EscalationPolicy {
+            enabled: false,
+            threshold: 2,
+            window_days: 30,
+        }
+    }
+}
+
+/// Target time, per severity, for an agent to acknowledge a live alert
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AckSla {
+    pub high_secs: u32,
+    pub medium_secs: u32,
+    pub low_secs: u32,
+}
+
+impl AckSla {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.high_secs == 0 || self.medium_secs == 0 || self.low_secs == 0 {
+            return Err("Acknowledgment SLA targets must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for AckSla {
+    fn default() -> Self {
+        AckSla {
+            high_secs: 10,
+            medium_secs: 30,
+            low_secs: 60,
+        }
+    }
+}
+
+/// Event emitted when LLM evaluation first fails and rules-only takes over
+pub const LLM_DEGRADED_EVENT: &str = "llm-degraded";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LlmDegradedEvent {
+    pub call_id: String,
+    pub reason: String,
+}
+
+/// Event emitted with a fresh `LlmStatus` whenever the connection is re-checked
+/// automatically, e.g. after Ollama restarts mid-shift
+pub const LLM_STATUS_EVENT: &str = "llm-status";
+
+/// Event emitted when corrected call metadata raises new alerts
+pub const METADATA_ALERTS_EVENT: &str = "metadata-alerts";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetadataAlertsEvent {
+    pub call_id: String,
+    pub alerts: Vec<Alert>,
+}
+
+/// Event emitted for every alert written to the database, for live wallboards
+pub const ALERT_STORED_EVENT: &str = "alert-stored";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertStoredEvent {
+    pub tenant_id: Option<String>,
+    pub alert: database::StoredAlert,
+}
+
+fn emit_alert_stored(app: &AppHandle, tenant_id: Option<String>, alert: database::StoredAlert) {
+    if let Err(e) = app.emit(ALERT_STORED_EVENT, AlertStoredEvent { tenant_id, alert }) {
+        log::warn!("Failed to emit {} event: {}", ALERT_STORED_EVENT, e);
+    }
+}
+
+/// Most alerts `get_recent_alerts` returns in one call
+pub const MAX_RECENT_ALERTS: u32 = 500;
+
+/// Minimum gap between automatic reconnection attempts while the LLM is down
+const LLM_RECONNECT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Upper bounds on benchmark iterations; LLM runs are full generations
+pub const MAX_BENCHMARK_ITERATIONS: u32 = 10_000;
+pub const MAX_LLM_BENCHMARK_ITERATIONS: u32 = 20;
+
+/// Throughput of the evaluation pipeline on this machine, for capacity planning
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchmarkResult {
+    pub iterations: u32,
+    pub use_llm: bool,
+    pub calls_per_sec: f64,
+    pub mean_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    /// Mean time spent in the rules engine per iteration
+    pub rules_mean_ms: f64,
+    /// Mean time spent waiting on the LLM per iteration, when it was used
+    pub llm_mean_ms: Option<f64>,
+    pub llm_failures: u32,
+    /// "cpu" if the rules engine dominates latency, "llm" if the LLM does
+    pub bound: String,
+}
+
+/// Current `ConfigSnapshot` format. Snapshots from newer builds are rejected;
+/// fields added later must be `#[serde(default)]` so older snapshots still load.
+pub const CONFIG_SNAPSHOT_VERSION: u32 = 1;
+
+/// One machine's full evaluation setup, for pushing a known-good configuration
+/// to other machines
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigSnapshot {
+    pub format_version: u32,
+    /// Active ruleset, including each rule's enabled flag and severity
+    pub rules: RuleSet,
+    /// Persisted (rule_id, enabled) overrides applied on startup
+    pub rule_overrides: Vec<(String, bool)>,
+    pub llm_model: String,
+    pub llm_endpoint: String,
+    pub llm_options: LlmOptions,
+    pub llm_fallback_policy: LlmFallbackPolicy,
+    pub min_transcript_chars: usize,
+    pub max_disclosure_wpm: u32,
+    #[serde(default = "default_max_monologue_secs")]
+    pub max_monologue_secs: u64,
+    #[serde(default = "default_disclosure_grace_chars")]
+    pub disclosure_grace_chars: usize,
+    #[serde(default = "default_min_diarization_confidence")]
+    pub min_diarization_confidence: f64,
+    #[serde(default)]
+    pub sentence_quotes: bool,
+    #[serde(default)]
+    pub priority_policy: PriorityPolicy,
+    #[serde(default)]
+    pub escalation_policy: EscalationPolicy,
+    #[serde(default)]
+    pub ack_sla: AckSla,
+    #[serde(default)]
+    pub wfm_columns: Option<Vec<WfmColumn>>,
+    #[serde(default = "evaluator::default_calling_windows")]
+    pub calling_windows: HashMap<String, Option<CallingWindow>>,
+    pub evaluation_debounce_ms: u64,
+    #[serde(default)]
+    pub hash_phone_numbers: bool,
+    #[serde(default)]
+    pub semantic: semantic::SemanticSettings,
+    #[serde(default = "default_max_transcript_chars")]
+    pub max_transcript_chars: usize,
+    pub script_allowlists: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub alert_text_overrides: HashMap<String, AlertTextOverride>,
+}
+
+fn default_max_transcript_chars() -> usize {
+    DEFAULT_MAX_TRANSCRIPT_CHARS
+}
+
+fn default_max_monologue_secs() -> u64 {
+    evaluator::DEFAULT_MAX_MONOLOGUE_SECS
+}
+
+fn default_disclosure_grace_chars() -> usize {
+    evaluator::DEFAULT_DISCLOSURE_GRACE_CHARS
+}
+
+fn default_min_diarization_confidence() -> f64 {
+    evaluator::DEFAULT_MIN_DIARIZATION_CONFIDENCE
+}
+
+/// Everything besides the transcript that changes an evaluation's result.
+/// Evaluations are debounced per key, so a rules-only result is never served
+/// for an LLM request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EvaluationKey {
+    pub tenant_id: Option<String>,
+    pub call_id: String,
+    pub use_llm: bool,
+    pub training_mode: bool,
+    /// Sorted, so the same subset in a different order shares a key
+    pub rule_ids: Option<Vec<String>>,
+    pub timing: bool,
+}
+
+/// Debounce state for one `EvaluationKey`
+pub struct RecentEvaluation {
+    /// When the last evaluation started
+    pub started_at: Option<Instant>,
+    /// Sequence number of the newest request
+    pub latest: u64,
+    /// Newest finished evaluation, with the sequence number of its request
+    pub finished: tokio::sync::watch::Sender<Option<(u64, Result<EvaluationResult, String>)>>,
+}
+
+impl RecentEvaluation {
+    fn new() -> Self {
+        RecentEvaluation {
+            started_at: None,
+            latest: 0,
+            finished: tokio::sync::watch::channel(None).0,
+        }
+    }
 }
 
 /// Call metadata for context during evaluation
@@ -35,6 +347,124 @@ pub struct CallMetadata {
     pub has_prior_consent: bool,
     pub is_prerecorded: bool,
     pub call_type: String,
+    /// The number has been reassigned to a new subscriber since consent was
+    /// given, per an authoritative Reassigned Numbers Database check upstream
+    #[serde(default)]
+    pub number_reassigned: bool,
+    /// Two-letter state code of the consumer, used to select state-specific rules
+    #[serde(default)]
+    pub consumer_state: Option<String>,
+    /// Campaign the call belongs to, used to look up its approved script allowlist
+    #[serde(default)]
+    pub campaign_id: Option<String>,
+    /// Seller name the agent must say to satisfy DISC-001, matched phonetically
+    #[serde(default)]
+    pub company_name: Option<String>,
+    /// Where the DNC, consent and prerecorded flags came from; one of
+    /// `METADATA_SOURCES`. Carried onto metadata-rule alerts.
+    #[serde(default = "default_metadata_source")]
+    pub metadata_source: String,
+    /// Client/tenant the call belongs to; each tenant's data is stored separately
+    #[serde(default)]
+    pub tenant_id: Option<String>,
+}
+
+/// Call types the rules know how to handle
+pub const CALL_TYPES: [&str; 4] = ["outbound_sales", "inbound", "service", "collections"];
+
+/// Provenance of call metadata flags, most to least trusted
+pub const METADATA_SOURCES: [&str; 3] = ["verified_scrub", "agent_entered", "unknown"];
+
+fn default_metadata_source() -> String {
+    "unknown".to_string()
+}
+
+/// A problem found in call metadata. `error` issues make `start_call_session`
+/// reject the call; `warning` issues let it start with degraded checks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataIssue {
+    pub field: String,
+    pub message: String,
+    /// "error" or "warning"
+    pub severity: String,
+}
+
+impl MetadataIssue {
+    fn new(field: &str, severity: &str, message: String) -> Self {
+        MetadataIssue {
+            field: field.to_string(),
+            message,
+            severity: severity.to_string(),
+        }
+    }
+}
+
+impl CallMetadata {
+    /// Check the metadata is complete enough for the time rules and analytics
+    pub fn validate(&self) -> Vec<MetadataIssue> {
+        let mut issues = Vec::new();
+        
+        for (field, value) in [
+            ("call_id", &self.call_id),
+            ("agent_id", &self.agent_id),
+            ("agent_name", &self.agent_name),
+            ("call_type", &self.call_type),
+        ] {
+            if value.trim().is_empty() {
+                issues.push(MetadataIssue::new(field, "error", format!("{} is required", field)));
+            }
+        }
+        
+        if evaluator::parse_call_start_time(&self.call_start_time).is_none() {
+            issues.push(MetadataIssue::new(
+                "call_start_time",
+                "error",
+                format!("'{}' is not an RFC 3339 date-time", self.call_start_time),
+            ));
+        }
+        
+        match self.caller_timezone.as_deref() {
+            Some(tz) if tz.parse::<chrono_tz::Tz>().is_err() => issues.push(MetadataIssue::new(
+                "caller_timezone",
+                "error",
+                format!("'{}' is not a known IANA time zone", tz),
+            )),
+            None => issues.push(MetadataIssue::new(
+                "caller_timezone",
+                "warning",
+                "No time zone; calling-time rules can't run".to_string(),
+            )),
+            _ => {}
+        }
+        
+        if !self.call_type.trim().is_empty() && !CALL_TYPES.contains(&self.call_type.as_str()) {
+            issues.push(MetadataIssue::new(
+                "call_type",
+                "warning",
+                format!("'{}' is not a recognized call type ({})", self.call_type, CALL_TYPES.join(", ")),
+            ));
+        }
+        
+        if !METADATA_SOURCES.contains(&self.metadata_source.as_str()) {
+            issues.push(MetadataIssue::new(
+                "metadata_source",
+                "warning",
+                format!("'{}' is not a recognized metadata source ({})", self.metadata_source, METADATA_SOURCES.join(", ")),
+            ));
+        }
+        
+        if let Some(ref state) = self.consumer_state {
+            if state.trim().len() != 2 || !state.trim().chars().all(|c| c.is_ascii_alphabetic()) {
+                issues.push(MetadataIssue::new(
+                    "consumer_state",
+                    "warning",
+                    format!("'{}' is not a two-letter state code; state rules won't apply", state),
+                ));
+            }
+        }
+        
+        issues
+    }
 }
 
 /// Transcript segment for real-time processing
@@ -44,8 +474,27 @@ pub struct TranscriptSegment {
     pub speaker: String,
     pub text: String,
     pub timestamp_ms: u64,
+    /// UTF-16 code unit offsets into the joined transcript, as for `Evidence`
     pub start_char: usize,
     pub end_char: usize,
+    /// STT diarization confidence (0-1) in the speaker label, if the engine
+    /// reports one. Below the evaluator's threshold the label isn't trusted.
+    #[serde(default)]
+    pub diarization_confidence: Option<f64>,
+}
+
+/// Optional inputs to `evaluate_transcript`; unset fields are off
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EvaluateOptions {
+    /// Practice call: alerts carry coaching and nothing is stored
+    #[serde(default)]
+    pub training_mode: bool,
+    /// Check only these rules, in both the regex pass and the LLM prompt
+    #[serde(default)]
+    pub rule_ids: Option<Vec<String>>,
+    /// Return a `timing` breakdown of `evaluation_time_ms`
+    #[serde(default)]
+    pub timing: bool,
 }
 
 /// Evaluation result returned to frontend
@@ -55,6 +504,39 @@ pub struct EvaluationResult {
     pub suggested_next_lines: Vec<SuggestedLine>,
     pub evaluation_time_ms: u64,
     pub llm_used: bool,
+    /// The LLM was requested but failed, so these results are rules-only
+    #[serde(default)]
+    pub llm_degraded: bool,
+    #[serde(default)]
+    pub llm_degraded_reason: Option<String>,
+    /// Practice evaluation: alerts carry coaching text and must not be stored
+    #[serde(default)]
+    pub training_mode: bool,
+    /// Data-quality problems with the input, e.g. an empty transcript from a
+    /// failed STT feed. Zero alerts with warnings is not a clean call.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Where the evaluation time went, when requested with `timing`
+    #[serde(default)]
+    pub timing: Option<TimingBreakdown>,
+}
+
+/// Breakdown of `evaluation_time_ms` for performance investigations. Stages
+/// that didn't run (e.g. the LLM on a rules-only evaluation) are zero.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TimingBreakdown {
+    /// Rules engine pass, including the fallback after an LLM failure
+    pub regex_ms: f64,
+    /// LLM request until its response arrived, across any retry: network plus generation
+    pub llm_request_ms: f64,
+    /// Parsing the LLM's JSON output
+    pub llm_parse_ms: f64,
+    /// Realigning LLM evidence offsets to the transcript, or shifting offsets
+    /// after a trimmed transcript
+    pub offset_correction_ms: f64,
+    /// Embedding and comparing turns for semantic rules
+    #[serde(default)]
+    pub semantic_ms: f64,
 }
 
 /// LLM status for frontend
@@ -63,21 +545,63 @@ pub struct LlmStatus {
     pub available: bool,
     pub model: String,
     pub endpoint: String,
+    /// Why the LLM is or isn't available, with the installed models when the
+    /// configured one is missing
+    #[serde(default)]
+    pub connection: ConnectionStatus,
+}
+
+/// Record a connection check in the cached `llm_enabled` flag and describe it
+fn apply_connection_status(state: &AppState, llm: &LlmClient, connection: ConnectionStatus) -> LlmStatus {
+    let connected = connection == ConnectionStatus::Connected;
+    *state.llm_enabled.lock().unwrap() = connected;
+    
+    LlmStatus {
+        available: connected,
+        model: llm.get_model().to_string(),
+        endpoint: llm.get_endpoint().to_string(),
+        connection,
+    }
 }
 
 /// Initialize and check LLM connection
 #[tauri::command]
 async fn check_llm_status(state: State<'_, AppState>) -> Result<LlmStatus, String> {
     let mut llm = state.llm.write().await;
-    let connected = llm.check_connection().await.unwrap_or(false);
-    
-    *state.llm_enabled.lock().unwrap() = connected;
+    let connection = llm.check_connection().await.unwrap_or_default();
+    Ok(apply_connection_status(&state, &llm, connection))
+}
+
+/// Download the configured model after the user confirms, e.g. in response to
+/// a `model_not_installed` status
+#[tauri::command]
+async fn pull_llm_model(state: State<'_, AppState>) -> Result<LlmStatus, String> {
+    let mut llm = state.llm.write().await;
+    let connection = llm.pull_model().await?;
+    Ok(apply_connection_status(&state, &llm, connection))
+}
+
+/// Let connection checks download a missing model without asking (off by default)
+#[tauri::command]
+async fn set_llm_auto_pull(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    state.llm.write().await.set_auto_pull(enabled);
+    Ok(())
+}
+
+/// Re-check the Ollama connection, update the cached `llm_enabled` flag and
+/// tell the UI, so the flag doesn't go stale when Ollama stops or restarts
+async fn reconnect_llm(app: &AppHandle, state: &AppState) -> LlmStatus {
+    *state.llm_last_reconnect.lock().unwrap() = Some(Instant::now());
+    let mut llm = state.llm.write().await;
+    let connection = llm.check_connection().await.unwrap_or_default();
+    let status = apply_connection_status(state, &llm, connection);
+    drop(llm);
     
-    Ok(LlmStatus {
-        available: connected,
-        model: llm.get_model().to_string(),
-        endpoint: "http://localhost:11434".to_string(),
-    })
+    log::info!("LLM reconnection check: {:?}", status.connection);
+    if let Err(e) = app.emit(LLM_STATUS_EVENT, status.clone()) {
+        log::warn!("Failed to emit {} event: {}", LLM_STATUS_EVENT, e);
+    }
+    status
 }
 
 /// Set LLM model
@@ -85,44 +609,340 @@ async fn check_llm_status(state: State<'_, AppState>) -> Result<LlmStatus, Strin
 async fn set_llm_model(state: State<'_, AppState>, model: String) -> Result<LlmStatus, String> {
     let mut llm = state.llm.write().await;
     llm.set_model(model);
-    let connected = llm.check_connection().await.unwrap_or(false);
+    let connection = llm.check_connection().await.unwrap_or_default();
+    Ok(apply_connection_status(&state, &llm, connection))
+}
+
+/// Force the LLM model to load before a shift, reporting the load latency
+#[tauri::command]
+async fn warmup_llm(state: State<'_, AppState>) -> Result<LlmWarmup, String> {
+    let llm = state.llm.read().await;
+    llm.warmup().await
+}
+
+/// Preview the exact prompts `evaluate_transcript` would send to the LLM
+#[tauri::command]
+async fn preview_llm_prompt(
+    state: State<'_, AppState>,
+    metadata: CallMetadata,
+    transcript: String,
+    rule_ids: Option<Vec<String>>,
+) -> Result<PromptPreview, String> {
+    let rules_yaml = {
+        let rules = state.rules.read().unwrap();
+        let rules = match rule_ids {
+            Some(ref ids) => rules.subset(ids)?,
+            None => rules.clone(),
+        };
+        rules.applicable_in(metadata.consumer_state.as_deref()).to_yaml()
+    };
+    let llm = state.llm.read().await;
+    let metadata_str = serde_json::to_string_pretty(&metadata).unwrap_or_default();
+    Ok(llm.build_prompts(&metadata_str, &transcript, &rules_yaml))
+}
+
+/// Run the LLM several times on the same transcript and report how
+/// consistently each alert is raised
+#[tauri::command]
+async fn llm_stability_check(
+    state: State<'_, AppState>,
+    metadata: CallMetadata,
+    transcript: String,
+    runs: u32,
+) -> Result<LlmStabilityReport, String> {
+    let llm = state.llm.read().await;
+    let rules_yaml = state.rules.read().unwrap().applicable_in(metadata.consumer_state.as_deref()).to_yaml();
+    let metadata_str = serde_json::to_string_pretty(&metadata).unwrap_or_default();
+    llm.stability_check(&metadata_str, &transcript, &rules_yaml, runs).await
+}
+
+/// Score a model against a labeled corpus file. Runs on its own LLM client,
+/// so the configured model and live evaluations are left alone, and nothing
+/// is written to the database.
+#[tauri::command]
+async fn evaluate_corpus_with_model(
+    state: State<'_, AppState>,
+    corpus_path: String,
+    model: String,
+) -> Result<ModelCorpusReport, String> {
+    let cases = corpus::load_corpus(std::path::Path::new(&corpus_path))?;
+    let mut llm = {
+        let current = state.llm.read().await;
+        let mut llm = LlmClient::new(Some(current.get_endpoint().to_string()), Some(model.clone()));
+        llm.set_options(current.get_options().clone());
+        llm
+    };
+    match llm.check_connection().await? {
+        ConnectionStatus::Connected => {}
+        ConnectionStatus::ModelNotInstalled { .. } => {
+            return Err(format!("Model {} is not installed", model));
+        }
+        ConnectionStatus::Unavailable => {
+            return Err("LLM not available. Check Ollama connection.".to_string());
+        }
+    }
+    let rules = state.rules.read().unwrap().clone();
     
-    *state.llm_enabled.lock().unwrap() = connected;
+    let mut tally = corpus::CorpusTally::default();
+    let (mut total_latency_ms, mut llm_failures) = (0u64, 0u32);
+    for case in &cases {
+        let metadata_str = serde_json::to_string_pretty(&case.metadata).unwrap_or_default();
+        let rules_yaml = rules.applicable_in(case.metadata.consumer_state.as_deref()).to_yaml();
+        let start = Instant::now();
+        let result = llm.evaluate(&metadata_str, &case.transcript, &rules_yaml).await;
+        total_latency_ms += start.elapsed().as_millis() as u64;
+        match result {
+            Ok(response) => {
+                let raised = response.alerts.into_iter().map(|a| a.rule_id).collect();
+                tally.record(&case.expected_alerts, &raised);
+            }
+            Err(e) => {
+                log::warn!("Corpus case {} failed on {}: {}", case.id, model, e);
+                llm_failures += 1;
+            }
+        }
+    }
     
-    Ok(LlmStatus {
-        available: connected,
-        model: llm.get_model().to_string(),
-        endpoint: "http://localhost:11434".to_string(),
+    let (rules, (precision, recall, f1)) = tally.scores();
+    Ok(ModelCorpusReport {
+        model,
+        cases: cases.len() as u32,
+        llm_failures,
+        rules,
+        precision,
+        recall,
+        f1,
+        total_latency_ms,
+        mean_latency_ms: total_latency_ms as f64 / cases.len() as f64,
+    })
+}
+
+/// Time repeated evaluations of a sample transcript to size how many agents
+/// this machine can support. Each iteration starts from fresh call state.
+#[tauri::command]
+async fn benchmark_evaluation(
+    state: State<'_, AppState>,
+    sample_transcript: String,
+    iterations: u32,
+    use_llm: bool,
+) -> Result<BenchmarkResult, String> {
+    let max_iterations = if use_llm { MAX_LLM_BENCHMARK_ITERATIONS } else { MAX_BENCHMARK_ITERATIONS };
+    if iterations == 0 || iterations > max_iterations {
+        return Err(format!("iterations must be between 1 and {}", max_iterations));
+    }
+    if use_llm && !*state.llm_enabled.lock().unwrap() {
+        return Err("LLM not available. Check Ollama connection.".to_string());
+    }
+    
+    let metadata = CallMetadata {
+        call_id: format!("benchmark-{}", uuid::Uuid::new_v4()),
+        agent_id: "benchmark".to_string(),
+        agent_name: "Benchmark".to_string(),
+        call_start_time: chrono::Utc::now().to_rfc3339(),
+        caller_timezone: None,
+        customer_phone: None,
+        is_dnc_listed: false,
+        has_prior_consent: false,
+        is_prerecorded: false,
+        call_type: "outbound_sales".to_string(),
+        number_reassigned: false,
+        consumer_state: None,
+        campaign_id: None,
+        company_name: None,
+        metadata_source: default_metadata_source(),
+        tenant_id: None,
+    };
+    let metadata_str = serde_json::to_string_pretty(&metadata).unwrap_or_default();
+    let rules_yaml = state.rules.read().unwrap().applicable_in(metadata.consumer_state.as_deref()).to_yaml();
+    let llm = state.llm.read().await;
+    
+    let mut latencies_ms = Vec::with_capacity(iterations as usize);
+    let (mut rules_total_ms, mut llm_total_ms, mut llm_failures) = (0.0, 0.0, 0);
+    let start = Instant::now();
+    for _ in 0..iterations {
+        let rules_start = Instant::now();
+        let result = state.evaluator.evaluate(&metadata, &sample_transcript, &state.rules.read().unwrap());
+        state.evaluator.reset_call(metadata.tenant_id.as_deref(), &metadata.call_id);
+        result?;
+        let rules_ms = rules_start.elapsed().as_secs_f64() * 1000.0;
+        rules_total_ms += rules_ms;
+        
+        let mut llm_ms = 0.0;
+        if use_llm {
+            let llm_start = Instant::now();
+            if let Err(e) = llm.evaluate(&metadata_str, &sample_transcript, &rules_yaml).await {
+                log::warn!("Benchmark LLM run failed: {}", e);
+                llm_failures += 1;
+            }
+            llm_ms = llm_start.elapsed().as_secs_f64() * 1000.0;
+            llm_total_ms += llm_ms;
+        }
+        latencies_ms.push(rules_ms + llm_ms);
+    }
+    let elapsed_secs = start.elapsed().as_secs_f64();
+    
+    latencies_ms.sort_by(|a, b| a.total_cmp(b));
+    let p95_index = ((latencies_ms.len() as f64 * 0.95).ceil() as usize).saturating_sub(1);
+    let n = iterations as f64;
+    let llm_mean_ms = use_llm.then(|| llm_total_ms / n);
+    
+    Ok(BenchmarkResult {
+        iterations,
+        use_llm,
+        calls_per_sec: if elapsed_secs > 0.0 { n / elapsed_secs } else { 0.0 },
+        mean_latency_ms: latencies_ms.iter().sum::<f64>() / n,
+        p95_latency_ms: latencies_ms[p95_index],
+        rules_mean_ms: rules_total_ms / n,
+        llm_mean_ms,
+        llm_failures,
+        bound: if llm_mean_ms.is_some_and(|llm| llm > rules_total_ms / n) { "llm" } else { "cpu" }.to_string(),
     })
 }
 
+/// Milliseconds elapsed since `start`, with sub-millisecond precision
+fn ms_since(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
 /// Evaluate transcript for compliance issues
 #[tauri::command]
 async fn evaluate_transcript(
+    app: AppHandle,
     state: State<'_, AppState>,
     metadata: CallMetadata,
     transcript: String,
     use_llm: bool,
+    options: Option<EvaluateOptions>,
 ) -> Result<EvaluationResult, String> {
     let start = std::time::Instant::now();
+    let EvaluateOptions { training_mode, rule_ids, timing } = options.unwrap_or_default();
+    let mut breakdown = TimingBreakdown::default();
     
-    let llm_enabled = *state.llm_enabled.lock().unwrap();
-    let should_use_llm = use_llm && llm_enabled;
+    // Restrict both the regex pass and the LLM prompt to the requested rules
+    let rules = {
+        let rules = state.rules.read().unwrap();
+        match rule_ids {
+            Some(ref ids) => rules.subset(ids)?,
+            None => rules.clone(),
+        }
+    };
+    
+    // An oversized transcript is scored on its most recent text, and never sent
+    // to the LLM. Asking for the LLM is only an error while it's available;
+    // otherwise the evaluation would fall back to the rules anyway.
+    let max_chars = *state.max_transcript_chars.lock().unwrap();
+    let window_start = transcript::trailing_window_start(&transcript, max_chars);
+    if window_start > 0 && use_llm && state.llm.read().await.is_enabled() {
+        return Err(format!(
+            "Transcript is over the {} character limit; evaluate it without the LLM",
+            max_chars
+        ));
+    }
+    if training_mode {
+        state.mark_training_call(metadata.tenant_id.as_deref(), &metadata.call_id);
+    }
+    
+    // Collapse evaluations arriving within the debounce window: each waits out
+    // the window and only the newest runs, so the latest text is always
+    // evaluated. Callers it supersedes get its result; transcripts only grow,
+    // so it covers their text too.
+    let key = EvaluationKey {
+        tenant_id: metadata.tenant_id.clone(),
+        call_id: metadata.call_id.clone(),
+        use_llm,
+        training_mode,
+        rule_ids: rule_ids.map(|mut ids| {
+            ids.sort();
+            ids.dedup();
+            ids
+        }),
+        timing,
+    };
+    let debounce = *state.evaluation_debounce.lock().unwrap();
+    let (seq, wait, mut finished) = {
+        let mut recent = state.recent_evaluations.lock().unwrap();
+        let entry = recent.entry(key.clone()).or_insert_with(RecentEvaluation::new);
+        entry.latest += 1;
+        let wait = entry.started_at.map_or(Duration::ZERO, |t| debounce.saturating_sub(t.elapsed()));
+        if wait.is_zero() {
+            entry.started_at = Some(Instant::now());
+        }
+        (entry.latest, wait, entry.finished.subscribe())
+    };
+    if !wait.is_zero() {
+        tokio::time::sleep(wait).await;
+        let superseded = {
+            let mut recent = state.recent_evaluations.lock().unwrap();
+            match recent.get_mut(&key) {
+                Some(entry) if entry.latest == seq => {
+                    entry.started_at = Some(Instant::now());
+                    false
+                }
+                _ => true,
+            }
+        };
+        if superseded {
+            let answered = finished
+                .wait_for(|f| f.as_ref().is_some_and(|(answered, _)| *answered >= seq))
+                .await
+                .map_err(|_| "Call state was reset before the evaluation finished".to_string())?;
+            let (_, result) = answered.clone().expect("wait_for returns once a result is in");
+            return result;
+        }
+    }
     
-    let result = if should_use_llm {
-        // Use LLM for evaluation
-        let llm = state.llm.read().await;
-        let rules_yaml = state.rules.to_yaml();
-        let metadata_str = serde_json::to_string_pretty(&metadata).unwrap_or_default();
+    let outcome = async {
+        let mut llm_enabled = *state.llm_enabled.lock().unwrap();
+        
+        // While the LLM is down, periodically check whether it has come back
+        if use_llm && !llm_enabled {
+            let due = state.llm_last_reconnect.lock().unwrap()
+                .map_or(true, |last| last.elapsed() >= LLM_RECONNECT_INTERVAL);
+            if due {
+                llm_enabled = reconnect_llm(&app, &state).await.available;
+            }
+        }
+        let should_use_llm = use_llm && llm_enabled && window_start == 0;
+        
+        let mut degraded_reason = None;
         
-        match llm.evaluate(&metadata_str, &transcript, &rules_yaml).await {
-            Ok(llm_result) => {
-                // Convert LLM response to our format
-                EvaluationOutput {
-                    alerts: llm_result.alerts.into_iter().map(|a| Alert {
+        let result = if should_use_llm {
+            // Use LLM for evaluation
+            let llm_result = {
+                let llm = state.llm.read().await;
+                let rules_yaml = rules.applicable_in(metadata.consumer_state.as_deref()).to_yaml();
+                let metadata_str = serde_json::to_string_pretty(&metadata).unwrap_or_default();
+                let policy = *state.llm_fallback_policy.lock().unwrap();
+                
+                let request_start = Instant::now();
+                let mut llm_result = llm.evaluate_timed(&metadata_str, &transcript, &rules_yaml).await;
+                if let (Err(e), LlmFallbackPolicy::RetryOnce) = (&llm_result, policy) {
+                    log::warn!("LLM evaluation failed: {}. Retrying once.", e);
+                    llm_result = llm.evaluate_timed(&metadata_str, &transcript, &rules_yaml).await;
+                }
+                match llm_result {
+                    Ok((response, llm_timing)) => {
+                        breakdown.llm_parse_ms = llm_timing.parse_ms;
+                        breakdown.llm_request_ms = ms_since(request_start) - llm_timing.parse_ms;
+                        Ok(response)
+                    }
+                    Err(e) => {
+                        breakdown.llm_request_ms = ms_since(request_start);
+                        Err(e)
+                    }
+                }
+            };
+            
+            match llm_result {
+                Ok(llm_result) => {
+                    *state.llm_degraded.lock().unwrap() = false;
+                    
+                    // Convert LLM response to our format
+                    let mut alerts: Vec<Alert> = llm_result.alerts.into_iter().map(|a| Alert {
                         id: uuid::Uuid::new_v4().to_string(),
                         rule_id: a.rule_id,
                         title: a.title,
+                        priority: severity_priority(&a.severity),
                         severity: a.severity,
                         confidence: a.confidence,
                         evidence: Evidence {
@@ -132,155 +952,1590 @@ async fn evaluate_transcript(
                         },
                         why_it_matters: a.why_it_matters,
                         agent_fix_suggestion: a.agent_fix_suggestion,
-                    }).collect(),
-                    suggested_next_lines: llm_result.suggested_next_lines.into_iter().map(|s| SuggestedLine {
-                        text: s.text,
-                        confidence: s.confidence,
-                    }).collect(),
+                        segment_id: None,
+                        display_order: 0,
+                        timestamp_ms: None,
+                        sources: vec![SOURCE_LLM.to_string()],
+                        coaching: None,
+                        metadata_source: None,
+                        captures: HashMap::new(),
+                    }).collect();
+                    let offsets_start = Instant::now();
+                    for alert in &mut alerts {
+                        align_llm_evidence(&transcript, &mut alert.evidence);
+                    }
+                    breakdown.offset_correction_ms = ms_since(offsets_start);
+                    // The prompt only lists rules for the consumer's state, but the model may not stick to it
+                    alerts.retain(|a| rules.get_rule(&a.rule_id)
+                        .map_or(true, |r| r.applies_in(metadata.consumer_state.as_deref())));
+                    state.evaluator.apply_alert_text_overrides(&mut alerts);
+                    state.evaluator.order_alerts(&mut alerts);
+                    
+                    EvaluationOutput {
+                        alerts,
+                        suggested_next_lines: llm_result.suggested_next_lines.into_iter().map(|s| SuggestedLine {
+                            text: s.text,
+                            confidence: s.confidence,
+                            rule_id: None,
+                        }).collect(),
+                    }
+                }
+                Err(e) => {
+                    log::warn!("LLM evaluation failed: {}. Falling back to rules-only.", e);
+                    if llm::is_connection_error(&e) {
+                        reconnect_llm(&app, &state).await;
+                    }
+                    degraded_reason = Some(e);
+                    // Fallback to regex evaluation
+                    let regex_start = Instant::now();
+                    let output = state.evaluator.evaluate(&metadata, &transcript, &rules)?;
+                    breakdown.regex_ms = ms_since(regex_start);
+                    output
                 }
             }
-            Err(e) => {
-                log::warn!("LLM evaluation failed: {}. Falling back to rules-only.", e);
-                // Fallback to regex evaluation
-                state.evaluator.evaluate(&metadata, &transcript, &state.rules)?
+        } else {
+            // Use regex-based evaluation
+            let regex_start = Instant::now();
+            let output = state.evaluator.evaluate_window(&metadata, &transcript, window_start, &rules)?;
+            breakdown.regex_ms = ms_since(regex_start);
+            output
+        };
+        
+        // Semantic matching adds paraphrases of `semantic` rules neither pass raised
+        let mut result = result;
+        if state.semantic.settings().enabled {
+            let semantic_rules: Vec<&Rule> = rules.get_enabled_rules().into_iter()
+                .filter(|r| r.semantic && !r.requires_metadata && r.applies_in(metadata.consumer_state.as_deref()))
+                .filter(|r| !result.alerts.iter().any(|a| a.rule_id == r.id))
+                .filter(|r| !state.evaluator.has_raised(metadata.tenant_id.as_deref(), &metadata.call_id, &r.id))
+                .collect();
+            let semantic_start = Instant::now();
+            // A copy of the client, so configuration changes aren't held up while turns are embedded
+            let llm = state.llm.read().await.clone();
+            match state.semantic.match_transcript(&llm, (metadata.tenant_id.as_deref(), &metadata.call_id), &transcript, window_start, &semantic_rules).await {
+                Ok(mut alerts) => {
+                    state.evaluator.record_external_alerts(metadata.tenant_id.as_deref(), &metadata.call_id, &mut alerts, &rules);
+                    result.alerts.extend(alerts);
+                    state.evaluator.order_alerts(&mut result.alerts);
+                }
+                Err(e) => log::warn!("Semantic matching failed: {}", e),
             }
+            breakdown.semantic_ms = ms_since(semantic_start);
         }
-    } else {
-        // Use regex-based evaluation
-        state.evaluator.evaluate(&metadata, &transcript, &state.rules)?
-    };
-    
-    let elapsed = start.elapsed().as_millis() as u64;
+        
+        // Tell the UI the first time the LLM drops out, so it can show a banner
+        if let Some(ref reason) = degraded_reason {
+            let already_degraded = std::mem::replace(&mut *state.llm_degraded.lock().unwrap(), true);
+            if !already_degraded {
+                let event = LlmDegradedEvent {
+                    call_id: metadata.call_id.clone(),
+                    reason: reason.clone(),
+                };
+                if let Err(e) = app.emit(LLM_DEGRADED_EVENT, event) {
+                    log::warn!("Failed to emit {} event: {}", LLM_DEGRADED_EVENT, e);
+                }
+            }
+        }
+        
+        let elapsed = start.elapsed().as_millis() as u64;
+        
+        let mut warnings = transcript::quality_warnings(&transcript);
+        if window_start > 0 {
+            warnings.push(format!(
+                "Transcript is over the {} character limit; only the most recent text was evaluated",
+                max_chars
+            ));
+        }
+        
+        // Merge duplicate hits for the same violation so the UI shows it once
+        let mut alerts = result.alerts;
+        dedup_alerts(&mut alerts);
+        if training_mode {
+            attach_coaching(&mut alerts, &rules);
+        }
+        
+        let result = EvaluationResult {
+            alerts,
+            suggested_next_lines: result.suggested_next_lines,
+            evaluation_time_ms: elapsed,
+            llm_used: should_use_llm && degraded_reason.is_none(),
+            llm_degraded: degraded_reason.is_some(),
+            llm_degraded_reason: degraded_reason,
+            training_mode,
+            warnings,
+            timing: timing.then_some(breakdown),
+        };
+        
+        if !training_mode {
+            record_suggestions_shown(&state, &metadata, &result).await;
+        }
+        
+        Ok::<_, String>(result)
+    }.await;
     
-    Ok(EvaluationResult {
-        alerts: result.alerts,
-        suggested_next_lines: result.suggested_next_lines,
-        evaluation_time_ms: elapsed,
-        llm_used: should_use_llm,
-    })
+    if let Some(entry) = state.recent_evaluations.lock().unwrap().get(&key) {
+        entry.finished.send_if_modified(|f| match f {
+            Some((answered, _)) if *answered >= seq => false,
+            _ => {
+                *f = Some((seq, outcome.clone()));
+                true
+            }
+        });
+    }
+    outcome
 }
 
-/// Store an alert in the database
-#[tauri::command]
-async fn store_alert(
-    state: State<'_, AppState>,
-    alert: Alert,
-    metadata: CallMetadata,
-) -> Result<String, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.insert_alert(&alert, &metadata).map_err(|e| e.to_string())?;
-    Ok(alert.id.clone())
+/// Note which rules' suggested lines were shown on a call, so
+/// `get_suggestion_effectiveness` can later tell whether they helped.
+/// Failures are logged rather than failing the evaluation.
+async fn record_suggestions_shown(state: &AppState, metadata: &CallMetadata, result: &EvaluationResult) {
+    let shown: Vec<(String, bool)> = result.suggested_next_lines.iter()
+        .filter_map(|s| s.rule_id.clone())
+        .map(|rule_id| {
+            let prompted_by_alert = result.alerts.iter().any(|a| a.rule_id == rule_id);
+            (rule_id, prompted_by_alert)
+        })
+        .collect();
+    if shown.is_empty() {
+        return;
+    }
+    let call_id = metadata.call_id.clone();
+    let recorded = state.db
+        .run_blocking(metadata.tenant_id.as_deref(), move |db| db.record_suggestions_shown(&call_id, &shown))
+        .await;
+    if let Err(e) = recorded {
+        log::warn!("Failed to record suggestions shown for call {}: {}", metadata.call_id, e);
+    }
 }
 
-/// Get alerts with filters
+/// Set the per-call debounce window for `evaluate_transcript` (0 disables it)
 #[tauri::command]
-async fn get_alerts(
-    state: State<'_, AppState>,
-    start_date: Option<String>,
-    end_date: Option<String>,
-    agent_id: Option<String>,
-    severity: Option<String>,
-    rule_id: Option<String>,
-    limit: Option<u32>,
-    offset: Option<u32>,
-) -> Result<Vec<database::StoredAlert>, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_alerts(start_date, end_date, agent_id, severity, rule_id, limit, offset)
-        .map_err(|e| e.to_string())
+async fn set_evaluation_debounce_ms(state: State<'_, AppState>, ms: u64) -> Result<(), String> {
+    *state.evaluation_debounce.lock().unwrap() = Duration::from_millis(ms);
+    Ok(())
 }
 
-/// Get analytics data
+/// Turn embedding-based matching of `semantic` rules on or off, and set the
+/// embedding model and similarity threshold
 #[tauri::command]
-async fn get_analytics(
-    state: State<'_, AppState>,
-    start_date: String,
-    end_date: String,
-) -> Result<database::AnalyticsData, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.get_analytics(&start_date, &end_date).map_err(|e| e.to_string())
+async fn set_semantic_matching(state: State<'_, AppState>, settings: semantic::SemanticSettings) -> Result<(), String> {
+    settings.validate()?;
+    state.semantic.set_settings(settings);
+    Ok(())
 }
 
-/// Export alerts to JSON
+/// Store customer phone numbers on call records as keyed hashes from now on.
+/// Numbers already stored are left as they are.
 #[tauri::command]
-async fn export_alerts_json(
-    state: State<'_, AppState>,
-    start_date: Option<String>,
-    end_date: Option<String>,
-) -> Result<String, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    let alerts = db.get_alerts(start_date, end_date, None, None, None, None, None)
-        .map_err(|e| e.to_string())?;
-    serde_json::to_string_pretty(&alerts).map_err(|e| e.to_string())
+async fn set_hash_phone_numbers(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    state.db
+        .run_blocking(None, move |db| db.set_setting(HASH_PHONE_NUMBERS_SETTING, &enabled.to_string()))
+        .await?;
+    *state.hash_phone_numbers.lock().unwrap() = enabled;
+    Ok(())
 }
 
-/// Get all rules
+/// Set the transcript length above which `evaluate_transcript` only scores the
+/// most recent text and refuses the LLM
 #[tauri::command]
-async fn get_rules(state: State<'_, AppState>) -> Result<Vec<Rule>, String> {
-    Ok(state.rules.rules.clone())
+async fn set_max_transcript_chars(state: State<'_, AppState>, chars: usize) -> Result<(), String> {
+    if chars == 0 {
+        return Err("max_transcript_chars must be greater than zero".to_string());
+    }
+    *state.max_transcript_chars.lock().unwrap() = chars;
+    Ok(())
 }
 
-/// Get rules as YAML
+/// Choose whether a failed LLM evaluation is retried once before falling back
 #[tauri::command]
-async fn get_rules_yaml(state: State<'_, AppState>) -> Result<String, String> {
-    Ok(state.rules.to_yaml())
+async fn set_llm_fallback_policy(state: State<'_, AppState>, policy: LlmFallbackPolicy) -> Result<(), String> {
+    *state.llm_fallback_policy.lock().unwrap() = policy;
+    Ok(())
 }
 
-/// Start a call session
+/// Data-quality warnings for a transcript without evaluating it, e.g. to check
+/// an STT feed before a shift. Empty when the transcript looks usable.
 #[tauri::command]
-async fn start_call_session(
-    state: State<'_, AppState>,
-    metadata: CallMetadata,
-) -> Result<String, String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.start_call_session(&metadata).map_err(|e| e.to_string())?;
-    
-    // Reset evaluator state for new call
-    state.evaluator.reset();
-    
-    log::info!("Started call session: {}", metadata.call_id);
-    Ok(metadata.call_id)
+async fn check_transcript_quality(transcript: String) -> Result<Vec<String>, String> {
+    Ok(transcript::quality_warnings(&transcript))
 }
 
-/// End a call session
+/// Evaluate a recorded call from a `.txt`, `.vtt` or `.srt` transcript file.
+/// The file is scored under a throwaway call ID, so evaluating it again gives
+/// the same alerts and the call's live session state is untouched.
 #[tauri::command]
-async fn end_call_session(
+async fn evaluate_transcript_file(
     state: State<'_, AppState>,
-    call_id: String,
-) -> Result<(), String> {
-    let db = state.db.lock().map_err(|e| e.to_string())?;
-    db.end_call_session(&call_id).map_err(|e| e.to_string())?;
-    log::info!("Ended call session: {}", call_id);
-    Ok(())
-}
+    mut metadata: CallMetadata,
+    path: String,
+    rule_ids: Option<Vec<String>>,
+) -> Result<EvaluationResult, String> {
+    let start = std::time::Instant::now();
+    
+    let rules = {
+        let rules = state.rules.read().unwrap();
+        match rule_ids {
+            Some(ref ids) => rules.subset(ids)?,
+            None => rules.clone(),
+        }
+    };
+    let mut segments = transcript::read_transcript_file(std::path::Path::new(&path))?;
+    let spoken: Vec<&str> = segments.iter().map(|s| s.text.as_str()).collect();
+    let warnings = transcript::quality_warnings(&spoken.join("\n"));
+    metadata.call_id = format!("reeval-{}", uuid::Uuid::new_v4());
+    let result = state.evaluator.evaluate_segments(&metadata, &mut segments, &rules);
+    state.evaluator.reset_call(metadata.tenant_id.as_deref(), &metadata.call_id);
+    let result = result?;
+    
+    Ok(EvaluationResult {
+        alerts: result.alerts,
+        suggested_next_lines: result.suggested_next_lines,
+        evaluation_time_ms: start.elapsed().as_millis() as u64,
+        llm_used: false,
+        llm_degraded: false,
+        llm_degraded_reason: None,
+        training_mode: false,
+        warnings,
+        timing: None,
+    })
+}
+
+/// Evaluate a transcript against a retained ruleset version instead of the
+/// active one, to reproduce how a historical call was judged at the time.
+/// The call is replayed from start to finish, including end-of-call checks,
+/// without touching its live session state.
+#[tauri::command]
+async fn evaluate_with_ruleset_version(
+    state: State<'_, AppState>,
+    metadata: CallMetadata,
+    transcript: String,
+    version: String,
+) -> Result<EvaluationResult, String> {
+    let start = std::time::Instant::now();
+    
+    let requested = version.clone();
+    let rules = state.db.run_blocking(None, move |db| db.get_ruleset_version(&requested)).await?
+        .ok_or_else(|| format!("Ruleset version {} is not retained", version))?;
+    let segments = transcript::parse_transcript_text(&transcript);
+    if segments.is_empty() {
+        return Err("No transcript text to evaluate".to_string());
+    }
+    let alerts = replay_call(&state.evaluator, &metadata, &segments, &rules)?;
+    
+    Ok(EvaluationResult {
+        alerts,
+        suggested_next_lines: Vec::new(),
+        evaluation_time_ms: start.elapsed().as_millis() as u64,
+        llm_used: false,
+        llm_degraded: false,
+        llm_degraded_reason: None,
+        training_mode: false,
+        warnings: transcript::quality_warnings(&transcript),
+        timing: None,
+    })
+}
+
+/// Ruleset versions available to `evaluate_with_ruleset_version`
+#[tauri::command]
+async fn list_ruleset_versions(state: State<'_, AppState>) -> Result<Vec<database::RulesetVersion>, String> {
+    state.db.run_blocking(None, |db| db.list_ruleset_versions()).await
+}
+
+/// Re-score stored calls under the active ruleset and a candidate ruleset file
+/// and report how each call's alerts would change. Each call's transcript is
+/// read from `transcript_dir/<call_id>.txt` (or `.vtt`/`.srt`). Nothing is stored.
+#[tauri::command]
+async fn reevaluate_and_diff(
+    state: State<'_, AppState>,
+    call_ids: Vec<String>,
+    transcript_dir: String,
+    new_ruleset_path: String,
+    tenant_id: Option<String>,
+) -> Result<Vec<CallAlertDiff>, String> {
+    let candidate = RuleSet::load_from_file(std::path::Path::new(&new_ruleset_path))?;
+    let current = state.rules.read().unwrap().clone();
+    let dir = std::path::PathBuf::from(&transcript_dir);
+    
+    let mut diffs = Vec::with_capacity(call_ids.len());
+    for call_id in call_ids {
+        let lookup = call_id.clone();
+        let metadata = state.db
+            .run_blocking(tenant_id.as_deref(), move |db| db.get_call_metadata(&lookup))
+            .await?
+            .ok_or_else(|| format!("Call not found: {}", call_id))?;
+        let path = transcript::find_call_transcript(&dir, &call_id)
+            .ok_or_else(|| format!("No transcript for call {} in {}", call_id, transcript_dir))?;
+        let segments = transcript::read_transcript_file(&path)?;
+        
+        let before = replay_call(&state.evaluator, &metadata, &segments, &current)?;
+        let after = replay_call(&state.evaluator, &metadata, &segments, &candidate)?;
+        diffs.push(evaluator::diff_alerts(&call_id, before, after));
+    }
+    
+    log::info!(
+        "Re-evaluated {} calls against {}: {} with changed alerts",
+        diffs.len(), new_ruleset_path,
+        diffs.iter().filter(|d| !d.added.is_empty() || !d.removed.is_empty() || !d.changed.is_empty()).count()
+    );
+    Ok(diffs)
+}
+
+/// Score a recorded call from start to finish, including end-of-call checks,
+/// under a throwaway call ID so the call's live session state is untouched
+fn replay_call(
+    evaluator: &ComplianceEvaluator,
+    metadata: &CallMetadata,
+    segments: &[TranscriptSegment],
+    rules: &RuleSet,
+) -> Result<Vec<Alert>, String> {
+    let mut metadata = metadata.clone();
+    metadata.call_id = format!("reeval-{}", uuid::Uuid::new_v4());
+    let mut segments = segments.to_vec();
+    
+    let result = evaluator.evaluate_segments(&metadata, &mut segments, rules);
+    let end_alerts = evaluator.finalize_call(metadata.tenant_id.as_deref(), &metadata.call_id, rules);
+    evaluator.reset_call(metadata.tenant_id.as_deref(), &metadata.call_id);
+    
+    let mut alerts = result?.alerts;
+    alerts.extend(end_alerts);
+    Ok(alerts)
+}
+
+/// Built-in scripted calls available to `run_scenario`
+#[tauri::command]
+async fn list_scenarios() -> Result<Vec<scenarios::ScenarioInfo>, String> {
+    Ok(scenarios::list_scenarios())
+}
+
+/// Play a built-in scripted call through the evaluator segment by segment, as
+/// a live call would arrive. Returns one result per segment, then one with the
+/// end-of-call alerts; alerts carry their segment's `timestamp_ms` so the
+/// frontend can replay them at the scripted pace. Nothing is stored.
+#[tauri::command]
+async fn run_scenario(state: State<'_, AppState>, scenario_id: String) -> Result<Vec<EvaluationResult>, String> {
+    let scenario = scenarios::find_scenario(&scenario_id)
+        .ok_or_else(|| format!("Unknown scenario: {}", scenario_id))?;
+    let metadata = scenario.metadata();
+    let rules = state.rules.read().unwrap().clone();
+    let script = scenario.segments();
+    
+    let mut results = Vec::with_capacity(script.len() + 1);
+    for heard in 1..=script.len() {
+        let start = std::time::Instant::now();
+        let mut segments = script[..heard].to_vec();
+        let output = match state.evaluator.evaluate_segments(&metadata, &mut segments, &rules) {
+            Ok(output) => output,
+            Err(e) => {
+                state.evaluator.reset_call(metadata.tenant_id.as_deref(), &metadata.call_id);
+                return Err(e);
+            }
+        };
+        results.push(EvaluationResult {
+            alerts: output.alerts,
+            suggested_next_lines: output.suggested_next_lines,
+            evaluation_time_ms: start.elapsed().as_millis() as u64,
+            llm_used: false,
+            llm_degraded: false,
+            llm_degraded_reason: None,
+            training_mode: false,
+            warnings: Vec::new(),
+            timing: None,
+        });
+    }
+    
+    let start = std::time::Instant::now();
+    let end_alerts = state.evaluator.finalize_call(metadata.tenant_id.as_deref(), &metadata.call_id, &rules);
+    results.push(EvaluationResult {
+        alerts: end_alerts,
+        suggested_next_lines: Vec::new(),
+        evaluation_time_ms: start.elapsed().as_millis() as u64,
+        llm_used: false,
+        llm_degraded: false,
+        llm_degraded_reason: None,
+        training_mode: false,
+        warnings: Vec::new(),
+        timing: None,
+    });
+    state.evaluator.reset_call(metadata.tenant_id.as_deref(), &metadata.call_id);
+    
+    log::info!("Ran scenario {} ({} segments)", scenario_id, script.len());
+    Ok(results)
+}
+
+/// Add one streamed segment to a call and evaluate the transcript once held
+/// segments are released in timestamp order. `flush` releases everything
+/// immediately, e.g. for the final segment of a call.
+#[tauri::command]
+async fn push_segment(
+    state: State<'_, AppState>,
+    metadata: CallMetadata,
+    segment: TranscriptSegment,
+    flush: Option<bool>,
+) -> Result<EvaluationResult, String> {
+    let start = std::time::Instant::now();
+    let window = *state.reorder_window.lock().unwrap();
+    
+    // Copy the released segments out so other calls' segments aren't held up
+    // behind this call's evaluation
+    let released = {
+        let mut buffers = state.segment_buffers.lock().unwrap();
+        let buffer = buffers.entry(call_key(metadata.tenant_id.as_deref(), &metadata.call_id)).or_default();
+        let segment_id = segment.id.clone();
+        if !buffer.push(segment, start) {
+            log::debug!("Dropped duplicate STT segment {} for call {}", segment_id, metadata.call_id);
+        }
+        let released = if flush.unwrap_or(false) {
+            buffer.flush()
+        } else {
+            buffer.release(window, start)
+        };
+        released.then(|| buffer.segments_mut().to_vec())
+    };
+    
+    let result = match released {
+        Some(mut segments) => {
+            state.evaluator.evaluate_segments(&metadata, &mut segments, &state.rules.read().unwrap())?
+        }
+        None => EvaluationOutput { alerts: Vec::new(), suggested_next_lines: Vec::new() },
+    };
+    
+    let result = EvaluationResult {
+        alerts: result.alerts,
+        suggested_next_lines: result.suggested_next_lines,
+        evaluation_time_ms: start.elapsed().as_millis() as u64,
+        llm_used: false,
+        llm_degraded: false,
+        llm_degraded_reason: None,
+        training_mode: state.is_training_call(metadata.tenant_id.as_deref(), &metadata.call_id),
+        warnings: Vec::new(),
+        timing: None,
+    };
+    if !result.training_mode {
+        record_suggestions_shown(&state, &metadata, &result).await;
+    }
+    Ok(result)
+}
+
+/// Set how long streamed segments are held for late arrivals (0 disables reordering)
+#[tauri::command]
+async fn set_reorder_window_ms(state: State<'_, AppState>, ms: u64) -> Result<(), String> {
+    *state.reorder_window.lock().unwrap() = Duration::from_millis(ms);
+    Ok(())
+}
+
+/// Store an alert in the database and return its ID. With an
+/// `idempotency_key`, a retry using the same key within 24 hours returns the
+/// first alert's ID instead of storing it again.
+#[tauri::command]
+async fn store_alert(
+    state: State<'_, AppState>,
+    alert: Alert,
+    metadata: CallMetadata,
+    idempotency_key: Option<String>,
+) -> Result<String, String> {
+    let tenant_id = metadata.tenant_id.clone();
+    if state.is_training_call(metadata.tenant_id.as_deref(), &metadata.call_id) {
+        return Ok(alert.id);
+    }
+    let policy = *state.escalation_policy.lock().unwrap();
+    let (alert_id, escalated) = state.db.run_blocking(tenant_id.as_deref(), move |db| {
+        match idempotency_key {
+            Some(ref key) => db.insert_alert_once(key, &alert, &metadata, &policy),
+            None => Ok((alert.id.clone(), Some(db.insert_alert(&alert, &metadata, &policy)?))),
+        }
+    }).await?;
+    
+    match escalated {
+        Some(true) => log::info!("Escalated repeat violation in alert {}", alert_id),
+        None => log::debug!("Alert {} already stored under its idempotency key", alert_id),
+        Some(false) => {}
+    }
+    Ok(alert_id)
+}
+
+/// Set when repeat violations by the same agent are escalated at store time
+#[tauri::command]
+async fn set_escalation_policy(state: State<'_, AppState>, policy: EscalationPolicy) -> Result<(), String> {
+    policy.validate()?;
+    *state.escalation_policy.lock().unwrap() = policy;
+    Ok(())
+}
+
+/// Record that the agent used one of the suggested next lines
+#[tauri::command]
+async fn accept_suggestion(
+    state: State<'_, AppState>,
+    call_id: String,
+    suggestion_text: String,
+    rule_id: Option<String>,
+    tenant_id: Option<String>,
+) -> Result<(), String> {
+    if state.is_training_call(tenant_id.as_deref(), &call_id) {
+        return Ok(());
+    }
+    state.db
+        .run_blocking(tenant_id.as_deref(), move |db| {
+            db.record_suggestion_usage(&call_id, &suggestion_text, rule_id.as_deref())
+        })
+        .await
+}
+
+/// For calls where `rule_id`'s suggested line was shown, how often the
+/// violation was avoided afterwards versus repeated, including by rules that
+/// depend on it
+#[tauri::command]
+async fn get_suggestion_effectiveness(
+    state: State<'_, AppState>,
+    rule_id: String,
+    tenant_id: Option<String>,
+) -> Result<database::SuggestionStats, String> {
+    let follow_on_ids: Vec<String> = state.rules.read().unwrap().rules
+        .iter()
+        .filter(|r| r.depends_on.as_deref() == Some(rule_id.as_str()))
+        .map(|r| r.id.clone())
+        .collect();
+    state.db.run_blocking(tenant_id.as_deref(), move |db| {
+        db.get_suggestion_effectiveness(&rule_id, &follow_on_ids)
+    }).await
+}
+
+/// Get alerts with filters, newest first. Pages are cursor-based: pass the
+/// returned `next_cursor` as `after_cursor` for the next `limit` alerts.
+/// `offset` is no longer supported and is rejected rather than ignored.
+#[tauri::command]
+async fn get_alerts(
+    state: State<'_, AppState>,
+    filters: Option<database::AlertQuery>,
+    limit: Option<u32>,
+    after_cursor: Option<String>,
+    offset: Option<u32>,
+    tenant_id: Option<String>,
+) -> Result<database::AlertPage, String> {
+    if offset.is_some() {
+        return Err("offset is no longer supported; pass the previous page's next_cursor as after_cursor".to_string());
+    }
+    let filters = filters.unwrap_or_default();
+    let after = after_cursor.as_deref().map(database::decode_alert_cursor).transpose()?;
+    // One extra row tells us whether another page follows
+    let fetch = limit.map(|l| l.saturating_add(1));
+    let mut alerts = state.db.run_blocking(tenant_id.as_deref(), move |db| {
+        db.get_alerts(&filters, fetch, after)
+    }).await?;
+    
+    let next_cursor = match limit {
+        Some(l) if alerts.len() > l as usize => {
+            alerts.truncate(l as usize);
+            alerts.last().map(database::encode_alert_cursor)
+        }
+        _ => None,
+    };
+    Ok(database::AlertPage { alerts, next_cursor })
+}
+
+/// The most recent alerts across every call, newest first, for a floor-wide
+/// wallboard. Pair with the `alert-stored` event to update it live.
+#[tauri::command]
+async fn get_recent_alerts(
+    state: State<'_, AppState>,
+    limit: u32,
+    tenant_id: Option<String>,
+) -> Result<Vec<database::StoredAlert>, String> {
+    if limit == 0 || limit > MAX_RECENT_ALERTS {
+        return Err(format!("limit must be between 1 and {}", MAX_RECENT_ALERTS));
+    }
+    state.db.run_blocking(tenant_id.as_deref(), move |db| {
+        db.get_alerts(&database::AlertQuery::default(), Some(limit), None)
+    }).await
+}
 
-/// Reset evaluator state (for new calls)
+/// Get all alerts carrying a review tag, newest first
+#[tauri::command]
+async fn get_alerts_by_tag(
+    state: State<'_, AppState>,
+    tag: String,
+    tenant_id: Option<String>,
+) -> Result<Vec<database::StoredAlert>, String> {
+    state.db.run_blocking(tenant_id.as_deref(), move |db| {
+        db.get_alerts(&database::AlertQuery { tag: Some(tag), ..Default::default() }, None, None)
+    }).await
+}
+
+/// Label a stored alert for review, e.g. "coaching needed" or "escalate to legal"
+#[tauri::command]
+async fn add_alert_tag(
+    state: State<'_, AppState>,
+    alert_id: String,
+    tag: String,
+    tenant_id: Option<String>,
+) -> Result<(), String> {
+    let tag = tag.trim().to_string();
+    if tag.is_empty() {
+        return Err("Tag cannot be empty".to_string());
+    }
+    let id = alert_id.clone();
+    let found = state.db.run_blocking(tenant_id.as_deref(), move |db| db.add_alert_tag(&id, &tag)).await?;
+    if !found {
+        return Err(format!("Alert not found: {}", alert_id));
+    }
+    Ok(())
+}
+
+/// Remove a review tag from an alert
+#[tauri::command]
+async fn remove_alert_tag(
+    state: State<'_, AppState>,
+    alert_id: String,
+    tag: String,
+    tenant_id: Option<String>,
+) -> Result<(), String> {
+    let tag = tag.trim().to_string();
+    state.db.run_blocking(tenant_id.as_deref(), move |db| db.remove_alert_tag(&alert_id, &tag)).await?;
+    Ok(())
+}
+
+/// Tags in use with alert counts, for building filter menus
+#[tauri::command]
+async fn get_tag_counts(
+    state: State<'_, AppState>,
+    tenant_id: Option<String>,
+) -> Result<Vec<database::TagCount>, String> {
+    state.db.run_blocking(tenant_id.as_deref(), |db| db.get_tag_counts()).await
+}
+
+/// Record that the agent acknowledged a live alert. Only the first
+/// acknowledgment counts.
+#[tauri::command]
+async fn acknowledge_alert(
+    state: State<'_, AppState>,
+    alert_id: String,
+    tenant_id: Option<String>,
+) -> Result<(), String> {
+    let id = alert_id.clone();
+    let found = state.db.run_blocking(tenant_id.as_deref(), move |db| db.acknowledge_alert(&id)).await?;
+    if !found {
+        return Err(format!("Alert not found: {}", alert_id));
+    }
+    Ok(())
+}
+
+/// Acknowledge all of a call's or agent's alerts, or a list of alert IDs, at
+/// once after review. Returns how many alerts were newly acknowledged.
+#[tauri::command]
+async fn acknowledge_alerts_bulk(
+    state: State<'_, AppState>,
+    filter: database::AlertFilter,
+    tenant_id: Option<String>,
+) -> Result<u32, String> {
+    filter.validate()?;
+    state.db.run_blocking(tenant_id.as_deref(), move |db| db.acknowledge_alerts(&filter)).await
+}
+
+/// Set the per-severity acknowledgment targets used by `get_ack_sla_report`
+#[tauri::command]
+async fn set_ack_sla(state: State<'_, AppState>, sla: AckSla) -> Result<(), String> {
+    sla.validate()?;
+    *state.ack_sla.lock().unwrap() = sla;
+    Ok(())
+}
+
+/// Share of alerts each agent acknowledged within the SLA, per severity
+#[tauri::command]
+async fn get_ack_sla_report(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    tenant_id: Option<String>,
+) -> Result<Vec<database::AckSlaRow>, String> {
+    let sla = *state.ack_sla.lock().unwrap();
+    state.db
+        .run_blocking(tenant_id.as_deref(), move |db| db.get_ack_sla_report(&start_date, &end_date, &sla))
+        .await
+}
+
+/// Get the audio position of a stored alert so review UIs can seek to it
+#[tauri::command]
+async fn get_alert_timestamp(
+    state: State<'_, AppState>,
+    alert_id: String,
+    tenant_id: Option<String>,
+) -> Result<Option<u64>, String> {
+    let id = alert_id.clone();
+    state.db.run_blocking(tenant_id.as_deref(), move |db| db.get_alert_timestamp(&id))
+        .await?
+        .ok_or_else(|| format!("Alert not found: {}", alert_id))
+}
+
+/// Get analytics data
+#[tauri::command]
+async fn get_analytics(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    refresh: Option<bool>,
+    tenant_id: Option<String>,
+) -> Result<database::AnalyticsData, String> {
+    let refresh = refresh.unwrap_or(false);
+    state.db
+        .run_blocking(tenant_id.as_deref(), move |db| db.get_analytics(&start_date, &end_date, refresh))
+        .await
+}
+
+/// Days in the range with an abnormally high alert count compared with the
+/// two weeks before each, e.g. after a bad script rollout
+#[tauri::command]
+async fn get_anomalies(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    tenant_id: Option<String>,
+) -> Result<Vec<database::AnomalyDay>, String> {
+    state.db
+        .run_blocking(tenant_id.as_deref(), move |db| db.get_anomalies(&start_date, &end_date))
+        .await
+}
+
+/// `get_analytics` for long ranges: pushes the partial analytics to `on_progress`
+/// as each section finishes, so the dashboard can show how far along it is
+#[tauri::command]
+async fn get_analytics_streamed(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    refresh: Option<bool>,
+    tenant_id: Option<String>,
+    on_progress: Channel<database::AnalyticsProgress>,
+) -> Result<database::AnalyticsData, String> {
+    let refresh = refresh.unwrap_or(false);
+    state.db
+        .run_blocking(tenant_id.as_deref(), move |db| {
+            db.get_analytics_with_progress(&start_date, &end_date, refresh, |progress| {
+                if let Err(e) = on_progress.send(progress) {
+                    log::warn!("Failed to send analytics progress: {}", e);
+                }
+            })
+        })
+        .await
+}
+
+/// Place or release a legal hold on a call so retention purges keep it
+#[tauri::command]
+async fn set_legal_hold(
+    state: State<'_, AppState>,
+    call_id: String,
+    on: bool,
+    tenant_id: Option<String>,
+) -> Result<(), String> {
+    let id = call_id.clone();
+    let found = state.db.run_blocking(tenant_id.as_deref(), move |db| db.set_legal_hold(&id, on)).await?;
+    if !found {
+        return Err(format!("Call not found: {}", call_id));
+    }
+    log::info!("Legal hold {} for call {}", if on { "placed" } else { "released" }, call_id);
+    Ok(())
+}
+
+/// List calls under legal hold
+#[tauri::command]
+async fn get_legal_holds(
+    state: State<'_, AppState>,
+    tenant_id: Option<String>,
+) -> Result<Vec<database::LegalHold>, String> {
+    state.db.run_blocking(tenant_id.as_deref(), |db| db.get_legal_holds()).await
+}
+
+/// Delete data older than the retention period, keeping calls under legal hold
+#[tauri::command]
+async fn purge_old_data(
+    state: State<'_, AppState>,
+    older_than_days: u32,
+    tenant_id: Option<String>,
+) -> Result<database::PurgeSummary, String> {
+    let summary = state.db
+        .run_blocking(tenant_id.as_deref(), move |db| db.purge_old_data(older_than_days))
+        .await?;
+    log::info!(
+        "Purged data older than {} days: {} alerts, {} calls ({} held calls kept)",
+        older_than_days, summary.alerts_deleted, summary.calls_deleted, summary.held_calls_skipped
+    );
+    Ok(summary)
+}
+
+/// Delete everything stored about a consumer's calls (right-to-delete),
+/// except calls under legal hold, which are reported back
+#[tauri::command]
+async fn delete_consumer_data(
+    state: State<'_, AppState>,
+    phone: String,
+    tenant_id: Option<String>,
+) -> Result<database::DeletionReport, String> {
+    // Non-US numbers are stored as typed (trimmed), so match them the same way
+    let phone = dnc::normalize_phone(&phone).unwrap_or_else(|| phone.trim().to_string());
+    if phone.is_empty() {
+        return Err("A phone number is required".to_string());
+    }
+    let report = state.db
+        .run_blocking(tenant_id.as_deref(), move |db| db.delete_consumer_data(&phone))
+        .await?;
+    log::info!(
+        "Consumer deletion: {} calls, {} alerts, {} milestones removed ({} held calls kept)",
+        report.calls_deleted, report.alerts_deleted, report.milestones_deleted, report.held_call_ids.len()
+    );
+    Ok(report)
+}
+
+/// Most common phrasings behind a rule's alerts, for targeted coaching
+#[tauri::command]
+async fn top_quotes_for_rule(
+    state: State<'_, AppState>,
+    rule_id: String,
+    limit: u32,
+    start_date: String,
+    end_date: String,
+    tenant_id: Option<String>,
+) -> Result<Vec<database::QuoteCount>, String> {
+    state.db.run_blocking(tenant_id.as_deref(), move |db| {
+        db.top_quotes_for_rule(&rule_id, limit, &start_date, &end_date)
+    }).await
+}
+
+/// How often each enabled rule fired in the range, including rules that never
+/// did, to find over-specific or irrelevant rules
+#[tauri::command]
+async fn get_rule_coverage(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    tenant_id: Option<String>,
+) -> Result<Vec<database::RuleCoverage>, String> {
+    let rule_ids: Vec<String> = state.rules.read().unwrap().get_enabled_rules()
+        .iter()
+        .map(|r| r.id.clone())
+        .collect();
+    state.db.run_blocking(tenant_id.as_deref(), move |db| {
+        db.get_rule_coverage(&start_date, &end_date, &rule_ids)
+    }).await
+}
+
+/// Estimate financial exposure from the alerts in a date range
+#[tauri::command]
+async fn estimate_exposure(
+    state: State<'_, AppState>,
+    start_date: String,
+    end_date: String,
+    tenant_id: Option<String>,
+) -> Result<ExposureEstimate, String> {
+    let analytics = state.db
+        .run_blocking(tenant_id.as_deref(), move |db| db.get_analytics(&start_date, &end_date, false))
+        .await?;
+    let penalties = state.penalty_table.lock().unwrap();
+    Ok(exposure::estimate_exposure(&analytics.alerts_by_rule, &penalties))
+}
+
+/// Replace the penalty table used for exposure estimates from a CSV or JSON file
+#[tauri::command]
+async fn import_penalty_table(state: State<'_, AppState>, path: String) -> Result<Vec<PenaltyTableEntry>, String> {
+    let content = tokio::fs::read_to_string(&path).await
+        .map_err(|e| format!("Failed to read penalty table {}: {}", path, e))?;
+    let table = exposure::parse_penalty_table(std::path::Path::new(&path), &content)?;
+    let persisted = table.clone();
+    state.db.run_blocking(None, move |db| db.set_penalty_table(&persisted)).await?;
+    
+    log::info!("Imported penalty table with {} rules from {}", table.len(), path);
+    let entries = exposure::effective_table(&state.rules.read().unwrap(), &table);
+    *state.penalty_table.lock().unwrap() = table;
+    Ok(entries)
+}
+
+/// Penalty range for every rule, marking which come from the imported table
+#[tauri::command]
+async fn get_penalty_table(state: State<'_, AppState>) -> Result<Vec<PenaltyTableEntry>, String> {
+    let penalties = state.penalty_table.lock().unwrap();
+    Ok(exposure::effective_table(&state.rules.read().unwrap(), &penalties))
+}
+
+/// Export alerts to JSON. With `locale` (e.g. "de-DE") or `tz_offset`
+/// (minutes east of UTC), each alert also gets `created_at_local` and
+/// `confidence_local`; the raw UTC `created_at` is always kept.
+#[tauri::command]
+async fn export_alerts_json(
+    state: State<'_, AppState>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    tenant_id: Option<String>,
+    locale: Option<String>,
+    tz_offset: Option<i32>,
+) -> Result<String, String> {
+    let localize = locale.is_some() || tz_offset.is_some();
+    let export_locale = ExportLocale::new(locale.as_deref(), tz_offset)?;
+    let alerts = state.db.run_blocking(tenant_id.as_deref(), move |db| {
+        db.get_alerts(&database::AlertQuery { start_date, end_date, ..Default::default() }, None, None)
+    }).await?;
+    
+    if localize {
+        serde_json::to_string_pretty(&locale::localize_alerts(alerts, &export_locale)).map_err(|e| e.to_string())
+    } else {
+        serde_json::to_string_pretty(&alerts).map_err(|e| e.to_string())
+    }
+}
+
+/// Export alerts to CSV with both the raw UTC and the localized `created_at`.
+/// Comma-decimal locales use `;` as the field separator.
+#[tauri::command]
+async fn export_alerts_csv(
+    state: State<'_, AppState>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    tenant_id: Option<String>,
+    locale: Option<String>,
+    tz_offset: Option<i32>,
+) -> Result<String, String> {
+    let export_locale = ExportLocale::new(locale.as_deref(), tz_offset)?;
+    let alerts = state.db.run_blocking(tenant_id.as_deref(), move |db| {
+        db.get_alerts(&database::AlertQuery { start_date, end_date, ..Default::default() }, None, None)
+    }).await?;
+    Ok(locale::alerts_to_csv(&alerts, &export_locale))
+}
+
+/// Export one row per call for a workforce-management QA import: agent, call,
+/// hit count per rule category and a composite QA score, laid out by the
+/// configured column mapping
+#[tauri::command]
+async fn export_alerts_wfm(
+    state: State<'_, AppState>,
+    format: WfmFormat,
+    start_date: String,
+    end_date: String,
+    tenant_id: Option<String>,
+) -> Result<String, String> {
+    let calls = state.db.run_blocking(tenant_id.as_deref(), move |db| {
+        db.get_call_alert_summaries(&start_date, &end_date)
+    }).await?;
+    
+    let rules = state.rules.read().unwrap();
+    let columns = state.wfm_columns.lock().unwrap().clone()
+        .unwrap_or_else(|| wfm::default_columns(&rules));
+    wfm::render(&calls, &rules, &columns, format)
+}
+
+/// Column mapping used by `export_alerts_wfm`
+#[tauri::command]
+async fn get_wfm_columns(state: State<'_, AppState>) -> Result<Vec<WfmColumn>, String> {
+    let columns = state.wfm_columns.lock().unwrap().clone();
+    Ok(columns.unwrap_or_else(|| wfm::default_columns(&state.rules.read().unwrap())))
+}
+
+/// Map export fields to a WFM vendor's column headers; `None` restores the default layout
+#[tauri::command]
+async fn set_wfm_columns(state: State<'_, AppState>, columns: Option<Vec<WfmColumn>>) -> Result<(), String> {
+    if let Some(ref columns) = columns {
+        wfm::validate_columns(columns)?;
+    }
+    *state.wfm_columns.lock().unwrap() = columns;
+    Ok(())
+}
+
+/// Get the rules, optionally only those that run (`enabled_only`) and without
+/// the optional modules (`include_optional: false`). Defaults return every rule.
+#[tauri::command]
+async fn get_rules(
+    state: State<'_, AppState>,
+    enabled_only: Option<bool>,
+    include_optional: Option<bool>,
+) -> Result<Vec<Rule>, String> {
+    let rules = state.rules.read().unwrap();
+    let candidates: Vec<&Rule> = if enabled_only.unwrap_or(false) {
+        rules.get_enabled_rules()
+    } else {
+        rules.rules.iter().collect()
+    };
+    let include_optional = include_optional.unwrap_or(true);
+    Ok(candidates.into_iter()
+        .filter(|r| include_optional || !r.optional)
+        .cloned()
+        .collect())
+}
+
+/// Enable or disable every rule in a category, persisting the change.
+/// Returns the IDs of the affected rules.
+#[tauri::command]
+async fn set_category_enabled(
+    state: State<'_, AppState>,
+    category: RuleCategory,
+    enabled: bool,
+) -> Result<Vec<String>, String> {
+    let affected = state.rules.write().unwrap().set_category_enabled(&category, enabled);
+    
+    let rule_ids = affected.clone();
+    state.db.run_blocking(None, move |db| db.set_rule_overrides(&rule_ids, enabled)).await?;
+    
+    log::info!("Set {:?} rules enabled={}: {:?}", category, enabled, affected);
+    Ok(affected)
+}
+
+/// Replace a campaign's allowlist of approved script phrases. Phrases matched
+/// verbatim in the transcript don't raise alerts; an empty list clears it.
+#[tauri::command]
+async fn set_script_allowlist(
+    state: State<'_, AppState>,
+    campaign_id: String,
+    phrases: Vec<String>,
+) -> Result<(), String> {
+    let (campaign, allowlist) = (campaign_id.clone(), phrases.clone());
+    state.db.run_blocking(None, move |db| db.set_script_allowlist(&campaign, &allowlist)).await?;
+    
+    state.evaluator.set_script_allowlist(&campaign_id, &phrases);
+    log::info!("Set script allowlist for campaign {}: {} phrases", campaign_id, phrases.len());
+    Ok(())
+}
+
+/// Load a DNC list file, replacing the stored list of the same format. Call
+/// sessions are scrubbed against every loaded list on start.
+#[tauri::command]
+async fn load_dnc_list(
+    state: State<'_, AppState>,
+    path: String,
+    format: DncListFormat,
+    tenant_id: Option<String>,
+) -> Result<DncLoadResult, String> {
+    let content = tokio::fs::read_to_string(&path).await
+        .map_err(|e| format!("Failed to read DNC list {}: {}", path, e))?;
+    let (numbers, skipped) = dnc::parse_dnc_list(&content, format);
+    if numbers.is_empty() {
+        return Err(format!("No phone numbers found in {}", path));
+    }
+    
+    let loaded = numbers.len();
+    state.db.run_blocking(tenant_id.as_deref(), move |db| db.replace_dnc_list(format.as_str(), &numbers)).await?;
+    log::info!("Loaded {} DNC list from {}: {} numbers, {} lines skipped", format.as_str(), path, loaded, skipped);
+    Ok(DncLoadResult { format, loaded, skipped })
+}
+
+/// Set an organization's approved `why_it_matters` and fix wording for a
+/// rule's alerts, from both the rules engine and the LLM. Either may be left
+/// out to keep the rule's own text; leaving out both removes the override.
+#[tauri::command]
+async fn set_alert_text_override(
+    state: State<'_, AppState>,
+    rule_id: String,
+    why_it_matters: Option<String>,
+    fix: Option<String>,
+) -> Result<(), String> {
+    if state.rules.read().unwrap().get_rule(&rule_id).is_none() {
+        return Err(format!("Unknown rule: {}", rule_id));
+    }
+    let non_empty = |text: Option<String>| text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let text = match (non_empty(why_it_matters), non_empty(fix)) {
+        (None, None) => None,
+        (why_it_matters, agent_fix_suggestion) => Some(AlertTextOverride { why_it_matters, agent_fix_suggestion }),
+    };
+    
+    let (id, stored) = (rule_id.clone(), text.clone());
+    state.db.run_blocking(None, move |db| db.set_alert_text_override(&id, stored.as_ref())).await?;
+    
+    log::info!("Set alert text override for {}: {}", rule_id, if text.is_some() { "custom" } else { "cleared" });
+    state.evaluator.set_alert_text_override(&rule_id, text);
+    Ok(())
+}
+
+/// Alert wording overrides, keyed by rule ID
+#[tauri::command]
+async fn get_alert_text_overrides(state: State<'_, AppState>) -> Result<HashMap<String, AlertTextOverride>, String> {
+    Ok(state.evaluator.alert_text_overrides())
+}
+
+/// Bundle the active rules, overrides, LLM and evaluator settings into a snapshot
+#[tauri::command]
+async fn export_config(state: State<'_, AppState>) -> Result<ConfigSnapshot, String> {
+    let rule_overrides = state.db.run_blocking(None, |db| db.get_rule_overrides()).await?;
+    let llm = state.llm.read().await;
+    
+    Ok(ConfigSnapshot {
+        format_version: CONFIG_SNAPSHOT_VERSION,
+        rules: state.rules.read().unwrap().clone(),
+        rule_overrides,
+        llm_model: llm.get_model().to_string(),
+        llm_endpoint: llm.get_endpoint().to_string(),
+        llm_options: llm.get_options().clone(),
+        llm_fallback_policy: *state.llm_fallback_policy.lock().unwrap(),
+        min_transcript_chars: state.evaluator.min_transcript_chars(),
+        max_disclosure_wpm: state.evaluator.max_disclosure_wpm(),
+        max_monologue_secs: state.evaluator.max_monologue_secs(),
+        disclosure_grace_chars: state.evaluator.disclosure_grace_chars(),
+        min_diarization_confidence: state.evaluator.min_diarization_confidence(),
+        sentence_quotes: state.evaluator.sentence_quotes(),
+        priority_policy: state.evaluator.priority_policy(),
+        escalation_policy: *state.escalation_policy.lock().unwrap(),
+        ack_sla: *state.ack_sla.lock().unwrap(),
+        wfm_columns: state.wfm_columns.lock().unwrap().clone(),
+        calling_windows: state.evaluator.calling_windows(),
+        evaluation_debounce_ms: state.evaluation_debounce.lock().unwrap().as_millis() as u64,
+        hash_phone_numbers: *state.hash_phone_numbers.lock().unwrap(),
+        semantic: state.semantic.settings(),
+        max_transcript_chars: *state.max_transcript_chars.lock().unwrap(),
+        script_allowlists: state.evaluator.script_allowlists(),
+        alert_text_overrides: state.evaluator.alert_text_overrides(),
+    })
+}
+
+/// Apply a snapshot from `export_config`. The snapshot is validated and persisted
+/// before anything in memory changes, so a bad snapshot leaves the setup untouched.
+#[tauri::command]
+async fn import_config(state: State<'_, AppState>, snapshot: ConfigSnapshot) -> Result<(), String> {
+    if snapshot.format_version > CONFIG_SNAPSHOT_VERSION {
+        return Err(format!(
+            "Config snapshot format {} is newer than supported format {}",
+            snapshot.format_version, CONFIG_SNAPSHOT_VERSION
+        ));
+    }
+    if snapshot.max_disclosure_wpm == 0 {
+        return Err("max_disclosure_wpm must be greater than zero".to_string());
+    }
+    if snapshot.max_transcript_chars == 0 {
+        return Err("max_transcript_chars must be greater than zero".to_string());
+    }
+    validate_diarization_confidence(snapshot.min_diarization_confidence)?;
+    snapshot.priority_policy.validate()?;
+    snapshot.semantic.validate()?;
+    snapshot.escalation_policy.validate()?;
+    snapshot.ack_sla.validate()?;
+    if let Some(ref columns) = snapshot.wfm_columns {
+        wfm::validate_columns(columns)?;
+    }
+    for window in snapshot.calling_windows.values().flatten() {
+        window.validate()?;
+    }
+    snapshot.rules.validate()?;
+    
+    // Everything is validated; write it all or nothing
+    let (overrides, allowlists) = (snapshot.rule_overrides.clone(), snapshot.script_allowlists.clone());
+    let alert_text = snapshot.alert_text_overrides.clone();
+    let ruleset = snapshot.rules.clone();
+    let settings = [(HASH_PHONE_NUMBERS_SETTING, snapshot.hash_phone_numbers.to_string())];
+    state.db.run_blocking(None, move |db| {
+        db.replace_config(&overrides, &allowlists, &alert_text, &settings, &ruleset)
+    }).await?;
+    
+    let mut llm = state.llm.write().await;
+    *llm = LlmClient::new(Some(snapshot.llm_endpoint), Some(snapshot.llm_model));
+    llm.set_options(snapshot.llm_options);
+    *state.llm_enabled.lock().unwrap() = false; // Require re-check against the new endpoint
+    
+    *state.rules.write().unwrap() = snapshot.rules;
+    *state.llm_fallback_policy.lock().unwrap() = snapshot.llm_fallback_policy;
+    *state.escalation_policy.lock().unwrap() = snapshot.escalation_policy;
+    *state.ack_sla.lock().unwrap() = snapshot.ack_sla;
+    *state.wfm_columns.lock().unwrap() = snapshot.wfm_columns;
+    *state.evaluation_debounce.lock().unwrap() = Duration::from_millis(snapshot.evaluation_debounce_ms);
+    *state.hash_phone_numbers.lock().unwrap() = snapshot.hash_phone_numbers;
+    state.semantic.set_settings(snapshot.semantic);
+    *state.max_transcript_chars.lock().unwrap() = snapshot.max_transcript_chars;
+    state.evaluator.set_min_transcript_chars(snapshot.min_transcript_chars);
+    state.evaluator.set_max_disclosure_wpm(snapshot.max_disclosure_wpm);
+    state.evaluator.set_max_monologue_secs(snapshot.max_monologue_secs);
+    state.evaluator.set_disclosure_grace_chars(snapshot.disclosure_grace_chars);
+    state.evaluator.set_min_diarization_confidence(snapshot.min_diarization_confidence);
+    state.evaluator.set_sentence_quotes(snapshot.sentence_quotes);
+    state.evaluator.set_priority_policy(snapshot.priority_policy);
+    state.evaluator.replace_calling_windows(snapshot.calling_windows);
+    state.evaluator.replace_script_allowlists(&snapshot.script_allowlists);
+    state.evaluator.replace_alert_text_overrides(snapshot.alert_text_overrides);
+    
+    log::info!("Imported config snapshot (format {})", snapshot.format_version);
+    Ok(())
+}
+
+/// Replace the active rules with a ruleset from a central rules service.
+/// Persisted enable/disable overrides are applied on top, as on startup.
+#[tauri::command]
+async fn load_remote_rules(state: State<'_, AppState>, url: String) -> Result<Vec<Rule>, String> {
+    let mut ruleset = remote_rules::load_rules_from_url(&url).await?;
+    let overrides = state.db.run_blocking(None, |db| db.get_rule_overrides()).await?;
+    ruleset.apply_overrides(&overrides);
+    
+    let retained = ruleset.clone();
+    state.db.run_blocking(None, move |db| db.retain_ruleset(&retained)).await?;
+    
+    let rules = ruleset.rules.clone();
+    *state.rules.write().unwrap() = ruleset;
+    Ok(rules)
+}
+
+/// Get rules as YAML
+#[tauri::command]
+async fn get_rules_yaml(state: State<'_, AppState>) -> Result<String, String> {
+    Ok(state.rules.read().unwrap().to_yaml())
+}
+
+/// Get the rules as the LLM sees them in its prompt, including the disclaimer
+#[tauri::command]
+async fn get_llm_rules_view(state: State<'_, AppState>) -> Result<String, String> {
+    Ok(state.rules.read().unwrap().to_llm_view())
+}
+
+/// Check call metadata for risk before dialing, without any transcript
+#[tauri::command]
+async fn precheck_call(state: State<'_, AppState>, metadata: CallMetadata) -> Result<Vec<Alert>, String> {
+    state.evaluator.precheck(&metadata, &state.rules.read().unwrap())
+}
+
+/// Apply corrected metadata to a call in progress and re-run the metadata rules
+/// (calling time, DNC list, prerecorded consent), returning any new alerts
+#[tauri::command]
+async fn update_call_metadata(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    call_id: String,
+    metadata: CallMetadata,
+) -> Result<Vec<Alert>, String> {
+    if metadata.call_id != call_id {
+        return Err(format!("Metadata is for call {}, not {}", metadata.call_id, call_id));
+    }
+    
+    if !state.is_training_call(metadata.tenant_id.as_deref(), &call_id) {
+        let tenant_id = metadata.tenant_id.clone();
+        let updated = metadata.clone();
+        let hash_phone = *state.hash_phone_numbers.lock().unwrap();
+        let found = state.db
+            .run_blocking(tenant_id.as_deref(), move |db| db.update_call_metadata(&updated, hash_phone))
+            .await?;
+        if !found {
+            return Err(format!("Call not found: {}", call_id));
+        }
+    }
+    
+    let alerts = state.evaluator.update_metadata(&metadata, &state.rules.read().unwrap())?;
+    log::info!("Updated metadata for call {}: {} new alerts", call_id, alerts.len());
+    if !alerts.is_empty() {
+        let event = MetadataAlertsEvent {
+            call_id,
+            alerts: alerts.clone(),
+        };
+        if let Err(e) = app.emit(METADATA_ALERTS_EVENT, event) {
+            log::warn!("Failed to emit {} event: {}", METADATA_ALERTS_EVENT, e);
+        }
+    }
+    Ok(alerts)
+}
+
+/// Check call metadata for missing or malformed fields before starting a session
+#[tauri::command]
+async fn validate_metadata(metadata: CallMetadata) -> Result<Vec<MetadataIssue>, String> {
+    Ok(metadata.validate())
+}
+
+/// Start a call session
+#[tauri::command]
+async fn start_call_session(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    mut metadata: CallMetadata,
+    training_mode: Option<bool>,
+) -> Result<String, String> {
+    let errors: Vec<String> = metadata.validate().into_iter()
+        .filter(|issue| issue.severity == "error")
+        .map(|issue| format!("{}: {}", issue.field, issue.message))
+        .collect();
+    if !errors.is_empty() {
+        return Err(format!("Invalid call metadata: {}", errors.join("; ")));
+    }
+    
+    // Scrub against the loaded DNC lists; a match overrides the metadata flag
+    let dnc_lists = match metadata.customer_phone.as_deref().and_then(dnc::normalize_phone) {
+        Some(phone) => state.db.run_blocking(metadata.tenant_id.as_deref(), move |db| db.dnc_lists_for(&phone)).await?,
+        None => Vec::new(),
+    };
+    let scrub_matched = !dnc_lists.is_empty() && !metadata.is_dnc_listed;
+    if scrub_matched {
+        log::info!("Call {}: number is on the {} DNC list", metadata.call_id, dnc_lists.join(" and "));
+        metadata.is_dnc_listed = true;
+    }
+    
+    let is_new = if training_mode.unwrap_or(false) {
+        state.mark_training_call(metadata.tenant_id.as_deref(), &metadata.call_id)
+    } else {
+        let session = metadata.clone();
+        let hash_phone = *state.hash_phone_numbers.lock().unwrap();
+        state.db
+            .run_blocking(metadata.tenant_id.as_deref(), move |db| db.start_call_session(&session, hash_phone))
+            .await?
+    };
+    
+    state.open_calls.lock().unwrap().insert(call_key(metadata.tenant_id.as_deref(), &metadata.call_id));
+    if is_new {
+        // Reset evaluator state for this call only
+        state.evaluator.reset_call(metadata.tenant_id.as_deref(), &metadata.call_id);
+        log::info!("Started call session: {}", metadata.call_id);
+    } else {
+        // Reconnect with the same call_id: keep the existing session and state
+        log::info!("Resumed call session: {}", metadata.call_id);
+    }
+    
+    // Raise DNC-003 now rather than waiting for the first evaluation
+    if scrub_matched {
+        let alerts = state.evaluator.update_metadata(&metadata, &state.rules.read().unwrap())?;
+        if !alerts.is_empty() {
+            let event = MetadataAlertsEvent {
+                call_id: metadata.call_id.clone(),
+                alerts,
+            };
+            if let Err(e) = app.emit(METADATA_ALERTS_EVENT, event) {
+                log::warn!("Failed to emit {} event: {}", METADATA_ALERTS_EVENT, e);
+            }
+        }
+    }
+    
+    Ok(metadata.call_id)
+}
+
+/// End a call session, returning (and storing) any end-of-call alerts
+#[tauri::command]
+async fn end_call_session(
+    state: State<'_, AppState>,
+    call_id: String,
+    tenant_id: Option<String>,
+) -> Result<Vec<Alert>, String> {
+    let alerts = state.evaluator.finalize_call(tenant_id.as_deref(), &call_id, &state.rules.read().unwrap());
+    
+    let milestones = state.evaluator.disclosure_milestones(tenant_id.as_deref(), &call_id);
+    let key = call_key(tenant_id.as_deref(), &call_id);
+    let talk_time = state.segment_buffers.lock().unwrap().remove(&key).and_then(|mut buffer| {
+        buffer.flush();
+        transcript::talk_time(buffer.segments_mut(), state.evaluator.min_diarization_confidence())
+    });
+    
+    let was_training = state.training_calls.lock().unwrap().remove(&key).is_some();
+    if !was_training {
+        let (id, tenant, end_alerts) = (call_id.clone(), tenant_id.clone(), alerts.clone());
+        let policy = *state.escalation_policy.lock().unwrap();
+        state.db.run_blocking(tenant_id.as_deref(), move |db| {
+            db.finish_call(&id, tenant, &end_alerts, &milestones, talk_time.as_ref(), &policy)
+        }).await?;
+    }
+    state.evaluator.reset_call(tenant_id.as_deref(), &call_id);
+    state.open_calls.lock().unwrap().remove(&key);
+    state.recent_evaluations.lock().unwrap().retain(|key, _| key.call_id != call_id || key.tenant_id != tenant_id);
+    state.semantic.forget_call(tenant_id.as_deref(), &call_id);
+    log::info!("Ended call session: {} ({} end-of-call alerts)", call_id, alerts.len());
+    Ok(alerts)
+}
+
+/// A call's alerts and disclosure milestones in order, between call start and
+/// end anchors, for review playback
+#[tauri::command]
+async fn get_call_timeline(
+    state: State<'_, AppState>,
+    call_id: String,
+    tenant_id: Option<String>,
+) -> Result<Vec<TimelineEvent>, String> {
+    let metadata_rule_ids: Vec<String> = state.rules.read().unwrap().rules.iter()
+        .filter(|r| r.requires_metadata)
+        .map(|r| r.id.clone())
+        .collect();
+    let id = call_id.clone();
+    state.db.run_blocking(tenant_id.as_deref(), move |db| db.get_call_timeline(&id, &metadata_rule_ids)).await?
+        .ok_or_else(|| format!("Unknown call: {}", call_id))
+}
+
+/// Current risk gauge for a call in progress, for polling alongside evaluation
+#[tauri::command]
+async fn get_live_risk(state: State<'_, AppState>, call_id: String, tenant_id: Option<String>) -> Result<LiveRisk, String> {
+    Ok(state.evaluator.live_risk(tenant_id.as_deref(), &call_id))
+}
+
+/// Live checklist of the required disclosures for a call in progress and
+/// whether each is done, pending or violated, for polling alongside evaluation
+#[tauri::command]
+async fn get_checklist(
+    state: State<'_, AppState>,
+    call_id: String,
+    tenant_id: Option<String>,
+) -> Result<Vec<ChecklistItem>, String> {
+    Ok(state.evaluator.checklist(tenant_id.as_deref(), &call_id, &state.rules.read().unwrap()))
+}
+
+/// Set the transcript length below which only High-severity rules run
+#[tauri::command]
+async fn set_min_transcript_chars(state: State<'_, AppState>, chars: usize) -> Result<(), String> {
+    state.evaluator.set_min_transcript_chars(chars);
+    Ok(())
+}
+
+/// Set the words-per-minute above which a disclosure is flagged as rushed
+#[tauri::command]
+async fn set_max_disclosure_wpm(state: State<'_, AppState>, wpm: u32) -> Result<(), String> {
+    if wpm == 0 {
+        return Err("wpm must be greater than zero".to_string());
+    }
+    state.evaluator.set_max_disclosure_wpm(wpm);
+    Ok(())
+}
+
+/// Set the TIME-001 calling window for a call type, or exempt it with `None`.
+/// Call types without an entry are held to telemarketing hours.
+#[tauri::command]
+async fn set_calling_window(
+    state: State<'_, AppState>,
+    call_type: String,
+    window: Option<CallingWindow>,
+) -> Result<(), String> {
+    if let Some(ref window) = window {
+        window.validate()?;
+    }
+    state.evaluator.set_calling_window(&call_type, window);
+    Ok(())
+}
+
+/// Calling window per call type used by TIME-001
+#[tauri::command]
+async fn get_calling_windows(state: State<'_, AppState>) -> Result<HashMap<String, Option<CallingWindow>>, String> {
+    Ok(state.evaluator.calling_windows())
+}
+
+/// Set how many seconds the agent may talk past a disclosure needing a response
+#[tauri::command]
+async fn set_max_monologue_secs(state: State<'_, AppState>, secs: u64) -> Result<(), String> {
+    state.evaluator.set_max_monologue_secs(secs);
+    Ok(())
+}
+
+/// Set the opening stretch of a call, in characters, in which seller-identity
+/// and sales-purpose disclosures can't be flagged late or missing
+#[tauri::command]
+async fn set_disclosure_grace_chars(state: State<'_, AppState>, chars: usize) -> Result<(), String> {
+    state.evaluator.set_disclosure_grace_chars(chars);
+    Ok(())
+}
+
+/// Set the diarization confidence below which a segment's speaker label isn't
+/// trusted and the segment is checked against both agent and customer rules
+#[tauri::command]
+async fn set_min_diarization_confidence(state: State<'_, AppState>, confidence: f64) -> Result<(), String> {
+    validate_diarization_confidence(confidence)?;
+    state.evaluator.set_min_diarization_confidence(confidence);
+    Ok(())
+}
+
+fn validate_diarization_confidence(confidence: f64) -> Result<(), String> {
+    if !(0.0..=1.0).contains(&confidence) {
+        return Err("min_diarization_confidence must be between 0 and 1".to_string());
+    }
+    Ok(())
+}
+
+/// Quote whole sentences as evidence instead of a fixed-width window
+#[tauri::command]
+async fn set_sentence_quotes(state: State<'_, AppState>, enabled: bool) -> Result<(), String> {
+    state.evaluator.set_sentence_quotes(enabled);
+    Ok(())
+}
+
+/// Set how strongly confidence raises or lowers an alert's display priority
+#[tauri::command]
+async fn set_priority_policy(state: State<'_, AppState>, policy: PriorityPolicy) -> Result<(), String> {
+    policy.validate()?;
+    state.evaluator.set_priority_policy(policy);
+    Ok(())
+}
+
+/// Reset evaluator state for all calls
 #[tauri::command]
 async fn reset_evaluator(state: State<'_, AppState>) -> Result<(), String> {
     state.evaluator.reset();
+    state.recent_evaluations.lock().unwrap().clear();
+    state.training_calls.lock().unwrap().clear();
+    state.semantic.forget_calls();
+    Ok(())
+}
+
+/// Reset evaluator state for a single call
+#[tauri::command]
+async fn reset_call_state(state: State<'_, AppState>, call_id: String, tenant_id: Option<String>) -> Result<(), String> {
+    state.evaluator.reset_call(tenant_id.as_deref(), &call_id);
+    state.recent_evaluations.lock().unwrap().retain(|key, _| key.call_id != call_id || key.tenant_id != tenant_id);
+    state.semantic.forget_call(tenant_id.as_deref(), &call_id);
+    state.training_calls.lock().unwrap().remove(&call_key(tenant_id.as_deref(), &call_id));
     Ok(())
 }
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // Initialize database
-    let db = Database::new().expect("Failed to initialize database");
+    let db = TenantDatabases::new().expect("Failed to initialize database");
     
-    // Load ruleset
-    let rules = RuleSet::load_default().expect("Failed to load rules");
+    // Load ruleset and apply persisted enable/disable overrides. A ruleset
+    // fetched from a rules service earlier takes precedence over the defaults.
+    let mut rules = match remote_rules::load_cached_rules() {
+        Some((url, cached)) => {
+            log::info!("Using cached remote rules from {}", url);
+            cached
+        }
+        None => RuleSet::load_default().expect("Failed to load rules"),
+    };
+    match db.for_tenant(None).and_then(|d| d.lock().map_err(|e| e.to_string())?.get_rule_overrides().map_err(|e| e.to_string())) {
+        Ok(overrides) => rules.apply_overrides(&overrides),
+        Err(e) => log::warn!("Failed to load rule overrides: {}", e),
+    }
+    if let Err(e) = db.for_tenant(None).and_then(|d| d.lock().map_err(|e| e.to_string())?.retain_ruleset(&rules).map_err(|e| e.to_string())) {
+        log::warn!("Failed to retain ruleset version {}: {}", rules.version, e);
+    }
     
-    // Create evaluator
+    // Create evaluator with persisted script allowlists
     let evaluator = ComplianceEvaluator::new();
+    match db.for_tenant(None).and_then(|d| d.lock().map_err(|e| e.to_string())?.get_script_allowlists().map_err(|e| e.to_string())) {
+        Ok(allowlists) => {
+            for (campaign_id, phrases) in allowlists {
+                evaluator.set_script_allowlist(&campaign_id, &phrases);
+            }
+        }
+        Err(e) => log::warn!("Failed to load script allowlists: {}", e),
+    }
+    match db.for_tenant(None).and_then(|d| d.lock().map_err(|e| e.to_string())?.get_alert_text_overrides().map_err(|e| e.to_string())) {
+        Ok(overrides) => evaluator.replace_alert_text_overrides(overrides),
+        Err(e) => log::warn!("Failed to load alert text overrides: {}", e),
+    }
+    
+    let penalty_table = match db.for_tenant(None).and_then(|d| d.lock().map_err(|e| e.to_string())?.get_penalty_table().map_err(|e| e.to_string())) {
+        Ok(table) => table,
+        Err(e) => {
+            log::warn!("Failed to load penalty table: {}", e);
+            HashMap::new()
+        }
+    };
+    
+    let hash_phone_numbers = match db.for_tenant(None).and_then(|d| d.lock().map_err(|e| e.to_string())?.get_setting(HASH_PHONE_NUMBERS_SETTING).map_err(|e| e.to_string())) {
+        Ok(value) => value.as_deref() == Some("true"),
+        Err(e) => {
+            log::warn!("Failed to load phone hashing setting: {}", e);
+            false
+        }
+    };
     
     // Create LLM client
     let llm = LlmClient::new(None, None);
     
     // Create app state
     let app_state = AppState {
-        db: Mutex::new(db),
-        rules,
+        db,
+        rules: StdRwLock::new(rules),
         evaluator,
         llm: RwLock::new(llm),
         llm_enabled: Mutex::new(false),
+        recent_evaluations: Mutex::new(HashMap::new()),
+        evaluation_debounce: Mutex::new(Duration::from_millis(DEFAULT_EVALUATION_DEBOUNCE_MS)),
+        hash_phone_numbers: Mutex::new(hash_phone_numbers),
+        semantic: semantic::SemanticMatcher::new(),
+        max_transcript_chars: Mutex::new(DEFAULT_MAX_TRANSCRIPT_CHARS),
+        llm_fallback_policy: Mutex::new(LlmFallbackPolicy::default()),
+        llm_degraded: Mutex::new(false),
+        training_calls: Mutex::new(HashMap::new()),
+        llm_last_reconnect: Mutex::new(None),
+        penalty_table: Mutex::new(penalty_table),
+        segment_buffers: Mutex::new(HashMap::new()),
+        reorder_window: Mutex::new(Duration::from_millis(transcript::DEFAULT_REORDER_WINDOW_MS)),
+        escalation_policy: Mutex::new(EscalationPolicy::default()),
+        ack_sla: Mutex::new(AckSla::default()),
+        wfm_columns: Mutex::new(None),
+        open_calls: Mutex::new(HashSet::new()),
     };
     
     tauri::Builder::default()
@@ -295,22 +2550,156 @@ pub fn run() {
                 )?;
             }
             log::info!("Whisperwire started");
+            
+            let handle = app.handle().clone();
+            app.state::<AppState>().db.on_alert_stored(Arc::new(move |tenant_id, alert| {
+                emit_alert_stored(&handle, tenant_id, alert);
+            }));
+            
+            #[cfg(feature = "http-api")]
+            match http_api::bind_addr() {
+                Ok(addr) => {
+                    tauri::async_runtime::spawn(http_api::serve(app.handle().clone(), addr));
+                }
+                Err(e) => log::error!("HTTP API not started: {}", e),
+            }
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             check_llm_status,
+            pull_llm_model,
+            set_llm_auto_pull,
             set_llm_model,
+            warmup_llm,
+            preview_llm_prompt,
+            llm_stability_check,
+            evaluate_corpus_with_model,
+            benchmark_evaluation,
             evaluate_transcript,
+            set_evaluation_debounce_ms,
+            set_hash_phone_numbers,
+            set_semantic_matching,
+            set_max_transcript_chars,
+            set_llm_fallback_policy,
+            evaluate_transcript_file,
+            check_transcript_quality,
+            evaluate_with_ruleset_version,
+            list_ruleset_versions,
+            reevaluate_and_diff,
+            push_segment,
+            list_scenarios,
+            run_scenario,
+            set_reorder_window_ms,
             store_alert,
+            set_escalation_policy,
+            accept_suggestion,
+            get_suggestion_effectiveness,
             get_alerts,
+            get_alerts_by_tag,
+            get_recent_alerts,
+            add_alert_tag,
+            remove_alert_tag,
+            get_tag_counts,
+            get_alert_timestamp,
+            acknowledge_alert,
+            acknowledge_alerts_bulk,
+            set_ack_sla,
+            get_ack_sla_report,
             get_analytics,
+            get_analytics_streamed,
+            get_anomalies,
+            top_quotes_for_rule,
+            get_rule_coverage,
+            estimate_exposure,
+            import_penalty_table,
+            get_penalty_table,
             export_alerts_json,
+            export_alerts_csv,
+            export_alerts_wfm,
+            get_wfm_columns,
+            set_wfm_columns,
+            set_legal_hold,
+            get_legal_holds,
+            purge_old_data,
+            delete_consumer_data,
             get_rules,
+            set_category_enabled,
+            set_script_allowlist,
+            load_dnc_list,
+            set_alert_text_override,
+            get_alert_text_overrides,
+            export_config,
+            import_config,
             get_rules_yaml,
+            load_remote_rules,
+            get_llm_rules_view,
+            precheck_call,
+            validate_metadata,
             start_call_session,
+            update_call_metadata,
             end_call_session,
+            get_live_risk,
+            get_checklist,
+            get_call_timeline,
             reset_evaluator,
+            reset_call_state,
+            set_min_transcript_chars,
+            set_max_disclosure_wpm,
+            set_calling_window,
+            get_calling_windows,
+            set_max_monologue_secs,
+            set_disclosure_grace_chars,
+            set_min_diarization_confidence,
+            set_sentence_quotes,
+            set_priority_policy,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            if let RunEvent::Exit = event {
+                shutdown(&app.state::<AppState>());
+            }
+        });
+}
+
+/// On exit, end calls still in progress (evaluating any segments held for
+/// reordering and storing end-of-call alerts) and checkpoint every database's
+/// WAL, so nothing in memory is lost on a normal quit
+fn shutdown(state: &AppState) {
+    let open_calls: Vec<CallKey> = state.open_calls.lock().unwrap().drain().collect();
+    let mut buffers = std::mem::take(&mut *state.segment_buffers.lock().unwrap());
+    let rules = state.rules.read().unwrap().clone();
+    let policy = *state.escalation_policy.lock().unwrap();
+    
+    for key in open_calls {
+        let (tenant_id, call_id) = key.clone();
+        let mut alerts = Vec::new();
+        if let (Some(buffer), Some(metadata)) = (buffers.get_mut(&key), state.evaluator.call_metadata(tenant_id.as_deref(), &call_id)) {
+            if buffer.flush() {
+                match state.evaluator.evaluate_segments(&metadata, buffer.segments_mut(), &rules) {
+                    Ok(output) => alerts.extend(output.alerts),
+                    Err(e) => log::warn!("Failed to evaluate held segments for {}: {}", call_id, e),
+                }
+            }
+        }
+        let talk_time = buffers.get_mut(&key)
+            .and_then(|buffer| transcript::talk_time(buffer.segments_mut(), state.evaluator.min_diarization_confidence()));
+        alerts.extend(state.evaluator.finalize_call(tenant_id.as_deref(), &call_id, &rules));
+        if state.training_calls.lock().unwrap().remove(&key).is_some() {
+            continue;
+        }
+        
+        let milestones = state.evaluator.disclosure_milestones(tenant_id.as_deref(), &call_id);
+        let result = state.db.for_tenant(tenant_id.as_deref()).and_then(|db| {
+            let db = db.lock().map_err(|e| e.to_string())?;
+            db.finish_call(&call_id, tenant_id.clone(), &alerts, &milestones, talk_time.as_ref(), &policy)
+                .map_err(|e| e.to_string())
+        });
+        match result {
+            Ok(()) => log::info!("Ended call session on shutdown: {} ({} alerts)", call_id, alerts.len()),
+            Err(e) => log::error!("Failed to end call session {} on shutdown: {}", call_id, e),
+        }
+    }
+    
+    state.db.checkpoint_all();
 }
@@ -1,14 +1,51 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::Duration;
 
 /// LLM Client for connecting to local Ollama
+#[derive(Clone)]
 pub struct LlmClient {
     endpoint: String,
     model: String,
     enabled: bool,
+    options: LlmOptions,
+    /// Download a missing model automatically instead of reporting it
+    auto_pull: bool,
     client: reqwest::Client,
 }
 
+/// Outcome of checking the Ollama connection
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ConnectionStatus {
+    /// Ollama is reachable and the configured model is installed
+    Connected,
+    /// Ollama is reachable but the model isn't installed and auto-pull is off.
+    /// Ask the user before calling `pull_model`, it can be several gigabytes.
+    ModelNotInstalled { available_models: Vec<String> },
+    /// Ollama couldn't be reached, or returned an error
+    #[default]
+    Unavailable,
+}
+
+/// Sampling options sent with each evaluation request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmOptions {
+    pub temperature: f32,
+    pub top_p: f32,
+    pub num_predict: u32,
+}
+
+impl Default for LlmOptions {
+    fn default() -> Self {
+        LlmOptions {
+            temperature: 0.1,
+            top_p: 0.9,
+            num_predict: 2048,
+        }
+    }
+}
+
 /// LLM response structure matching our required output format
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmResponse {
@@ -40,6 +77,53 @@ pub struct LlmSuggestion {
     pub confidence: u8,
 }
 
+/// The exact prompts that would be sent to the LLM for an evaluation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptPreview {
+    pub system: String,
+    pub user: String,
+}
+
+/// Result of warming up the model before a shift
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmWarmup {
+    pub model: String,
+    pub latency_ms: u64,
+}
+
+/// How consistently each alert appeared across repeated LLM runs on the same input
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LlmStabilityReport {
+    pub model: String,
+    pub requested_runs: u32,
+    /// Runs that returned a parseable response; ratios are relative to this
+    pub completed_runs: u32,
+    pub alerts: Vec<AlertStability>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertStability {
+    pub rule_id: String,
+    /// Number of completed runs that raised this rule
+    pub appearances: u32,
+    pub appearance_ratio: f64,
+    pub mean_confidence: f64,
+    /// Population variance of the confidence across the runs that raised it
+    pub confidence_variance: f64,
+}
+
+/// Prefix of errors meaning Ollama couldn't be reached at all, as opposed to a
+/// bad response from a running server
+pub const LLM_CONNECTION_FAILED: &str = "LLM connection failed";
+
+/// Whether an evaluation error means the Ollama connection itself is gone
+pub fn is_connection_error(error: &str) -> bool {
+    error.starts_with(LLM_CONNECTION_FAILED)
+}
+
+/// Upper bound on runs for a stability check, since each run is a full evaluation
+pub const MAX_STABILITY_RUNS: u32 = 20;
+
 #[derive(Debug, Deserialize)]
 struct OllamaTagsResponse {
     models: Option<Vec<OllamaModel>>,
@@ -50,11 +134,25 @@ struct OllamaModel {
     name: String,
 }
 
+/// Where the time in one LLM evaluation went
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LlmTiming {
+    /// Sending the request until the whole response arrived: network plus generation
+    pub request_ms: f64,
+    /// Parsing the model's JSON output into alerts
+    pub parse_ms: f64,
+}
+
 #[derive(Debug, Deserialize)]
 struct OllamaGenerateResponse {
     response: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct OllamaEmbeddingResponse {
+    embedding: Vec<f32>,
+}
+
 impl LlmClient {
     /// Create a new LLM client
     pub fn new(endpoint: Option<String>, model: Option<String>) -> Self {
@@ -67,12 +165,15 @@ impl LlmClient {
             endpoint: endpoint.unwrap_or_else(|| "http://localhost:11434".to_string()),
             model: model.unwrap_or_else(|| "llama3.2:1b".to_string()),
             enabled: false,
+            options: LlmOptions::default(),
+            auto_pull: false,
             client,
         }
     }
     
-    /// Check if Ollama is available and has the required model
-    pub async fn check_connection(&mut self) -> Result<bool, String> {
+    /// Check if Ollama is available and has the required model. A missing model
+    /// is only downloaded when auto-pull is on.
+    pub async fn check_connection(&mut self) -> Result<ConnectionStatus, String> {
         let url = format!("{}/api/tags", self.endpoint);
         
         match self.client.get(&url).send().await {
@@ -85,29 +186,35 @@ impl LlmClient {
                             if model_available {
                                 self.enabled = true;
                                 log::info!("LLM connected: Ollama with model {}", self.model);
-                                return Ok(true);
-                            } else {
-                                log::warn!("Model {} not found in Ollama. Available models: {:?}", self.model, models.iter().map(|m| &m.name).collect::<Vec<_>>());
-                                // Try to pull the model
-                                return self.try_pull_model().await;
+                                return Ok(ConnectionStatus::Connected);
+                            }
+                            
+                            let available_models: Vec<String> = models.into_iter().map(|m| m.name).collect();
+                            log::warn!("Model {} not found in Ollama. Available models: {:?}", self.model, available_models);
+                            if self.auto_pull {
+                                return self.pull_model().await;
                             }
+                            self.enabled = false;
+                            return Ok(ConnectionStatus::ModelNotInstalled { available_models });
                         }
                     }
                     self.enabled = true;
-                    Ok(true)
+                    Ok(ConnectionStatus::Connected)
                 } else {
+                    self.enabled = false;
                     Err(format!("Ollama returned status: {}", resp.status()))
                 }
             }
             Err(e) => {
+                self.enabled = false;
                 log::warn!("Ollama not available: {}. Running in rules-only mode.", e);
                 Err(format!("Ollama connection failed: {}", e))
             }
         }
     }
     
-    /// Try to pull the model if not available
-    async fn try_pull_model(&mut self) -> Result<bool, String> {
+    /// Download the configured model through Ollama
+    pub async fn pull_model(&mut self) -> Result<ConnectionStatus, String> {
         log::info!("Attempting to pull model: {}", self.model);
         
         let url = format!("{}/api/pull", self.endpoint);
@@ -121,7 +228,7 @@ impl LlmClient {
                 if resp.status().is_success() {
                     self.enabled = true;
                     log::info!("Successfully pulled model: {}", self.model);
-                    Ok(true)
+                    Ok(ConnectionStatus::Connected)
                 } else {
                     Err(format!("Failed to pull model: {}", resp.status()))
                 }
@@ -164,13 +271,25 @@ OUTPUT FORMAT (STRICT JSON ONLY):
 RULES:
 1. Return ONLY valid JSON - no markdown, no explanation
 2. Only flag actual violations with evidence from the transcript
-3. Include accurate character positions for evidence quotes
+3. Include accurate character positions for evidence quotes (UTF-16 code unit offsets, as JavaScript counts string indices)
 4. Confidence 0-100 based on certainty
 5. If no violations, return: {{"alerts": [], "suggested_next_lines": []}}
 
 Analyze the transcript now:"#, rules_yaml)
     }
     
+    /// Build the system and user prompts for an evaluation without sending them
+    pub fn build_prompts(&self, call_metadata: &str, transcript: &str, rules_yaml: &str) -> PromptPreview {
+        PromptPreview {
+            system: self.generate_system_prompt(rules_yaml),
+            user: format!(
+                "CALL METADATA:\n{}\n\nTRANSCRIPT:\n{}\n\nAnalyze and return JSON:",
+                call_metadata,
+                transcript
+            ),
+        }
+    }
+    
     /// Evaluate transcript using LLM
     pub async fn evaluate(
         &self,
@@ -178,18 +297,25 @@ Analyze the transcript now:"#, rules_yaml)
         transcript: &str,
         rules_yaml: &str,
     ) -> Result<LlmResponse, String> {
+        self.evaluate_timed(call_metadata, transcript, rules_yaml).await.map(|(response, _)| response)
+    }
+    
+    /// `evaluate`, also reporting how long the request and the parsing of the
+    /// model's output took
+    pub async fn evaluate_timed(
+        &self,
+        call_metadata: &str,
+        transcript: &str,
+        rules_yaml: &str,
+    ) -> Result<(LlmResponse, LlmTiming), String> {
         if !self.enabled {
             return Err("LLM not enabled. Check Ollama connection.".to_string());
         }
         
-        let system_prompt = self.generate_system_prompt(rules_yaml);
-        
-        let user_prompt = format!(
-            "CALL METADATA:\n{}\n\nTRANSCRIPT:\n{}\n\nAnalyze and return JSON:",
-            call_metadata,
-            transcript
-        );
+        let PromptPreview { system: system_prompt, user: user_prompt } =
+            self.build_prompts(call_metadata, transcript, rules_yaml);
         
+        let request_start = std::time::Instant::now();
         let url = format!("{}/api/generate", self.endpoint);
         let request_body = serde_json::json!({
             "model": self.model,
@@ -198,9 +324,9 @@ Analyze the transcript now:"#, rules_yaml)
             "stream": false,
             "format": "json",
             "options": {
-                "temperature": 0.1,
-                "top_p": 0.9,
-                "num_predict": 2048
+                "temperature": self.options.temperature,
+                "top_p": self.options.top_p,
+                "num_predict": self.options.num_predict
             }
         });
         
@@ -209,7 +335,11 @@ Analyze the transcript now:"#, rules_yaml)
             .json(&request_body)
             .send()
             .await
-            .map_err(|e| format!("LLM request failed: {}", e))?;
+            .map_err(|e| if e.is_connect() {
+                format!("{}: {}", LLM_CONNECTION_FAILED, e)
+            } else {
+                format!("LLM request failed: {}", e)
+            })?;
         
         if !response.status().is_success() {
             return Err(format!("LLM error status: {}", response.status()));
@@ -219,12 +349,156 @@ Analyze the transcript now:"#, rules_yaml)
             .json()
             .await
             .map_err(|e| format!("Failed to parse Ollama response: {}", e))?;
+        let request_ms = request_start.elapsed().as_secs_f64() * 1000.0;
         
         // Parse the JSON response from the LLM
-        let llm_response: LlmResponse = serde_json::from_str(&ollama_response.response)
-            .map_err(|e| format!("Failed to parse LLM JSON output: {}. Raw: {}", e, &ollama_response.response))?;
+        let parse_start = std::time::Instant::now();
+        let response = parse_llm_output(&ollama_response.response)?;
+        Ok((response, LlmTiming {
+            request_ms,
+            parse_ms: parse_start.elapsed().as_secs_f64() * 1000.0,
+        }))
+    }
+    
+    /// Embed `text` with an Ollama embedding model, which may differ from the
+    /// evaluation model, for semantic rule matching
+    pub async fn embed(&self, model: &str, text: &str) -> Result<Vec<f32>, String> {
+        let url = format!("{}/api/embeddings", self.endpoint);
+        let request_body = serde_json::json!({
+            "model": model,
+            "prompt": text,
+        });
+        
+        let response = self.client
+            .post(&url)
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| if e.is_connect() {
+                format!("{}: {}", LLM_CONNECTION_FAILED, e)
+            } else {
+                format!("Embedding request failed: {}", e)
+            })?;
+        
+        if !response.status().is_success() {
+            return Err(format!("Embedding error status: {}", response.status()));
+        }
+        
+        let embedding: OllamaEmbeddingResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Ollama embedding: {}", e))?;
+        if embedding.embedding.is_empty() {
+            return Err(format!("Model {} returned an empty embedding", model));
+        }
+        Ok(embedding.embedding)
+    }
+    
+    /// Send a trivial one-token generation so Ollama loads the model into memory
+    /// before the first live call, rather than that call eating the cold start
+    pub async fn warmup(&self) -> Result<LlmWarmup, String> {
+        if !self.enabled {
+            return Err("LLM not enabled. Check Ollama connection.".to_string());
+        }
+        
+        let start = std::time::Instant::now();
+        let url = format!("{}/api/generate", self.endpoint);
+        let request_body = serde_json::json!({
+            "model": self.model,
+            "prompt": "Reply with OK.",
+            "stream": false,
+            "options": {
+                "num_predict": 1
+            }
+        });
+        
+        // Loading a model from disk can take longer than a normal evaluation
+        let response = self.client
+            .post(&url)
+            .timeout(Duration::from_secs(300))
+            .json(&request_body)
+            .send()
+            .await
+            .map_err(|e| format!("LLM warmup failed: {}", e))?;
         
-        Ok(llm_response)
+        if !response.status().is_success() {
+            return Err(format!("LLM warmup error status: {}", response.status()));
+        }
+        
+        let latency_ms = start.elapsed().as_millis() as u64;
+        log::info!("LLM warmup for {} took {}ms", self.model, latency_ms);
+        
+        Ok(LlmWarmup {
+            model: self.model.clone(),
+            latency_ms,
+        })
+    }
+    
+    /// Evaluate the same input `runs` times and report how often each rule is
+    /// raised and how much its confidence moves between runs
+    pub async fn stability_check(
+        &self,
+        call_metadata: &str,
+        transcript: &str,
+        rules_yaml: &str,
+        runs: u32,
+    ) -> Result<LlmStabilityReport, String> {
+        if runs == 0 || runs > MAX_STABILITY_RUNS {
+            return Err(format!("runs must be between 1 and {}", MAX_STABILITY_RUNS));
+        }
+        
+        // rule_id -> highest confidence for that rule in each run that raised it
+        let mut confidences: BTreeMap<String, Vec<u8>> = BTreeMap::new();
+        let mut completed_runs = 0;
+        let mut last_error = None;
+        
+        for run in 1..=runs {
+            match self.evaluate(call_metadata, transcript, rules_yaml).await {
+                Ok(response) => {
+                    completed_runs += 1;
+                    let mut per_run: BTreeMap<String, u8> = BTreeMap::new();
+                    for alert in response.alerts {
+                        let best = per_run.entry(alert.rule_id).or_insert(0);
+                        *best = (*best).max(alert.confidence);
+                    }
+                    for (rule_id, confidence) in per_run {
+                        confidences.entry(rule_id).or_default().push(confidence);
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Stability check run {}/{} failed: {}", run, runs, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        
+        if completed_runs == 0 {
+            return Err(last_error.unwrap_or_else(|| "No runs completed".to_string()));
+        }
+        
+        let mut alerts: Vec<AlertStability> = confidences
+            .into_iter()
+            .map(|(rule_id, values)| {
+                let n = values.len() as f64;
+                let mean = values.iter().map(|&c| c as f64).sum::<f64>() / n;
+                let variance = values.iter().map(|&c| (c as f64 - mean).powi(2)).sum::<f64>() / n;
+                AlertStability {
+                    rule_id,
+                    appearances: values.len() as u32,
+                    appearance_ratio: n / completed_runs as f64,
+                    mean_confidence: mean,
+                    confidence_variance: variance,
+                }
+            })
+            .collect();
+        alerts.sort_by(|a, b| b.appearances.cmp(&a.appearances).then_with(|| a.rule_id.cmp(&b.rule_id)));
+        
+        Ok(LlmStabilityReport {
+            model: self.model.clone(),
+            requested_runs: runs,
+            completed_runs,
+            alerts,
+        })
     }
     
     /// Check if LLM is enabled
@@ -242,6 +516,31 @@ Analyze the transcript now:"#, rules_yaml)
         self.model = model;
         self.enabled = false; // Require re-check
     }
+    
+    /// Get the Ollama endpoint
+    pub fn get_endpoint(&self) -> &str {
+        &self.endpoint
+    }
+    
+    /// Get the sampling options
+    pub fn get_options(&self) -> &LlmOptions {
+        &self.options
+    }
+    
+    /// Set the sampling options used for evaluations
+    pub fn set_options(&mut self, options: LlmOptions) {
+        self.options = options;
+    }
+    
+    /// Whether a missing model is downloaded automatically
+    pub fn auto_pull(&self) -> bool {
+        self.auto_pull
+    }
+    
+    /// Allow or stop automatic model downloads during connection checks
+    pub fn set_auto_pull(&mut self, enabled: bool) {
+        self.auto_pull = enabled;
+    }
 }
 
 impl Default for LlmClient {
@@ -249,3 +548,113 @@ impl Default for LlmClient {
         Self::new(None, None)
     }
 }
+
+/// Turn raw model output into an `LlmResponse`. Tolerates a leading BOM,
+/// surrounding whitespace, markdown code fences, prose before the JSON, and
+/// trailing content such as a second concatenated JSON object. Output cut off
+/// by the token limit keeps the alerts that were complete before the cutoff.
+pub fn parse_llm_output(raw: &str) -> Result<LlmResponse, String> {
+    let mut text = raw.trim_start_matches('\u{feff}').trim();
+    
+    // Strip ```json ... ``` fences some models wrap around their output
+    if let Some(rest) = text.strip_prefix("```") {
+        let rest = rest.trim_start_matches(|c: char| c.is_ascii_alphanumeric());
+        text = rest.rsplit_once("```").map(|(body, _)| body).unwrap_or(rest).trim();
+    }
+    
+    // Skip any preamble before the first object
+    if let Some(start) = text.find('{') {
+        text = &text[start..];
+    }
+    
+    let mut stream = serde_json::Deserializer::from_str(text).into_iter::<LlmResponse>();
+    match stream.next() {
+        Some(Ok(response)) => {
+            if !text[stream.byte_offset()..].trim().is_empty() {
+                log::debug!("Ignoring trailing content after LLM JSON output");
+            }
+            Ok(response)
+        }
+        Some(Err(e)) if e.is_eof() => match salvage_truncated_output(text) {
+            Some(response) => {
+                log::warn!(
+                    "LLM output was truncated (num_predict reached?); salvaged {} complete alerts, results may be incomplete",
+                    response.alerts.len()
+                );
+                Ok(response)
+            }
+            None => Err(format!("LLM JSON output was truncated: {}. Raw: {}", e, raw)),
+        },
+        Some(Err(e)) => Err(format!("Failed to parse LLM JSON output: {}. Raw: {}", e, raw)),
+        None => Err(format!("LLM returned no JSON output. Raw: {}", raw)),
+    }
+}
+
+/// Recover what we can from output cut off mid-JSON: every complete object in
+/// the `alerts` and `suggested_next_lines` arrays before the cutoff. Returns
+/// `None` if no complete alert made it out, so an empty salvage is never
+/// mistaken for a clean transcript.
+fn salvage_truncated_output(text: &str) -> Option<LlmResponse> {
+    let alerts: Vec<LlmAlert> = complete_array_items(text, "alerts")?
+        .into_iter()
+        .filter_map(|item| serde_json::from_str::<LlmAlert>(item).ok())
+        .collect();
+    if alerts.is_empty() {
+        return None;
+    }
+    let suggested_next_lines = complete_array_items(text, "suggested_next_lines")
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|item| serde_json::from_str::<LlmSuggestion>(item).ok())
+        .collect();
+    Some(LlmResponse { alerts, suggested_next_lines })
+}
+
+/// Slices of the complete top-level objects in the array under `key`, stopping
+/// at the end of the array or of the input, whichever comes first
+fn complete_array_items<'a>(text: &'a str, key: &str) -> Option<Vec<&'a str>> {
+    let key_pos = text.find(&format!("\"{}\"", key))?;
+    let after_key = key_pos + key.len() + 2;
+    let open = after_key + text[after_key..].find('[')?;
+    if !text[after_key..open].trim().trim_start_matches(':').trim().is_empty() {
+        return None;
+    }
+    
+    let mut items = Vec::new();
+    let mut depth = 0usize;
+    let mut item_start = None;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in text[open + 1..].char_indices() {
+        let pos = open + 1 + i;
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' | '[' => {
+                if depth == 0 {
+                    item_start = Some(pos);
+                }
+                depth += 1;
+            }
+            '}' | ']' if depth == 0 => break,
+            '}' | ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    if let Some(start) = item_start.take() {
+                        items.push(&text[start..=pos]);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(items)
+}
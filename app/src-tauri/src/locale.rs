@@ -0,0 +1,184 @@
+use chrono::FixedOffset;
+use serde::Serialize;
+use crate::database::StoredAlert;
+use crate::evaluator::parse_call_start_time;
+
+/// Field order of a numeric date
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DateOrder {
+    MonthDayYear,
+    DayMonthYear,
+    YearMonthDay,
+}
+
+/// (language or language-REGION, date order, date separator, decimal separator, group separator)
+const LOCALES: &[(&str, DateOrder, char, char, &str)] = &[
+    ("en-US", DateOrder::MonthDayYear, '/', '.', ","),
+    ("en-GB", DateOrder::DayMonthYear, '/', '.', ","),
+    ("en-AU", DateOrder::DayMonthYear, '/', '.', ","),
+    ("en-CA", DateOrder::YearMonthDay, '-', '.', ","),
+    ("en", DateOrder::MonthDayYear, '/', '.', ","),
+    ("de", DateOrder::DayMonthYear, '.', ',', "."),
+    ("fr", DateOrder::DayMonthYear, '/', ',', "\u{202f}"),
+    ("es", DateOrder::DayMonthYear, '/', ',', "."),
+    ("it", DateOrder::DayMonthYear, '/', ',', "."),
+    ("pt", DateOrder::DayMonthYear, '/', ',', "."),
+    ("nl", DateOrder::DayMonthYear, '-', ',', "."),
+    ("ja", DateOrder::YearMonthDay, '/', '.', ","),
+    ("zh", DateOrder::YearMonthDay, '/', '.', ","),
+];
+
+/// Date and number formatting for exports. Timestamps are shifted by a fixed
+/// UTC offset, since stored times carry no zone of their own.
+#[derive(Debug, Clone)]
+pub struct ExportLocale {
+    date_order: DateOrder,
+    date_separator: char,
+    decimal_separator: char,
+    group_separator: &'static str,
+    offset: FixedOffset,
+}
+
+impl ExportLocale {
+    /// Look up a BCP 47 tag such as "de-DE", falling back from the region to
+    /// the language. `tz_offset_minutes` is minutes east of UTC (-300 for EST).
+    pub fn new(locale: Option<&str>, tz_offset_minutes: Option<i32>) -> Result<Self, String> {
+        let tag = locale.unwrap_or("en-US").trim().replace('_', "-");
+        let language = tag.split('-').next().unwrap_or_default();
+        let (_, date_order, date_separator, decimal_separator, group_separator) = LOCALES.iter()
+            .find(|(l, ..)| l.eq_ignore_ascii_case(&tag))
+            .or_else(|| LOCALES.iter().find(|(l, ..)| l.eq_ignore_ascii_case(language)))
+            .copied()
+            .ok_or_else(|| format!("Unsupported export locale: {}", tag))?;
+
+        let offset_minutes = tz_offset_minutes.unwrap_or(0);
+        let offset = FixedOffset::east_opt(offset_minutes * 60)
+            .ok_or_else(|| format!("Invalid UTC offset: {} minutes", offset_minutes))?;
+
+        Ok(ExportLocale {
+            date_order,
+            date_separator,
+            decimal_separator,
+            group_separator,
+            offset,
+        })
+    }
+
+    /// Format a stored UTC timestamp as local date and 24-hour time. Values
+    /// that don't parse are returned unchanged.
+    pub fn format_timestamp(&self, raw: &str) -> String {
+        let Some(utc) = parse_call_start_time(raw) else {
+            return raw.to_string();
+        };
+        let local = utc.with_timezone(&self.offset);
+        let sep = self.date_separator;
+        let date_format = match self.date_order {
+            DateOrder::MonthDayYear => format!("%m{sep}%d{sep}%Y"),
+            DateOrder::DayMonthYear => format!("%d{sep}%m{sep}%Y"),
+            DateOrder::YearMonthDay => format!("%Y{sep}%m{sep}%d"),
+        };
+        local.format(&format!("{} %H:%M:%S", date_format)).to_string()
+    }
+
+    /// Format a count with the locale's digit grouping, e.g. 12,345 or 12.345
+    pub fn format_integer(&self, value: i64) -> String {
+        let digits = value.unsigned_abs().to_string();
+        let mut grouped = String::new();
+        for (i, digit) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i) % 3 == 0 {
+                grouped.push_str(self.group_separator);
+            }
+            grouped.push(digit);
+        }
+        if value < 0 {
+            grouped.insert(0, '-');
+        }
+        grouped
+    }
+
+    /// Spreadsheet apps in comma-decimal locales expect `;` between CSV fields
+    pub fn csv_delimiter(&self) -> char {
+        if self.decimal_separator == ',' { ';' } else { ',' }
+    }
+}
+
+/// A stored alert with localized display fields next to the raw ones
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalizedAlert {
+    #[serde(flatten)]
+    pub alert: StoredAlert,
+    pub created_at_local: String,
+    pub confidence_local: String,
+}
+
+pub fn localize_alerts(alerts: Vec<StoredAlert>, locale: &ExportLocale) -> Vec<LocalizedAlert> {
+    alerts.into_iter()
+        .map(|alert| LocalizedAlert {
+            created_at_local: locale.format_timestamp(&alert.created_at),
+            confidence_local: locale.format_integer(alert.confidence as i64),
+            alert,
+        })
+        .collect()
+}
+
+/// Alerts as CSV, with both the raw UTC `created_at` and the localized time
+pub fn alerts_to_csv(alerts: &[StoredAlert], locale: &ExportLocale) -> String {
+    let delimiter = locale.csv_delimiter().to_string();
+    let header = ["id", "call_id", "agent_id", "agent_name", "rule_id", "title", "severity",
+                  "confidence", "quote", "tags", "created_at", "created_at_local"];
+    let mut csv = header.join(&delimiter);
+    csv.push('\n');
+
+    for alert in alerts {
+        let row = [
+            alert.id.clone(),
+            alert.call_id.clone(),
+            alert.agent_id.clone(),
+            alert.agent_name.clone(),
+            alert.rule_id.clone(),
+            alert.title.clone(),
+            alert.severity.clone(),
+            locale.format_integer(alert.confidence as i64),
+            alert.quote.clone(),
+            alert.tags.join("|"),
+            alert.created_at.clone(),
+            locale.format_timestamp(&alert.created_at),
+        ];
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f, &delimiter)).collect();
+        csv.push_str(&fields.join(&delimiter));
+        csv.push('\n');
+    }
+
+    csv
+}
+
+/// Quote a field if it contains the delimiter, a quote or a line break
+pub fn csv_field(value: &str, delimiter: &str) -> String {
+    if value.contains(delimiter) || value.contains('"') || value.contains('\n') || value.contains('\r') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Split one CSV line into its fields, undoing `csv_field`'s quoting, so a
+/// quoted "$1,500" stays one field
+pub fn csv_fields(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
@@ -0,0 +1,82 @@
+/// Name words shorter than this must match exactly; their phonetic keys are
+/// too short to tell apart
+const MIN_PHONETIC_WORD_LEN: usize = 3;
+
+/// Soundex-style key for a word: the first letter, then a digit per consonant
+/// group, with repeats collapsed. Unlike classic Soundex it isn't cut to four
+/// characters, so long company names stay distinct.
+pub fn phonetic_key(word: &str) -> String {
+    let code = |c: char| match c {
+        'b' | 'f' | 'p' | 'v' => Some('1'),
+        'c' | 'g' | 'j' | 'k' | 'q' | 's' | 'x' | 'z' => Some('2'),
+        'd' | 't' => Some('3'),
+        'l' => Some('4'),
+        'm' | 'n' => Some('5'),
+        'r' => Some('6'),
+        _ => None,
+    };
+
+    let mut letters = word.chars().filter(|c| c.is_ascii_alphabetic()).map(|c| c.to_ascii_lowercase());
+    let Some(first) = letters.next() else {
+        return String::new();
+    };
+    let mut key = first.to_ascii_uppercase().to_string();
+    let mut last = code(first);
+    for c in letters {
+        match code(c) {
+            Some(digit) if Some(digit) != last => {
+                key.push(digit);
+                last = Some(digit);
+            }
+            Some(_) => {}
+            // `h` and `w` don't separate repeated codes; vowels do
+            None if c == 'h' || c == 'w' => {}
+            None => last = None,
+        }
+    }
+    key
+}
+
+/// Whether `name` is said in `text`, allowing for speech-to-text errors:
+/// words may sound alike ("Akme" for "Acme") or be split or merged
+/// ("ack me" for "Acme").
+pub fn mentions_name(text: &str, name: &str) -> bool {
+    let name_words = words(name);
+    if name_words.is_empty() {
+        return false;
+    }
+    let text_words = words(text);
+    let name_joined = name_words.concat();
+    let name_key = phonetic_key(&name_joined);
+
+    let n = name_words.len();
+    (0..text_words.len()).any(|start| {
+        // Word-by-word match over the same number of words
+        let aligned = text_words.get(start..start + n).is_some_and(|window| {
+            window.iter().zip(&name_words).all(|(heard, expected)| sounds_like(heard, expected))
+        });
+        // Or the same sounds with the words split or merged differently
+        aligned || (n.saturating_sub(1).max(1)..=n + 1).any(|len| {
+            text_words.get(start..start + len).is_some_and(|window| {
+                let joined = window.concat();
+                joined == name_joined
+                    || (name_joined.len() >= MIN_PHONETIC_WORD_LEN && phonetic_key(&joined) == name_key)
+            })
+        })
+    })
+}
+
+fn sounds_like(heard: &str, expected: &str) -> bool {
+    heard == expected
+        || (expected.len() >= MIN_PHONETIC_WORD_LEN
+            && expected.chars().all(|c| c.is_ascii_alphabetic())
+            && phonetic_key(heard) == phonetic_key(expected))
+}
+
+/// Lowercased alphanumeric words
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,113 @@
+use std::path::Path;
+use std::time::Duration;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use crate::rules::RuleSet;
+
+/// Local copy of the last ruleset fetched from a rules service, used offline
+/// and on startup
+pub const REMOTE_RULES_CACHE: &str = "whisperwire-remote-rules.json";
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Cached ruleset with the validators needed for a conditional request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedRules {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+    fetched_at: String,
+    ruleset: RuleSet,
+}
+
+/// Fetch a YAML or JSON ruleset over HTTP, validate it and cache it locally.
+/// The cached copy is sent as `If-None-Match`/`If-Modified-Since`, so an
+/// unchanged ruleset isn't downloaded again. If the service is unreachable
+/// the cached copy for the same URL is returned; an invalid download is an
+/// error and leaves the cache as it was.
+pub async fn load_rules_from_url(url: &str) -> Result<RuleSet, String> {
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return Err(format!("Rules URL must be http(s): {}", url));
+    }
+    let cached = read_cache(Path::new(REMOTE_RULES_CACHE)).filter(|c| c.url == url);
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let mut request = client.get(url);
+    if let Some(ref cached) = cached {
+        if let Some(ref etag) = cached.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(ref last_modified) = cached.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    let response = match request.send().await {
+        Ok(response) if response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED => response,
+        Ok(response) => return fall_back_to_cache(cached, url, &format!("HTTP {}", response.status())),
+        Err(e) => return fall_back_to_cache(cached, url, &e.to_string()),
+    };
+
+    if response.status() == StatusCode::NOT_MODIFIED {
+        if let Some(cached) = cached {
+            log::info!("Remote rules unchanged since {}: {}", cached.fetched_at, url);
+            return Ok(cached.ruleset);
+        }
+        return Err(format!("Rules service returned 304 Not Modified with no cached copy: {}", url));
+    }
+
+    let header = |name| response.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+    let (etag, last_modified) = (header(ETAG), header(LAST_MODIFIED));
+    let body = response.text().await
+        .map_err(|e| format!("Failed to read rules from {}: {}", url, e))?;
+    let ruleset = RuleSet::parse(&body)?;
+
+    let entry = CachedRules {
+        url: url.to_string(),
+        etag,
+        last_modified,
+        fetched_at: chrono::Utc::now().to_rfc3339(),
+        ruleset: ruleset.clone(),
+    };
+    if let Err(e) = write_cache(Path::new(REMOTE_RULES_CACHE), &entry) {
+        log::warn!("Failed to cache remote rules: {}", e);
+    }
+
+    log::info!("Loaded {} rules (v{}) from {}", ruleset.rules.len(), ruleset.version, url);
+    Ok(ruleset)
+}
+
+/// The cached remote ruleset and the URL it came from, if one was ever fetched
+pub fn load_cached_rules() -> Option<(String, RuleSet)> {
+    read_cache(Path::new(REMOTE_RULES_CACHE)).map(|c| (c.url, c.ruleset))
+}
+
+fn fall_back_to_cache(cached: Option<CachedRules>, url: &str, error: &str) -> Result<RuleSet, String> {
+    match cached {
+        Some(cached) => {
+            log::warn!("Rules service unavailable ({}); using copy cached at {}", error, cached.fetched_at);
+            Ok(cached.ruleset)
+        }
+        None => Err(format!("Failed to fetch rules from {}: {}", url, error)),
+    }
+}
+
+fn read_cache(path: &Path) -> Option<CachedRules> {
+    let content = std::fs::read_to_string(path).ok()?;
+    match serde_json::from_str::<CachedRules>(&content) {
+        Ok(cached) if cached.ruleset.validate().is_ok() => Some(cached),
+        Ok(_) | Err(_) => {
+            log::warn!("Ignoring invalid remote rules cache {}", path.display());
+            None
+        }
+    }
+}
+
+fn write_cache(path: &Path, entry: &CachedRules) -> Result<(), String> {
+    let json = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    std::fs::write(path, json).map_err(|e| e.to_string())
+}
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+use std::path::Path;
 use serde::{Deserialize, Serialize};
 
 /// Rule category for grouping and filtering
@@ -11,6 +13,9 @@ pub enum RuleCategory {
     Identification,
     RecordingDisclosure,
     Prerecorded,
+    DeliveryQuality,
+    VulnerableConsumer,
+    Misrepresentation,
 }
 
 /// Severity level for alerts
@@ -39,13 +44,75 @@ pub struct Rule {
     pub legal_reference: String,
     pub enabled: bool,
     pub optional: bool,
+    /// Rule that must already have fired this session before this one can fire
+    #[serde(default)]
+    pub depends_on: Option<String>,
+    /// Where the rule applies: "federal" or two-letter state codes such as "FL"
+    #[serde(default = "default_jurisdictions")]
+    pub jurisdictions: Vec<String>,
+    /// Plain phrases that, for disclosure-type rules, mark the disclosure as
+    /// made just like a regex match. Matched case-insensitively, on agent lines.
+    #[serde(default)]
+    pub satisfying_phrases: Vec<String>,
+    /// Rules that stop firing for the rest of the session once this one fires.
+    /// Only rules of lower severity than the alert raised can be suppressed.
+    #[serde(default)]
+    pub suppresses: Vec<String>,
+    /// Also matched by meaning, against embeddings of the triggers and
+    /// description, when semantic matching is turned on
+    #[serde(default)]
+    pub semantic: bool,
+    /// Sample transcripts with the expected outcome, shown to the LLM as
+    /// few-shot examples
+    #[serde(default)]
+    pub examples: Vec<RuleExample>,
 }
 
+/// A transcript snippet and whether the rule should fire on it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleExample {
+    pub transcript: String,
+    pub expect_alert: bool,
+    /// Evidence quote the alert should carry, when one is expected
+    #[serde(default)]
+    pub quote: Option<String>,
+}
+
+/// Most examples included in the LLM prompt across all rules
+pub const MAX_PROMPT_EXAMPLES: usize = 8;
+
+/// Most examples included in the LLM prompt for any one rule
+const MAX_PROMPT_EXAMPLES_PER_RULE: usize = 2;
+
+fn default_jurisdictions() -> Vec<String> {
+    vec![FEDERAL_JURISDICTION.to_string()]
+}
+
+/// Jurisdiction tag for rules that apply to every call
+pub const FEDERAL_JURISDICTION: &str = "federal";
+
+impl Rule {
+    /// Whether the rule applies to a call in `consumer_state`. Federal rules always
+    /// apply; state rules only when the call's state is known and listed.
+    pub fn applies_in(&self, consumer_state: Option<&str>) -> bool {
+        self.jurisdictions.iter().any(|j| {
+            j.eq_ignore_ascii_case(FEDERAL_JURISDICTION)
+                || consumer_state.is_some_and(|state| j.eq_ignore_ascii_case(state.trim()))
+        })
+    }
+}
+
+/// Shown with every ruleset; used when a loaded ruleset doesn't carry its own
+const DEFAULT_DISCLAIMER: &str = "This tool provides compliance risk signals only. It is NOT legal advice. \
+                                  Compliance requirements depend on jurisdiction and require legal counsel review. \
+                                  Always consult with qualified legal professionals for compliance decisions.";
+
 /// Complete ruleset with metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuleSet {
     pub version: String,
     pub last_updated: String,
+    #[serde(default)]
     pub disclaimer: String,
     pub rules: Vec<Rule>,
 }
@@ -56,13 +123,64 @@ impl RuleSet {
         Ok(RuleSet {
             version: "1.0.0".to_string(),
             last_updated: "2026-01-16".to_string(),
-            disclaimer: "This tool provides compliance risk signals only. It is NOT legal advice. \
-                         Compliance requirements depend on jurisdiction and require legal counsel review. \
-                         Always consult with qualified legal professionals for compliance decisions.".to_string(),
+            disclaimer: DEFAULT_DISCLAIMER.to_string(),
             rules: Self::get_default_rules(),
         })
     }
     
+    /// Load a ruleset from a `.yaml` or `.json` file in the format of
+    /// `rules/tcpa-rules.yaml`
+    pub fn load_from_file(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read rules file {}: {}", path.display(), e))?;
+        Self::parse(&content)
+    }
+    
+    /// Parse and validate a ruleset from JSON or YAML text
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let content = content.trim_start_matches('\u{feff}').trim_start();
+        let mut ruleset: RuleSet = if content.starts_with('{') {
+            serde_json::from_str(content).map_err(|e| format!("Invalid ruleset JSON: {}", e))?
+        } else {
+            serde_yaml::from_str(content).map_err(|e| format!("Invalid ruleset YAML: {}", e))?
+        };
+        if ruleset.disclaimer.trim().is_empty() {
+            ruleset.disclaimer = DEFAULT_DISCLAIMER.to_string();
+        }
+        ruleset.validate()?;
+        Ok(ruleset)
+    }
+    
+    /// Check that rule IDs are unique, every regex compiles, and `depends_on`
+    /// and `suppresses` only name rules in the set
+    pub fn validate(&self) -> Result<(), String> {
+        if self.rules.is_empty() {
+            return Err("Ruleset has no rules".to_string());
+        }
+        
+        let mut ids = HashSet::new();
+        for rule in &self.rules {
+            if rule.id.trim().is_empty() {
+                return Err(format!("Rule '{}' has no ID", rule.title));
+            }
+            if !ids.insert(rule.id.as_str()) {
+                return Err(format!("Duplicate rule ID: {}", rule.id));
+            }
+            for pattern in &rule.regex_patterns {
+                regex::Regex::new(pattern)
+                    .map_err(|e| format!("Invalid regex in rule {}: {}", rule.id, e))?;
+            }
+        }
+        
+        for rule in &self.rules {
+            let references = rule.depends_on.iter().chain(rule.suppresses.iter());
+            if let Some(unknown) = references.into_iter().find(|id| !ids.contains(id.as_str())) {
+                return Err(format!("Rule {} refers to unknown rule {}", rule.id, unknown));
+            }
+        }
+        Ok(())
+    }
+    
     fn get_default_rules() -> Vec<Rule> {
         vec![
             // Calling Time Rules
@@ -77,12 +195,21 @@ impl RuleSet {
                 requires_metadata: true,
                 metadata_field: Some("call_time_local".to_string()),
                 why_it_matters: "The TCPA prohibits telemarketing calls before 8am or after 9pm in the \
-                                 consumer's local time zone. Violations can result in $500-$1,500 per call.".to_string(),
+                                 consumer's local time zone. Violations can result in $500-$1,500 per call. \
+                                 Prior express consent takes the call outside the TCPA's definition of a \
+                                 telephone solicitation, but the TSR's calling hours still apply, so consent \
+                                 downgrades this alert to Medium rather than suppressing it.".to_string(),
                 recommended_fix: "Verify time zone before calling. If outside hours, apologize and offer \
                                   to call back during appropriate hours.".to_string(),
                 legal_reference: "47 U.S.C. § 227(c)(5); 47 C.F.R. § 64.1200(c)(1)".to_string(),
                 enabled: true,
                 optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
             },
             
             // Do Not Call Rules
@@ -119,6 +246,29 @@ impl RuleSet {
                 legal_reference: "47 U.S.C. § 227(c); 47 C.F.R. § 64.1200(d)".to_string(),
                 enabled: true,
                 optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![
+                    RuleExample {
+                        transcript: "Customer: Look, I'm not interested. Please stop calling this number.".to_string(),
+                        expect_alert: true,
+                        quote: Some("stop calling this number".to_string()),
+                    },
+                    RuleExample {
+                        transcript: "Customer: Can you call me back tomorrow? I'm driving right now.".to_string(),
+                        expect_alert: false,
+                        quote: None,
+                    },
+                ],
+                suppresses: vec![
+                    "DISC-002".to_string(),
+                    "DISC-003".to_string(),
+                    "DELIV-001".to_string(),
+                    "DELIV-002".to_string(),
+                    "DELIV-003".to_string(),
+                ],
+                semantic: true,
             },
             Rule {
                 id: "DNC-002".to_string(),
@@ -147,6 +297,18 @@ impl RuleSet {
                 legal_reference: "47 C.F.R. § 64.1200(d)(3)".to_string(),
                 enabled: true,
                 optional: false,
+                depends_on: Some("DNC-001".to_string()),
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![
+                    RuleExample {
+                        transcript: "Customer: Take me off your list. Agent: Before you go, this offer ends Friday.".to_string(),
+                        expect_alert: true,
+                        quote: Some("Before you go, this offer ends Friday".to_string()),
+                    },
+                ],
+                suppresses: vec![],
+                semantic: false,
             },
             Rule {
                 id: "DNC-003".to_string(),
@@ -159,12 +321,20 @@ impl RuleSet {
                 requires_metadata: true,
                 metadata_field: Some("is_dnc_listed".to_string()),
                 why_it_matters: "Calling numbers on the National DNC Registry without prior express consent \
-                                 or an established business relationship is a TCPA violation.".to_string(),
+                                 or an established business relationship is a TCPA violation. Documented \
+                                 prior express written consent exempts the call, so this rule does not fire \
+                                 when consent is on file.".to_string(),
                 recommended_fix: "If calling a DNC-listed number, ensure you have documented consent or \
                                   an existing business relationship. If unsure, end the marketing call.".to_string(),
                 legal_reference: "47 C.F.R. § 64.1200(c)(2)".to_string(),
                 enabled: true,
                 optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
             },
             
             // Disclosure Rules
@@ -186,6 +356,17 @@ impl RuleSet {
                 legal_reference: "16 C.F.R. § 310.4(d)(1)".to_string(),
                 enabled: true,
                 optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![
+                    "you've reached".to_string(),
+                    "i represent".to_string(),
+                    "i'm reaching out from".to_string(),
+                    "i work for".to_string(),
+                ],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
             },
             Rule {
                 id: "DISC-002".to_string(),
@@ -205,6 +386,12 @@ impl RuleSet {
                 legal_reference: "16 C.F.R. § 310.4(d)(2)".to_string(),
                 enabled: true,
                 optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
             },
             Rule {
                 id: "DISC-003".to_string(),
@@ -222,6 +409,101 @@ impl RuleSet {
                 legal_reference: "16 C.F.R. § 310.4(d)(3)".to_string(),
                 enabled: true,
                 optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
+            },
+            Rule {
+                id: "DISC-004".to_string(),
+                title: "Vague Seller Identification".to_string(),
+                category: RuleCategory::Disclosure,
+                description: "Agent identified the caller only by a vague affiliation, e.g. \"calling on behalf \
+                              of your current provider\" or \"from the warranty department\", without naming \
+                              the company in the same or an adjacent agent turn. Regex patterns are the agent \
+                              introducing themselves by a vague affiliation.".to_string(),
+                severity: Severity::Medium,
+                triggers: vec![],
+                regex_patterns: vec![
+                    r"(?i)\b(calling|(i'?m|i\s+am|we'?re|we\s+are|this\s+is(\s+\w+)?))\s+(from|with|on\s+behalf\s+of)\s+your\s+(current\s+|local\s+)?(provider|service\s+provider|utility(\s+company)?|electric(\s+company)?|credit\s+card\s+(company|issuer)|bank|carrier|lender|insurance\s+company)".to_string(),
+                    r"(?i)\b(calling|(i'?m|i\s+am|we'?re|we\s+are|this\s+is(\s+\w+)?))\s+(from|with|on\s+behalf\s+of)\s+the\s+(warranty|vehicle\s+services?|benefits|enrollment|processing|billing|activation|verification|rebate)\s+(department|center|office|division)".to_string(),
+                    r"(?i)\b(calling|(i'?m|i\s+am|we'?re|we\s+are|this\s+is(\s+\w+)?))\s+(from|with|on\s+behalf\s+of)\s+(the\s+)?(manufacturer|dealership|your\s+dealer)".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: None,
+                why_it_matters: "The seller disclosure must name who is actually selling. \"Your current provider\" \
+                                 satisfies a keyword check but lets the consumer believe they're talking to a \
+                                 company they already do business with.".to_string(),
+                recommended_fix: "Name the company: 'My name is [Name] and I'm calling from [Company Name]. We're \
+                                  not affiliated with your current provider.'".to_string(),
+                legal_reference: "16 C.F.R. § 310.4(d)(1); 16 C.F.R. § 310.3(a)(2)(vii)".to_string(),
+                enabled: true,
+                optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![
+                    RuleExample {
+                        transcript: "Agent: Hi, I'm calling on behalf of your current provider about your account.".to_string(),
+                        expect_alert: true,
+                        quote: Some("calling on behalf of your current provider".to_string()),
+                    },
+                    RuleExample {
+                        transcript: "Agent: This is Sam from Acme Energy. We can beat the rate from your current provider.".to_string(),
+                        expect_alert: false,
+                        quote: None,
+                    },
+                ],
+                suppresses: vec![],
+                semantic: false,
+            },
+            Rule {
+                id: "PAY-001".to_string(),
+                title: "Payment Requested Before Material Terms".to_string(),
+                category: RuleCategory::Disclosure,
+                description: "Agent asked for payment details or authorization before describing the product \
+                              and stating its cost. Triggers and regex patterns are the agent's payment \
+                              request.".to_string(),
+                severity: Severity::High,
+                triggers: vec![
+                    "let's process that".to_string(),
+                    "authorize the charge".to_string(),
+                ],
+                regex_patterns: vec![
+                    r"(?i)\b(what'?s|what\s+is|give\s+me|read\s+(me\s+)?|(can|could|may)\s+i\s+(get|have)|(i|we)('ll|\s+will)?\s+need|provide|enter)\s+(\w+\s+){0,3}((card|bank\s+account|checking(\s+account)?|savings(\s+account)?|routing)\s+number|expiration\s+date|security\s+code|cvv)\b".to_string(),
+                    r"(?i)\b(number|expiration\s+date|security\s+code|cvv)\s+on\s+(the\s+(back|front)\s+of\s+)?(the|your)\s+card".to_string(),
+                    r"(?i)(let'?s|i'?ll|go\s+ahead\s+and)\s+(process|run|charge)\s+(that|it|the\s+payment|your\s+card)".to_string(),
+                    r"(?i)authorize\s+(the|this|a)\s+(charge|payment)".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: None,
+                why_it_matters: "The Telemarketing Sales Rule requires the total cost and a description of \
+                                 what is being sold before the consumer pays. Taking payment first is a \
+                                 deceptive practice.".to_string(),
+                recommended_fix: "Before we go any further, let me go over exactly what you'd be getting: \
+                                  [product/service], for a total of [cost]. Would you like to go ahead?".to_string(),
+                legal_reference: "16 C.F.R. § 310.3(a)(1)".to_string(),
+                enabled: true,
+                optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![
+                    RuleExample {
+                        transcript: "Agent: Great, I can get you started today. What's the card number?".to_string(),
+                        expect_alert: true,
+                        quote: Some("Great, I can get you started today. What's the card number?".to_string()),
+                    },
+                    RuleExample {
+                        transcript: "Agent: I'll text you a security code to verify your identity.".to_string(),
+                        expect_alert: false,
+                        quote: None,
+                    },
+                ],
+                suppresses: vec![],
+                semantic: false,
             },
             
             // Consent Rules
@@ -256,6 +538,137 @@ impl RuleSet {
                 legal_reference: "47 C.F.R. § 64.1200(a)(7)(ii)".to_string(),
                 enabled: true,
                 optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![
+                    RuleExample {
+                        transcript: "Customer: I signed up online but I don't want these calls anymore, I take back my permission.".to_string(),
+                        expect_alert: true,
+                        quote: Some("I take back my permission".to_string()),
+                    },
+                ],
+                suppresses: vec![
+                    "DISC-002".to_string(),
+                    "DISC-003".to_string(),
+                    "DELIV-001".to_string(),
+                    "DELIV-002".to_string(),
+                    "DELIV-003".to_string(),
+                ],
+                semantic: true,
+            },
+            
+            Rule {
+                id: "CONS-002".to_string(),
+                title: "Consent Obtained Under Confusion".to_string(),
+                category: RuleCategory::Consent,
+                description: "Consumer expressed confusion about what they were agreeing to and the agent \
+                              pushed for a yes instead of explaining. Triggers are the consumer's confusion; \
+                              regex patterns are the agent's follow-up push.".to_string(),
+                severity: Severity::Medium,
+                triggers: vec![
+                    "what am i agreeing to".to_string(),
+                    "i'm confused".to_string(),
+                    "i am confused".to_string(),
+                    "i don't understand what this is".to_string(),
+                    "i don't understand".to_string(),
+                    "what is this for".to_string(),
+                    "what does that mean".to_string(),
+                ],
+                regex_patterns: vec![
+                    r"(?i)(just|all\s+i\s+need\s+is(\s+a)?)\s+(say\s+)?yes".to_string(),
+                    r"(?i)(just\s+agree|go\s+ahead\s+and\s+(say\s+yes|agree|confirm))".to_string(),
+                    r"(?i)(don'?t\s+worry\s+about\s+(it|that)|it'?s\s+(just\s+)?(standard|a\s+formality))".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: None,
+                why_it_matters: "Consent must be informed. A yes obtained right after the consumer said they \
+                                 didn't understand is unlikely to hold up as valid consent.".to_string(),
+                recommended_fix: "No problem, let me explain. You'd be agreeing to [terms]. Would you like \
+                                  to go ahead, or would you prefer some time to think about it?".to_string(),
+                legal_reference: "47 C.F.R. § 64.1200(f)(9)".to_string(),
+                enabled: true,
+                optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![
+                    RuleExample {
+                        transcript: "Customer: Wait, what am I agreeing to? Agent: Don't worry about it, it's just a formality. Just say yes.".to_string(),
+                        expect_alert: true,
+                        quote: Some("Don't worry about it, it's just a formality".to_string()),
+                    },
+                ],
+                suppresses: vec![],
+                semantic: false,
+            },
+            
+            Rule {
+                id: "CONS-003".to_string(),
+                title: "Consent Contradicted".to_string(),
+                category: RuleCategory::Consent,
+                description: "Consumer agreed to something earlier in the call and later denied agreeing to it. \
+                              Triggers are the consumer's denial; regex patterns are the earlier agreement, \
+                              which only counts when it is about a recognizable topic (recording, further \
+                              contact, a purchase).".to_string(),
+                severity: Severity::Medium,
+                triggers: vec![
+                    "i didn't agree".to_string(),
+                    "i did not agree".to_string(),
+                    "i never agreed".to_string(),
+                    "i don't agree".to_string(),
+                    "i didn't say yes".to_string(),
+                    "i never said yes".to_string(),
+                    "that's not what i agreed to".to_string(),
+                    "i changed my mind".to_string(),
+                    "i take that back".to_string(),
+                ],
+                regex_patterns: vec![
+                    r"(?i)^\s*(yes|yeah|yep|sure|okay|ok|alright)\b".to_string(),
+                    r"(?i)\b(that'?s\s+fine|sounds\s+good|that\s+works|go\s+ahead|i\s+agree|i\s+consent)\b".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: None,
+                why_it_matters: "Consent the consumer later disputes within the same call can't be relied on. \
+                                 The earlier yes may need to be treated as withdrawn or never given.".to_string(),
+                recommended_fix: "I want to make sure we have this right. Earlier it sounded like you agreed to \
+                                  [topic], but I'll treat that as a no unless you tell me otherwise.".to_string(),
+                legal_reference: "47 C.F.R. § 64.1200(f)(9)".to_string(),
+                enabled: true,
+                optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
+            },
+            
+            Rule {
+                id: "CONS-004".to_string(),
+                title: "Call to Reassigned Number".to_string(),
+                category: RuleCategory::Consent,
+                description: "Marketing call reached a number that has been reassigned to a new subscriber \
+                              since consent was obtained, per an upstream Reassigned Numbers Database check".to_string(),
+                severity: Severity::High,
+                triggers: vec![],
+                regex_patterns: vec![],
+                requires_metadata: true,
+                metadata_field: Some("number_reassigned".to_string()),
+                why_it_matters: "Consent belongs to the person who gave it, not the phone number. Calling a \
+                                 reassigned number reaches someone who never consented, and the caller is \
+                                 liable unless it checked the Reassigned Numbers Database first.".to_string(),
+                recommended_fix: "Confirm who you're speaking with. If it isn't the consumer who gave consent, \
+                                  apologize, end the marketing call and have the number removed from the campaign.".to_string(),
+                legal_reference: "47 U.S.C. § 227(b)(1); 47 C.F.R. § 64.1200(m) (Reassigned Numbers Database safe harbor)".to_string(),
+                enabled: true,
+                optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
             },
             
             // Identification Rules  
@@ -277,6 +690,17 @@ impl RuleSet {
                 legal_reference: "16 C.F.R. § 310.4(d)(7)".to_string(),
                 enabled: true,
                 optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![
+                    "you can call us at".to_string(),
+                    "our phone number is".to_string(),
+                    "our toll-free number is".to_string(),
+                    "call us anytime at".to_string(),
+                ],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
             },
             
             // Prerecorded Voice Rules
@@ -291,12 +715,168 @@ impl RuleSet {
                 requires_metadata: true,
                 metadata_field: Some("is_prerecorded".to_string()),
                 why_it_matters: "TCPA requires prior express written consent for prerecorded telemarketing \
-                                 calls to cell phones.".to_string(),
+                                 calls to cell phones. This rule is suppressed when consent is on file.".to_string(),
                 recommended_fix: "Ensure written consent is obtained and documented before using \
                                   prerecorded messages for marketing.".to_string(),
                 legal_reference: "47 U.S.C. § 227(b)(1)(A)".to_string(),
                 enabled: true,
                 optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
+            },
+            
+            Rule {
+                id: "PREC-002".to_string(),
+                title: "Prerecorded Message Without Opt-Out".to_string(),
+                category: RuleCategory::Prerecorded,
+                description: "Prerecorded telemarketing message did not include an automated opt-out mechanism. \
+                              Regex patterns detect the opt-out instructions; the alert fires at end of call \
+                              if none were heard.".to_string(),
+                severity: Severity::High,
+                triggers: vec![],
+                regex_patterns: vec![
+                    r"(?i)press\s+(\d|nine|one|two|star|pound)\s+to\s+(be\s+removed|opt\s+out|stop|unsubscribe)".to_string(),
+                    r"(?i)(to\s+be\s+removed\s+from|to\s+opt\s+out|to\s+stop\s+receiving)".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: Some("is_prerecorded".to_string()),
+                why_it_matters: "Prerecorded telemarketing messages must provide an automated, interactive \
+                                 opt-out mechanism that the consumer can use during the message. This applies \
+                                 even with prior consent, so the consent flag does not affect this rule.".to_string(),
+                recommended_fix: "Include opt-out instructions in the message: 'To be removed from our \
+                                  calling list, press 9 now.'".to_string(),
+                legal_reference: "47 C.F.R. § 64.1200(b)(3)".to_string(),
+                enabled: true,
+                optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
+            },
+            
+            // Vulnerable Consumer Rules
+            Rule {
+                id: "VULN-001".to_string(),
+                title: "Possible Minor or Vulnerable Consumer".to_string(),
+                category: RuleCategory::VulnerableConsumer,
+                description: "Customer indicated they may be a minor or may lack the capacity to make \
+                              the decision (cognitive impairment, someone else manages their finances)".to_string(),
+                severity: Severity::High,
+                triggers: vec![
+                    "i'm under 18".to_string(),
+                    "my mom handles that".to_string(),
+                    "my dad handles that".to_string(),
+                    "my son handles my money".to_string(),
+                    "my daughter handles my money".to_string(),
+                    "i have dementia".to_string(),
+                    "i have alzheimer's".to_string(),
+                    "i don't understand money stuff".to_string(),
+                    "i get confused easily".to_string(),
+                ],
+                regex_patterns: vec![
+                    r"(?im)\bi'?m\s+((only|just)\s+)?(1[0-7]|thirteen|fourteen|fifteen|sixteen|seventeen)(\s+years?\s+old\b|\s*([.,!?;]|$)|\s+(and|so|but)\b)".to_string(),
+                    r"(?i)\b(i'?m\s+(still\s+)?in\s+(high|middle)\s+school|i'?m\s+not\s+(18|eighteen)\s+yet)".to_string(),
+                    r"(?i)\bmy\s+(mom|mother|dad|father|parents?|guardian|caregiver|son|daughter)\s+(handles?|takes?\s+care\s+of|manages?|does)\s+(that|all\s+that|the\s+bills|my\s+(money|finances|bills))".to_string(),
+                    r"(?i)\bi\s+(have|was\s+diagnosed\s+with)\s+(dementia|alzheimer'?s|memory\s+problems)".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: None,
+                why_it_matters: "Minors can't enter most contracts and some products can't be marketed to them \
+                                 at all. A sale to someone who lacks capacity can be voided and treated as an \
+                                 unfair practice.".to_string(),
+                recommended_fix: "Stop the pitch and verify: 'Before we go any further, can I confirm you're \
+                                  over 18 and the person who handles these decisions?' If not, end the call \
+                                  politely.".to_string(),
+                legal_reference: "FTC Act § 5, 15 U.S.C. § 45 (unfair practices); state contract capacity laws".to_string(),
+                enabled: true,
+                optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![
+                    RuleExample {
+                        transcript: "Customer: Oh, I'm only 16, my mom handles all that.".to_string(),
+                        expect_alert: true,
+                        quote: Some("I'm only 16".to_string()),
+                    },
+                    RuleExample {
+                        transcript: "Customer: My son set up the account, but I handle the bills myself.".to_string(),
+                        expect_alert: false,
+                        quote: None,
+                    },
+                    RuleExample {
+                        transcript: "Customer: Sure, I'm only 15 minutes from the store.".to_string(),
+                        expect_alert: false,
+                        quote: None,
+                    },
+                ],
+                suppresses: vec![],
+                semantic: false,
+            },
+            
+            // Misrepresentation Rules
+            Rule {
+                id: "MISREP-001".to_string(),
+                title: "Unqualified Savings or Earnings Claim".to_string(),
+                category: RuleCategory::Misrepresentation,
+                description: "Agent promised a specific dollar or percentage savings or earnings figure with \
+                              no qualification such as \"up to\" or \"results vary\". Regex patterns capture \
+                              the claim, including the figure.".to_string(),
+                severity: Severity::High,
+                triggers: vec![
+                    "save".to_string(),
+                    "saving".to_string(),
+                    "earn".to_string(),
+                    "guarantee".to_string(),
+                    "in your pocket".to_string(),
+                    "cut".to_string(),
+                    "lower".to_string(),
+                    "reduce".to_string(),
+                ],
+                regex_patterns: vec![
+                    r"(?i)\b(save|saving|earn|pocket|cut)\b[^.?!]{0,40}?(?P<amount>\$\s?\d[\d,]*(\.\d+)?(\s*(k|thousand|hundred))?)(\s+(a|per|every)\s+(?P<period>year|month|week))?".to_string(),
+                    r"(?i)(?P<amount>\$\s?\d[\d,]*(\.\d+)?)(\s+(a|per|every)\s+(?P<period>year|month|week))?\s+(in\s+savings|guaranteed|in\s+your\s+pocket)".to_string(),
+                    r"(?i)\bguarantee[ds]?\b[^.?!]{0,40}?(?P<amount>\$\s?\d[\d,]*(\.\d+)?)".to_string(),
+                    r"(?i)\b(save|cut|lower|reduce)\b[^.?!]{0,30}?\b(?P<amount>\d{1,3}\s?(%|percent))".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: None,
+                why_it_matters: "Misrepresenting the benefits of a product is a deceptive telemarketing act. \
+                                 A specific promised figure is the claim regulators and plaintiffs cite, and \
+                                 the agent can rarely substantiate it for this consumer.".to_string(),
+                recommended_fix: "Use approved, substantiated figures with their qualification: 'Customers \
+                                  save an average of $200 a year, and your results may vary.'".to_string(),
+                legal_reference: "16 C.F.R. § 310.3(a)(2)(iii), § 310.3(a)(4)".to_string(),
+                enabled: true,
+                optional: false,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![
+                    RuleExample {
+                        transcript: "Agent: Switch today and you'll save $600 a year on your electric bill.".to_string(),
+                        expect_alert: true,
+                        quote: Some("save $600 a year".to_string()),
+                    },
+                    RuleExample {
+                        transcript: "Agent: Most customers save up to $300 a year, though results vary.".to_string(),
+                        expect_alert: false,
+                        quote: None,
+                    },
+                    RuleExample {
+                        transcript: "Agent: You'll make your first payment of $99 next month.".to_string(),
+                        expect_alert: false,
+                        quote: None,
+                    },
+                ],
+                suppresses: vec![],
+                semantic: false,
             },
             
             // Recording Disclosure Rules (Optional Module)
@@ -319,6 +899,157 @@ impl RuleSet {
                 legal_reference: "State-specific wiretapping/recording consent laws".to_string(),
                 enabled: true,
                 optional: true,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![
+                    "this conversation is recorded".to_string(),
+                    "we record our calls".to_string(),
+                    "calls are recorded".to_string(),
+                ],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
+            },
+            Rule {
+                id: "REC-002".to_string(),
+                title: "Recording Disclosed Too Late".to_string(),
+                category: RuleCategory::RecordingDisclosure,
+                description: "On a collections call, the agent gave the recording notice only after the consumer \
+                              had started making statements. Triggers and regex patterns are the agent's \
+                              recording notice.".to_string(),
+                severity: Severity::Medium,
+                triggers: vec![
+                    "this is a recorded line".to_string(),
+                    "you are on a recorded line".to_string(),
+                    "this call is being recorded".to_string(),
+                ],
+                regex_patterns: vec![
+                    r"(?i)(on\s+a\s+recorded\s+line|this\s+call\s+(is|may\s+be)\s+(being\s+)?recorded|calls\s+are\s+recorded)".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: None,
+                why_it_matters: "A recording notice given after the consumer has already made statements doesn't \
+                                 cover them. Admissions made before the notice may not be usable, and recording \
+                                 them can breach two-party consent laws.".to_string(),
+                recommended_fix: "Before we go any further, I need to let you know this is a recorded line.".to_string(),
+                legal_reference: "State-specific wiretapping/recording consent laws".to_string(),
+                enabled: true,
+                optional: true,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
+            },
+            
+            // Delivery Quality Rules (Optional Module)
+            Rule {
+                id: "DELIV-001".to_string(),
+                title: "Rushed Disclosure Delivery".to_string(),
+                category: RuleCategory::DeliveryQuality,
+                description: "Agent read a required disclosure faster than the configured words-per-minute limit. \
+                              Regex patterns identify the disclosure turns; timing comes from segment timestamps.".to_string(),
+                severity: Severity::Low,
+                triggers: vec![],
+                regex_patterns: vec![
+                    r"(?i)(calling\s+(from|on\s+behalf\s+of)|my\s+name\s+is.*?(with|from))".to_string(),
+                    r"(?i)(this\s+call\s+(is|may\s+be)\s+(being\s+)?recorded|for\s+quality\s+(and\s+training\s+)?purposes)".to_string(),
+                    r"(?i)(to\s+be\s+removed\s+from|to\s+opt\s+out|to\s+stop\s+receiving)".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: None,
+                why_it_matters: "A disclosure rattled off too fast to follow may not count as clear and \
+                                 conspicuous, and signals an agent reading the script rather than talking \
+                                 to the consumer.".to_string(),
+                recommended_fix: "Slow down for disclosures and pause after them so the consumer can take \
+                                  them in.".to_string(),
+                legal_reference: "16 C.F.R. § 310.3(a)(1) (clear and conspicuous disclosure)".to_string(),
+                enabled: true,
+                optional: true,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
+            },
+            
+            Rule {
+                id: "DELIV-002".to_string(),
+                title: "No Pause for Consumer Response".to_string(),
+                category: RuleCategory::DeliveryQuality,
+                description: "After a disclosure or question that needs the consumer's acknowledgment, the agent \
+                              kept talking past the configured limit without a customer turn. Regex patterns \
+                              identify the turns that need a response.".to_string(),
+                severity: Severity::Medium,
+                triggers: vec![],
+                regex_patterns: vec![
+                    r"(?i)(this\s+call\s+(is|may\s+be)\s+(being\s+)?recorded)".to_string(),
+                    r"(?i)(do\s+you\s+(agree|consent|understand|accept)|is\s+that\s+(ok(ay)?|alright|all\s+right))".to_string(),
+                    r"(?i)(terms\s+and\s+conditions|by\s+(saying\s+yes|continuing),?\s+you)".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: None,
+                why_it_matters: "Consent and acknowledgments only count if the consumer had a real chance to \
+                                 respond. Talking straight past a disclosure undermines any agreement that \
+                                 follows.".to_string(),
+                recommended_fix: "Pause after the disclosure and wait for the consumer to respond: \
+                                  'Does that sound okay to you?'".to_string(),
+                legal_reference: "16 C.F.R. § 310.4(a)(7) (express informed consent)".to_string(),
+                enabled: true,
+                optional: true,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
+            },
+            
+            Rule {
+                id: "DELIV-003".to_string(),
+                title: "Disclosure Undercut".to_string(),
+                category: RuleCategory::DeliveryQuality,
+                description: "Agent distanced themselves from a required disclosure, e.g. \"I'm required to read \
+                              this but\" or \"legally I have to say\". Triggers are the undercutting phrases; \
+                              regex patterns identify the disclosure, which must be in the same or an adjacent \
+                              agent turn.".to_string(),
+                severity: Severity::Medium,
+                triggers: vec![
+                    "required to read".to_string(),
+                    "required to say".to_string(),
+                    "legally i have to".to_string(),
+                    "legally i've got to".to_string(),
+                    "i have to read this".to_string(),
+                    "i have to say this".to_string(),
+                    "supposed to read".to_string(),
+                    "supposed to say".to_string(),
+                    "just a formality".to_string(),
+                    "the lawyers make me".to_string(),
+                    "don't worry about this part".to_string(),
+                ],
+                regex_patterns: vec![
+                    r"(?i)(calling\s+(from|on\s+behalf\s+of)|my\s+name\s+is.*?(with|from))".to_string(),
+                    r"(?i)(this\s+call\s+(is|may\s+be)\s+(being\s+)?recorded|for\s+quality\s+(and\s+training\s+)?purposes)".to_string(),
+                    r"(?i)(to\s+be\s+removed\s+from|to\s+opt\s+out|to\s+stop\s+receiving)".to_string(),
+                    r"(?i)(terms\s+and\s+conditions|cancel\s+(at\s+)?any\s*time|no\s+purchase\s+(is\s+)?necessary)".to_string(),
+                ],
+                requires_metadata: false,
+                metadata_field: None,
+                why_it_matters: "A disclosure the agent dismisses while reading it may not count as clear and \
+                                 conspicuous, and tells the consumer it can be ignored.".to_string(),
+                recommended_fix: "Read disclosures plainly, without commentary: 'Before we go on, I want you \
+                                  to know that this call is being recorded.'".to_string(),
+                legal_reference: "16 C.F.R. § 310.3(a)(1) (clear and conspicuous disclosure)".to_string(),
+                enabled: true,
+                optional: true,
+                depends_on: None,
+                jurisdictions: vec![FEDERAL_JURISDICTION.to_string()],
+                satisfying_phrases: vec![],
+                examples: vec![],
+                suppresses: vec![],
+                semantic: false,
             },
         ]
     }
@@ -338,12 +1069,63 @@ impl RuleSet {
         self.rules.iter().filter(|r| r.category == category).collect()
     }
     
+    /// Enable or disable all rules in a category, returning the affected rule IDs
+    pub fn set_category_enabled(&mut self, category: &RuleCategory, enabled: bool) -> Vec<String> {
+        self.rules.iter_mut()
+            .filter(|r| &r.category == category)
+            .map(|r| {
+                r.enabled = enabled;
+                r.id.clone()
+            })
+            .collect()
+    }
+    
+    /// Apply persisted (rule_id, enabled) overrides on top of the loaded rules
+    pub fn apply_overrides(&mut self, overrides: &[(String, bool)]) {
+        for (rule_id, enabled) in overrides {
+            if let Some(rule) = self.rules.iter_mut().find(|r| &r.id == rule_id) {
+                rule.enabled = *enabled;
+            }
+        }
+    }
+
+    /// Copy of the ruleset restricted to the given rule IDs, for evaluating a
+    /// subset of rules. Unknown IDs are an error rather than silently ignored.
+    pub fn subset(&self, rule_ids: &[String]) -> Result<RuleSet, String> {
+        let unknown: Vec<&str> = rule_ids.iter()
+            .filter(|id| !self.rules.iter().any(|r| &r.id == *id))
+            .map(|id| id.as_str())
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!("Unknown rule IDs: {}", unknown.join(", ")));
+        }
+
+        Ok(RuleSet {
+            version: self.version.clone(),
+            last_updated: self.last_updated.clone(),
+            disclaimer: self.disclaimer.clone(),
+            rules: self.rules.iter().filter(|r| rule_ids.contains(&r.id)).cloned().collect(),
+        })
+    }
+
+    /// Copy of the ruleset restricted to rules that apply to a consumer in
+    /// `consumer_state`, for building an LLM prompt that matches the regex pass
+    pub fn applicable_in(&self, consumer_state: Option<&str>) -> RuleSet {
+        RuleSet {
+            version: self.version.clone(),
+            last_updated: self.last_updated.clone(),
+            disclaimer: self.disclaimer.clone(),
+            rules: self.rules.iter().filter(|r| r.applies_in(consumer_state)).cloned().collect(),
+        }
+    }
+
     /// Generate YAML representation for LLM prompt
     pub fn to_yaml(&self) -> String {
         let mut yaml = String::new();
         yaml.push_str(&format!("# TCPA Compliance Rules v{}\n\n", self.version));
         
-        for rule in &self.rules {
+        let example_counts = self.prompt_example_counts();
+        for (i, rule) in self.rules.iter().enumerate() {
             if !rule.enabled {
                 continue;
             }
@@ -357,9 +1139,78 @@ impl RuleSet {
             if !rule.triggers.is_empty() {
                 yaml.push_str(&format!("- Trigger phrases: {:?}\n", rule.triggers));
             }
+            for example in rule.examples.iter().take(example_counts[i]) {
+                let outcome = match (&example.expect_alert, &example.quote) {
+                    (true, Some(quote)) => format!("alert, quote {:?}", quote),
+                    (true, None) => "alert".to_string(),
+                    (false, _) => "no alert".to_string(),
+                };
+                yaml.push_str(&format!("- Example: {:?} -> {}\n", example.transcript, outcome));
+            }
             yaml.push_str("\n");
         }
         
         yaml
     }
+    
+    /// How many of each rule's examples go in the prompt: up to
+    /// `MAX_PROMPT_EXAMPLES_PER_RULE` per enabled rule, High-severity rules
+    /// first, until `MAX_PROMPT_EXAMPLES` are used
+    fn prompt_example_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.rules.len()];
+        let mut budget = MAX_PROMPT_EXAMPLES;
+        for severity in [Severity::High, Severity::Medium, Severity::Low] {
+            for (i, rule) in self.rules.iter().enumerate() {
+                if rule.enabled && rule.severity == severity {
+                    counts[i] = rule.examples.len().min(MAX_PROMPT_EXAMPLES_PER_RULE).min(budget);
+                    budget -= counts[i];
+                }
+            }
+        }
+        counts
+    }
+    
+    /// The rules exactly as they are presented to the LLM, headed by the disclaimer.
+    /// This is a read-only prompt view, not a round-trippable serialization.
+    pub fn to_llm_view(&self) -> String {
+        format!("# {}\n\n{}", self.disclaimer, self.to_yaml())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_rule_patterns_compile() {
+        for rule in RuleSet::get_default_rules() {
+            for pattern in &rule.regex_patterns {
+                if let Err(e) = regex::Regex::new(pattern) {
+                    panic!("{} has an invalid pattern {:?}: {}", rule.id, pattern, e);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn metadata_rules_name_their_field() {
+        for rule in RuleSet::get_default_rules() {
+            if rule.requires_metadata {
+                assert!(rule.metadata_field.is_some(), "{} requires metadata but has no metadata_field", rule.id);
+            }
+        }
+    }
+
+    #[test]
+    fn vague_seller_needs_self_identification() {
+        let rules = RuleSet::get_default_rules();
+        let rule = rules.iter().find(|r| r.id == "DISC-004").unwrap();
+        let patterns: Vec<regex::Regex> = rule.regex_patterns.iter().map(|p| regex::Regex::new(p).unwrap()).collect();
+        let matches = |text: &str| patterns.iter().any(|re| re.is_match(text));
+        
+        assert!(matches("Hi, I'm calling on behalf of your current provider about your account."));
+        assert!(matches("This is Sam with the warranty department."));
+        assert!(!matches("We're not affiliated with your current provider."));
+        assert!(!matches("We can beat the rate from your current provider."));
+    }
 }
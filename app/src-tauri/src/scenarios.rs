@@ -0,0 +1,124 @@
+use serde::Serialize;
+use crate::{CallMetadata, TranscriptSegment};
+
+/// A scripted call for demos and onboarding, played through the evaluator one
+/// segment at a time
+pub struct Scenario {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// (speaker, timestamp_ms, text) in call order
+    turns: &'static [(&'static str, u64, &'static str)],
+}
+
+/// Scenario summary for the picker
+#[derive(Debug, Clone, Serialize)]
+pub struct ScenarioInfo {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    pub segments: usize,
+}
+
+const SCENARIOS: &[Scenario] = &[
+    Scenario {
+        id: "clean_call",
+        title: "Clean sales call",
+        description: "Seller, sales purpose and recording are disclosed up front; no alerts expected.",
+        turns: &[
+            ("agent", 0, "Hi, this is Dana calling from Brightline Solar. This call may be recorded for quality purposes."),
+            ("customer", 6_500, "Okay, what's this about?"),
+            ("agent", 9_000, "I'm calling with a special offer on home solar panels for homeowners in your area."),
+            ("customer", 15_000, "Sure, go ahead."),
+            ("agent", 18_000, "Our panels lower most electric bills by about a third, with no installation fee this month."),
+            ("customer", 25_500, "How much would it be?"),
+            ("agent", 28_000, "The lease is $89 per month, and you can cancel within the first thirty days."),
+            ("customer", 35_000, "Let me talk to my wife and call you back."),
+            ("agent", 38_000, "Of course. You can reach us at 1-800-555-0142. Thanks for your time!"),
+        ],
+    },
+    Scenario {
+        id: "dnc_violation",
+        title: "Do Not Call request ignored",
+        description: "The customer asks to stop the calls and the agent keeps pitching.",
+        turns: &[
+            ("agent", 0, "Hi, this is Sam calling from Apex Home Warranty with a special offer on your home coverage."),
+            ("customer", 7_000, "I've had three of these calls this week. Please stop calling me."),
+            ("agent", 12_500, "I understand, but before you go, this offer ends Friday and it covers every major appliance."),
+            ("customer", 19_000, "I said no. Take me off your list."),
+            ("agent", 22_000, "Just one more thing, it's only $29 per month if I sign you up today."),
+            ("customer", 27_000, "Goodbye."),
+        ],
+    },
+    Scenario {
+        id: "missing_disclosures",
+        title: "Disclosures skipped",
+        description: "The agent goes straight to pricing, names the seller only afterwards and never says it's a sales call.",
+        turns: &[
+            ("agent", 0, "Hi there, is this the homeowner? Great, how are you doing today?"),
+            ("customer", 4_000, "I'm fine, who is this?"),
+            ("agent", 6_500, "I can get your internet bill down to $39 per month and sign you up in two minutes."),
+            ("customer", 13_000, "Wait, who did you say you were with?"),
+            ("agent", 16_000, "Sorry about that, this is Jordan calling from Riverstone Internet."),
+            ("customer", 21_000, "I'm not really interested right now."),
+            ("agent", 24_000, "No problem, have a great day."),
+        ],
+    },
+];
+
+/// Built-in scenarios in display order
+pub fn list_scenarios() -> Vec<ScenarioInfo> {
+    SCENARIOS.iter()
+        .map(|s| ScenarioInfo {
+            id: s.id.to_string(),
+            title: s.title.to_string(),
+            description: s.description.to_string(),
+            segments: s.turns.len(),
+        })
+        .collect()
+}
+
+pub fn find_scenario(id: &str) -> Option<&'static Scenario> {
+    SCENARIOS.iter().find(|s| s.id == id)
+}
+
+impl Scenario {
+    /// Metadata for one run: an outbound sales call at 10am Eastern, under a
+    /// fresh call ID so runs never share evaluator state
+    pub fn metadata(&self) -> CallMetadata {
+        CallMetadata {
+            call_id: format!("scenario-{}-{}", self.id, uuid::Uuid::new_v4()),
+            agent_id: "demo-agent".to_string(),
+            agent_name: "Demo Agent".to_string(),
+            call_start_time: "2026-01-15T15:00:00Z".to_string(),
+            caller_timezone: Some("America/New_York".to_string()),
+            customer_phone: None,
+            is_dnc_listed: false,
+            has_prior_consent: false,
+            is_prerecorded: false,
+            call_type: "outbound_sales".to_string(),
+            number_reassigned: false,
+            consumer_state: None,
+            campaign_id: None,
+            company_name: None,
+            metadata_source: "verified_scrub".to_string(),
+            tenant_id: None,
+        }
+    }
+
+    /// The script as transcript segments; offsets are filled in on assembly
+    pub fn segments(&self) -> Vec<TranscriptSegment> {
+        self.turns.iter()
+            .enumerate()
+            .map(|(i, (speaker, timestamp_ms, text))| TranscriptSegment {
+                id: format!("{}-{}", self.id, i + 1),
+                speaker: speaker.to_string(),
+                text: text.to_string(),
+                timestamp_ms: *timestamp_ms,
+                start_char: 0,
+                end_char: 0,
+                diarization_confidence: None,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,263 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use crate::evaluator::{build_alert, byte_to_utf16, Alert, Evidence, SOURCE_SEMANTIC};
+use crate::llm::LlmClient;
+use crate::rules::Rule;
+use crate::transcript::normalize_speaker;
+
+/// Ollama embedding model used unless another is configured
+pub const DEFAULT_EMBEDDING_MODEL: &str = "nomic-embed-text";
+
+/// Cosine similarity a turn needs with one of a rule's triggers or its
+/// description before the rule is flagged
+pub const DEFAULT_SEMANTIC_THRESHOLD: f64 = 0.8;
+
+/// Only the most recent turns are matched on each evaluation, to bound the
+/// embedding requests. A paraphrase that has scrolled out of this window
+/// before an evaluation sees it is missed.
+const SEMANTIC_MAX_TURNS: usize = 20;
+
+/// Turn prefixes longer than this aren't treated as a speaker label
+const MAX_SPEAKER_LABEL_LEN: usize = 20;
+
+/// Semantic matching configuration. Off by default: it needs an embedding
+/// model installed in Ollama and adds a request per turn to each evaluation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SemanticSettings {
+    pub enabled: bool,
+    pub model: String,
+    pub threshold: f64,
+}
+
+impl SemanticSettings {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.model.trim().is_empty() {
+            return Err("Semantic matching needs an embedding model".to_string());
+        }
+        if !(self.threshold > 0.0 && self.threshold <= 1.0) {
+            return Err("Semantic threshold must be greater than 0 and at most 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+impl Default for SemanticSettings {
+    fn default() -> Self {
+        SemanticSettings {
+            enabled: false,
+            model: DEFAULT_EMBEDDING_MODEL.to_string(),
+            threshold: DEFAULT_SEMANTIC_THRESHOLD,
+        }
+    }
+}
+
+/// A call's turn embeddings keyed by (byte offset, text)
+type TurnEmbeddings = HashMap<(usize, String), Vec<f32>>;
+
+/// Flags paraphrases the regex triggers miss ("quit bugging me") by comparing
+/// embeddings of transcript turns with embeddings of each `semantic` rule's
+/// triggers and description
+pub struct SemanticMatcher {
+    settings: Mutex<SemanticSettings>,
+    /// Embeddings of rule text keyed by (model, text), kept across calls
+    rule_embeddings: Mutex<HashMap<(String, String), Vec<f32>>>,
+    /// Embeddings of each call's recent turns, so re-evaluating a growing
+    /// transcript only embeds the new turns
+    turn_embeddings: Mutex<HashMap<(Option<String>, String), TurnEmbeddings>>,
+}
+
+impl SemanticMatcher {
+    pub fn new() -> Self {
+        SemanticMatcher {
+            settings: Mutex::new(SemanticSettings::default()),
+            rule_embeddings: Mutex::new(HashMap::new()),
+            turn_embeddings: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn settings(&self) -> SemanticSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    pub fn set_settings(&self, settings: SemanticSettings) {
+        let mut current = self.settings.lock().unwrap();
+        if current.model != settings.model {
+            self.turn_embeddings.lock().unwrap().clear();
+        }
+        *current = settings;
+    }
+    
+    /// Drop a call's cached turn embeddings once its session is over
+    pub fn forget_call(&self, tenant_id: Option<&str>, call_id: &str) {
+        self.turn_embeddings.lock().unwrap().remove(&(tenant_id.map(str::to_string), call_id.to_string()));
+    }
+    
+    /// Drop every call's cached turn embeddings
+    pub fn forget_calls(&self) {
+        self.turn_embeddings.lock().unwrap().clear();
+    }
+
+    /// Match the turns of the call's `transcript` from byte offset `from`
+    /// against `rules`, returning at most one alert per rule, for its most
+    /// similar turn. Evidence offsets are UTF-16 offsets into the whole
+    /// transcript.
+    pub async fn match_transcript(
+        &self,
+        llm: &LlmClient,
+        call: (Option<&str>, &str),
+        transcript: &str,
+        from: usize,
+        rules: &[&Rule],
+    ) -> Result<Vec<Alert>, String> {
+        let settings = self.settings();
+        if !settings.enabled || rules.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Only turns some rule can match on are embedded
+        let turns: Vec<Turn> = recent_turns(transcript, from).into_iter()
+            .filter(|turn| rules.iter().any(|rule| matches_speaker(rule, &turn.speaker)))
+            .collect();
+        let turn_embeddings = self.turn_embeddings(llm, &settings.model, call, transcript, &turns).await?;
+
+        let mut alerts = Vec::new();
+        for rule in rules {
+            let references = self.rule_references(llm, &settings.model, rule).await?;
+            let best = turn_embeddings.iter().enumerate()
+                .filter(|(i, _)| matches_speaker(rule, &turns[*i].speaker))
+                .map(|(i, turn)| {
+                    let similarity = references.iter()
+                        .map(|reference| cosine_similarity(turn, reference))
+                        .fold(0.0, f64::max);
+                    (i, similarity)
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+
+            if let Some((i, similarity)) = best.filter(|(_, s)| *s >= settings.threshold) {
+                let Turn { start, end, .. } = turns[i];
+                let mut alert = build_alert(rule, (similarity * 100.0).round().min(100.0) as u8, Evidence {
+                    quote: transcript[start..end].to_string(),
+                    start_char: byte_to_utf16(transcript, start),
+                    end_char: byte_to_utf16(transcript, end),
+                });
+                alert.sources = vec![SOURCE_SEMANTIC.to_string()];
+                alerts.push(alert);
+            }
+        }
+        Ok(alerts)
+    }
+
+    /// Embeddings of `turns`, embedding only those not cached for the call.
+    /// The cache keeps just these turns, so it doesn't grow with the call.
+    async fn turn_embeddings(
+        &self,
+        llm: &LlmClient,
+        model: &str,
+        (tenant_id, call_id): (Option<&str>, &str),
+        transcript: &str,
+        turns: &[Turn],
+    ) -> Result<Vec<Vec<f32>>, String> {
+        let key = (tenant_id.map(str::to_string), call_id.to_string());
+        let mut cached = self.turn_embeddings.lock().unwrap().remove(&key).unwrap_or_default();
+        let mut kept = HashMap::with_capacity(turns.len());
+        let mut embeddings = Vec::with_capacity(turns.len());
+        for turn in turns {
+            let text = &transcript[turn.start..turn.end];
+            let turn_key = (turn.start, text.to_string());
+            let embedding = match cached.remove(&turn_key) {
+                Some(embedding) => embedding,
+                None => llm.embed(model, text).await?,
+            };
+            kept.insert(turn_key, embedding.clone());
+            embeddings.push(embedding);
+        }
+        self.turn_embeddings.lock().unwrap().insert(key, kept);
+        Ok(embeddings)
+    }
+
+    /// Embeddings of a rule's triggers and description, from the cache when
+    /// they've been embedded before with this model
+    async fn rule_references(&self, llm: &LlmClient, model: &str, rule: &Rule) -> Result<Vec<Vec<f32>>, String> {
+        let texts = rule.triggers.iter().chain(std::iter::once(&rule.description));
+        let mut references = Vec::new();
+        for text in texts {
+            let key = (model.to_string(), text.clone());
+            let cached = self.rule_embeddings.lock().unwrap().get(&key).cloned();
+            let embedding = match cached {
+                Some(embedding) => embedding,
+                None => {
+                    let embedding = llm.embed(model, text).await?;
+                    self.rule_embeddings.lock().unwrap().insert(key, embedding.clone());
+                    embedding
+                }
+            };
+            references.push(embedding);
+        }
+        Ok(references)
+    }
+}
+
+/// A transcript turn: the byte span of its spoken text, without any
+/// `Speaker:` label, and its speaker (`unknown` when unlabelled)
+struct Turn {
+    start: usize,
+    end: usize,
+    speaker: String,
+}
+
+/// DNC-001 and CONS-001 are about what the customer asked for. An agent
+/// offering to add the number to the do-not-call list isn't a request.
+fn matches_speaker(rule: &Rule, speaker: &str) -> bool {
+    match rule.id.as_str() {
+        "DNC-001" | "CONS-001" => speaker == "customer",
+        _ => true,
+    }
+}
+
+/// The last `SEMANTIC_MAX_TURNS` non-empty lines at or after `from`
+fn recent_turns(transcript: &str, from: usize) -> Vec<Turn> {
+    let mut turns = Vec::new();
+    let mut line_start = 0;
+    for line in transcript.split_inclusive('\n') {
+        let start = line_start;
+        line_start += line.len();
+        if start < from {
+            continue;
+        }
+
+        let mut text_start = start;
+        let mut speaker = "unknown".to_string();
+        if let Some(colon) = line.find(':').filter(|&c| c <= MAX_SPEAKER_LABEL_LEN) {
+            text_start += colon + 1;
+            speaker = normalize_speaker(line[..colon].trim());
+        }
+        let text = &transcript[text_start..start + line.len()];
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let text_start = text_start + (text.len() - text.trim_start().len());
+        turns.push(Turn { start: text_start, end: text_start + trimmed.len(), speaker });
+    }
+
+    let skip = turns.len().saturating_sub(SEMANTIC_MAX_TURNS);
+    turns.split_off(skip)
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f64 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let (mut dot, mut norm_a, mut norm_b) = (0.0f64, 0.0f64, 0.0f64);
+    for (x, y) in a.iter().zip(b) {
+        let (x, y) = (*x as f64, *y as f64);
+        dot += x * y;
+        norm_a += x * x;
+        norm_b += y * y;
+    }
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a.sqrt() * norm_b.sqrt())
+}
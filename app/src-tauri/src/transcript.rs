@@ -0,0 +1,474 @@
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use crate::TranscriptSegment;
+
+/// Default time a streamed segment is held back in case an earlier one arrives late
+pub const DEFAULT_REORDER_WINDOW_MS: u64 = 500;
+
+/// Reordering buffer for one call's streamed segments. Segments from several
+/// STT streams can arrive out of `timestamp_ms` order; each is held for the
+/// reorder window before being released into the ordered transcript.
+#[derive(Debug, Default)]
+pub struct SegmentBuffer {
+    /// Released segments, sorted by `timestamp_ms` then `start_char`
+    released: Vec<TranscriptSegment>,
+    /// Segments still inside the reorder window, with their arrival time
+    pending: Vec<(TranscriptSegment, Instant)>,
+    /// ID of the most recently accepted segment, for STT duplicate checks
+    last_pushed: Option<String>,
+}
+
+impl SegmentBuffer {
+    /// Add a segment. A segment re-sent with the same id replaces the earlier copy.
+    ///
+    /// Some STT engines re-emit a segment as it finalizes, under a new id. When
+    /// the previous segment is from the same speaker, a segment extending its
+    /// text word for word is taken as the final of that partial and replaces it,
+    /// while one repeating it (or a stale partial of it) is dropped. Returns
+    /// whether the segment was kept.
+    pub fn push(&mut self, segment: TranscriptSegment, now: Instant) -> bool {
+        self.released.retain(|s| s.id != segment.id);
+        self.pending.retain(|(s, _)| s.id != segment.id);
+        
+        let previous = self.last_pushed.as_deref().and_then(|id| {
+            self.pending.iter().map(|(s, _)| s)
+                .chain(&self.released)
+                .find(|s| s.id == id)
+        });
+        if let Some(previous) = previous.filter(|p| p.speaker == segment.speaker) {
+            let (previous_text, text) = (normalize_stt_text(&previous.text), normalize_stt_text(&segment.text));
+            if starts_with_words(&previous_text, &text) {
+                return false;
+            }
+            if starts_with_words(&text, &previous_text) {
+                let partial_id = previous.id.clone();
+                self.released.retain(|s| s.id != partial_id);
+                self.pending.retain(|(s, _)| s.id != partial_id);
+            }
+        }
+        
+        self.last_pushed = Some(segment.id.clone());
+        self.pending.push((segment, now));
+        true
+    }
+
+    /// Move segments that have waited at least `window` into the ordered
+    /// transcript. Returns whether anything was released.
+    pub fn release(&mut self, window: Duration, now: Instant) -> bool {
+        let (ready, waiting): (Vec<_>, Vec<_>) = self.pending
+            .drain(..)
+            .partition(|(_, arrived)| now.duration_since(*arrived) >= window);
+        self.pending = waiting;
+        if ready.is_empty() {
+            return false;
+        }
+
+        // A late segment can still land before ones already released
+        self.released.extend(ready.into_iter().map(|(segment, _)| segment));
+        self.released.sort_by_key(|s| (s.timestamp_ms, s.start_char));
+        true
+    }
+
+    /// Release everything, e.g. when the call ends
+    pub fn flush(&mut self) -> bool {
+        self.release(Duration::ZERO, Instant::now())
+    }
+
+    /// The ordered transcript so far
+    pub fn segments_mut(&mut self) -> &mut [TranscriptSegment] {
+        &mut self.released
+    }
+}
+
+/// Speaking rate assumed for a call's last turn, which has no following turn
+/// to time it against
+const LAST_TURN_WPM: f64 = 150.0;
+
+/// Each party's speaking time and word count on a call
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct TalkTime {
+    pub agent_talk_ms: u64,
+    pub customer_talk_ms: u64,
+    pub agent_words: u32,
+    pub customer_words: u32,
+}
+
+impl TalkTime {
+    /// Agent's share of the total speaking time, if anyone spoke
+    pub fn agent_ratio(&self) -> Option<f64> {
+        let total = self.agent_talk_ms + self.customer_talk_ms;
+        (total > 0).then(|| self.agent_talk_ms as f64 / total as f64)
+    }
+}
+
+/// Talk time from ordered, timestamped segments. A turn lasts until the next
+/// one starts, so pauses count towards whoever spoke last. Turns diarized
+/// below `min_confidence` count for neither party. `None` without segments.
+pub fn talk_time(segments: &[TranscriptSegment], min_confidence: f64) -> Option<TalkTime> {
+    if segments.is_empty() {
+        return None;
+    }
+    let mut talk = TalkTime::default();
+    for (i, segment) in segments.iter().enumerate() {
+        let words = segment.text.split_whitespace().count() as u32;
+        let duration_ms = match segments.get(i + 1) {
+            Some(next) => next.timestamp_ms.saturating_sub(segment.timestamp_ms),
+            None => (words as f64 / LAST_TURN_WPM * 60_000.0) as u64,
+        };
+        if segment.diarization_confidence.is_some_and(|c| c < min_confidence) {
+            continue;
+        }
+        match segment.speaker.as_str() {
+            "agent" => {
+                talk.agent_talk_ms += duration_ms;
+                talk.agent_words += words;
+            }
+            "customer" => {
+                talk.customer_talk_ms += duration_ms;
+                talk.customer_words += words;
+            }
+            _ => {}
+        }
+    }
+    Some(talk)
+}
+
+/// Fewer words than this in a transcript suggests the speech-to-text feed
+/// dropped out rather than a real conversation
+const MIN_PLAUSIBLE_WORDS: usize = 5;
+
+/// Data-quality warnings for a transcript about to be evaluated. An empty or
+/// near-empty transcript scores zero alerts, which would otherwise look like
+/// a clean call.
+pub fn quality_warnings(transcript: &str) -> Vec<String> {
+    let words = transcript.split_whitespace().count();
+    let warning = if transcript.is_empty() {
+        "Transcript is empty; speech-to-text may have failed".to_string()
+    } else if words == 0 {
+        "Transcript contains only whitespace; speech-to-text may have failed".to_string()
+    } else if words < MIN_PLAUSIBLE_WORDS {
+        format!("Transcript is suspiciously short ({} words); check the speech-to-text feed", words)
+    } else {
+        return Vec::new();
+    };
+    vec![warning]
+}
+
+/// Byte index where the last `max_chars` characters of a transcript begin,
+/// moved forward to the next line so no turn is cut in half. 0 if the whole
+/// transcript fits.
+pub fn trailing_window_start(transcript: &str, max_chars: usize) -> usize {
+    let total = transcript.chars().count();
+    if total <= max_chars {
+        return 0;
+    }
+    let Some((cut, _)) = transcript.char_indices().nth(total - max_chars) else {
+        return 0;
+    };
+    match transcript[cut..].find('\n') {
+        Some(newline) => cut + newline + 1,
+        None => cut,
+    }
+}
+
+/// Segment text for duplicate checks: lowercase, single-spaced, without
+/// trailing punctuation that STT engines add on finalizing
+fn normalize_stt_text(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+        .trim_end_matches(|c: char| c.is_ascii_punctuation())
+        .to_string()
+}
+
+/// Whether `text` starts with the words of `prefix`, comparing whole words so
+/// a customer's "no" isn't taken as a partial of "not now"
+fn starts_with_words(text: &str, prefix: &str) -> bool {
+    let mut words = text.split_whitespace();
+    prefix.split_whitespace().all(|word| words.next() == Some(word))
+}
+
+/// Supported transcript file layouts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TranscriptFormat {
+    PlainText,
+    WebVtt,
+    Srt,
+}
+
+impl TranscriptFormat {
+    /// Pick a format from the file extension, sniffing the content for VTT headers
+    fn detect(path: &Path, content: &str) -> Self {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+
+        match ext.as_str() {
+            "vtt" => TranscriptFormat::WebVtt,
+            "srt" => TranscriptFormat::Srt,
+            _ if content.trim_start().starts_with("WEBVTT") => TranscriptFormat::WebVtt,
+            _ => TranscriptFormat::PlainText,
+        }
+    }
+}
+
+/// Read a `.txt`, `.vtt` or `.srt` transcript file into segments
+pub fn read_transcript_file(path: &Path) -> Result<Vec<TranscriptSegment>, String> {
+    let bytes = std::fs::read(path)
+        .map_err(|e| format!("Failed to read transcript file {}: {}", path.display(), e))?;
+    let content = decode_bytes(&bytes);
+
+    let mut segments = match TranscriptFormat::detect(path, &content) {
+        TranscriptFormat::WebVtt => parse_cues(&content, true),
+        TranscriptFormat::Srt => parse_cues(&content, false),
+        TranscriptFormat::PlainText => parse_plain_text(&content),
+    };
+
+    if segments.is_empty() {
+        return Err(format!("No transcript text found in {}", path.display()));
+    }
+
+    assemble_transcript(&mut segments);
+    Ok(segments)
+}
+
+/// Segments of a plain-text transcript passed in directly rather than read
+/// from a file, one turn per line
+pub fn parse_transcript_text(content: &str) -> Vec<TranscriptSegment> {
+    let mut segments = parse_plain_text(content);
+    assemble_transcript(&mut segments);
+    segments
+}
+
+/// The transcript file for a call in `dir`, named after the call ID with a
+/// `.txt`, `.vtt` or `.srt` extension. `None` if there isn't one, or if the
+/// call ID couldn't be a plain file name.
+pub fn find_call_transcript(dir: &Path, call_id: &str) -> Option<PathBuf> {
+    if call_id.is_empty() || call_id.contains(['/', '\\']) || call_id.starts_with('.') {
+        return None;
+    }
+    ["txt", "vtt", "srt"].iter()
+        .map(|ext| dir.join(format!("{}.{}", call_id, ext)))
+        .find(|path| path.is_file())
+}
+
+/// Join segments into the `speaker: text` transcript the evaluator expects,
+/// rewriting each segment's `start_char`/`end_char` to match the joined text
+pub fn assemble_transcript(segments: &mut [TranscriptSegment]) -> String {
+    let mut transcript = String::new();
+
+    for segment in segments.iter_mut() {
+        if !transcript.is_empty() {
+            transcript.push('\n');
+        }
+        segment.start_char = transcript.len();
+        transcript.push_str(&format!("{}: {}", segment.speaker, segment.text));
+        segment.end_char = transcript.len();
+    }
+
+    transcript
+}
+
+/// Decode raw file bytes, handling BOMs, UTF-16 and legacy Windows-1252 exports
+fn decode_bytes(bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        return String::from_utf8_lossy(rest).into_owned();
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFF, 0xFE]) {
+        return decode_utf16(rest, u16::from_le_bytes);
+    }
+    if let Some(rest) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        return decode_utf16(rest, u16::from_be_bytes);
+    }
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+
+    // BOM-less UTF-16 from some dialer exports: ASCII text leaves every other byte zero
+    let odd_zeros = bytes.iter().skip(1).step_by(2).filter(|b| **b == 0).count();
+    let even_zeros = bytes.iter().step_by(2).filter(|b| **b == 0).count();
+    let half = bytes.len() / 2;
+    if half > 0 && odd_zeros * 2 > half {
+        return decode_utf16(bytes, u16::from_le_bytes);
+    }
+    if half > 0 && even_zeros * 2 > half {
+        return decode_utf16(bytes, u16::from_be_bytes);
+    }
+
+    bytes.iter().map(|b| windows_1252_char(*b)).collect()
+}
+
+fn decode_utf16(bytes: &[u8], to_unit: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| to_unit([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+fn windows_1252_char(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8D}', 'Ž', '\u{8F}',
+        '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9D}', 'ž', 'Ÿ',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+/// Parse WebVTT or SRT cues. Both are blank-line separated blocks with a
+/// `start --> end` timing line; SRT uses a comma before milliseconds.
+fn parse_cues(content: &str, is_vtt: bool) -> Vec<TranscriptSegment> {
+    let normalized = content.replace("\r\n", "\n").replace('\r', "\n");
+    let mut segments = Vec::new();
+
+    for block in normalized.split("\n\n") {
+        let lines: Vec<&str> = block.lines().map(|l| l.trim()).filter(|l| !l.is_empty()).collect();
+        let Some(timing_idx) = lines.iter().position(|l| l.contains("-->")) else {
+            continue; // WEBVTT header, NOTE, STYLE and REGION blocks have no timing line
+        };
+
+        let start_ms = lines[timing_idx]
+            .split("-->")
+            .next()
+            .and_then(parse_timestamp_ms)
+            .unwrap_or(0);
+
+        let text = lines[timing_idx + 1..].join(" ");
+        let (speaker, text) = if is_vtt {
+            split_voice_tag(&text)
+        } else {
+            split_speaker_prefix(&text)
+        };
+        let text = strip_tags(&text);
+
+        if text.is_empty() {
+            continue;
+        }
+
+        segments.push(new_segment(segments.len(), speaker, text, start_ms));
+    }
+
+    segments
+}
+
+/// Parse plain text, one turn per line, accepting the `[mm:ss] SPEAKER: text`
+/// layout produced by the agent view's transcript download
+fn parse_plain_text(content: &str) -> Vec<TranscriptSegment> {
+    let mut segments = Vec::new();
+
+    for line in content.lines() {
+        let mut line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        let mut timestamp_ms = 0;
+        if let Some(rest) = line.strip_prefix('[') {
+            if let Some((stamp, after)) = rest.split_once(']') {
+                if let Some(ms) = parse_timestamp_ms(stamp) {
+                    timestamp_ms = ms;
+                    line = after.trim();
+                }
+            }
+        }
+
+        let (speaker, text) = split_speaker_prefix(line);
+        if text.is_empty() {
+            continue;
+        }
+        segments.push(new_segment(segments.len(), speaker, text, timestamp_ms));
+    }
+
+    segments
+}
+
+fn new_segment(index: usize, speaker: String, text: String, timestamp_ms: u64) -> TranscriptSegment {
+    TranscriptSegment {
+        id: format!("seg-{}", index + 1),
+        speaker,
+        text,
+        timestamp_ms,
+        start_char: 0,
+        end_char: 0,
+        diarization_confidence: None,
+    }
+}
+
+/// Parse `hh:mm:ss.mmm`, `mm:ss.mmm`, `hh:mm:ss,mmm` or `mm:ss` into milliseconds
+fn parse_timestamp_ms(stamp: &str) -> Option<u64> {
+    // VTT timing lines may carry cue settings after the timestamp
+    let stamp = stamp.split_whitespace().next()?.replace(',', ".");
+    let (clock, millis) = match stamp.split_once('.') {
+        Some((clock, frac)) => {
+            if !frac.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let frac = format!("{:0<3}", &frac[..frac.len().min(3)]);
+            (clock.to_string(), frac.parse::<u64>().ok()?)
+        }
+        None => (stamp, 0),
+    };
+
+    let mut seconds = 0u64;
+    for part in clock.split(':') {
+        seconds = seconds * 60 + part.parse::<u64>().ok()?;
+    }
+    Some(seconds * 1000 + millis)
+}
+
+/// Extract the speaker from a VTT `<v Speaker>` voice span, falling back to a text prefix
+fn split_voice_tag(text: &str) -> (String, String) {
+    if let Some(rest) = text.strip_prefix("<v") {
+        if let Some((name, body)) = rest.split_once('>') {
+            let name = name.trim_start_matches(|c: char| c == '.' || c.is_alphanumeric());
+            return (normalize_speaker(name.trim()), body.replace("</v>", "").trim().to_string());
+        }
+    }
+    split_speaker_prefix(text)
+}
+
+/// Split `Speaker: text`, only treating short leading labels as speakers
+pub fn split_speaker_prefix(text: &str) -> (String, String) {
+    if let Some((label, body)) = text.split_once(':') {
+        let label = label.trim();
+        if !label.is_empty() && label.len() <= 24 && !label.chars().any(|c| c.is_ascii_digit()) {
+            return (normalize_speaker(label), body.trim().to_string());
+        }
+    }
+    ("unknown".to_string(), text.trim().to_string())
+}
+
+/// Map common diarization labels onto the `agent`/`customer` speakers the UI uses
+pub fn normalize_speaker(label: &str) -> String {
+    let lower = label.to_lowercase();
+    if ["agent", "rep", "representative", "advisor"].iter().any(|k| lower.contains(k)) {
+        "agent".to_string()
+    } else if ["customer", "caller", "consumer", "client", "prospect"].iter().any(|k| lower.contains(k)) {
+        "customer".to_string()
+    } else if lower.is_empty() {
+        "unknown".to_string()
+    } else {
+        lower
+    }
+}
+
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out.split_whitespace().collect::<Vec<_>>().join(" ")
+}
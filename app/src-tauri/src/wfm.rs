@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::database::CallAlertSummary;
+use crate::locale::csv_field;
+use crate::rules::{RuleCategory, RuleSet};
+
+/// Fields available to a WFM export besides the per-category hit counts,
+/// which use the category name (e.g. "do_not_call")
+const CALL_FIELDS: &[&str] = &["call_id", "agent_id", "agent_name", "call_start_time", "alert_count", "composite_score"];
+
+/// Alerts from rules no longer in the ruleset are counted under this field
+const OTHER_CATEGORY: &str = "other";
+
+/// Output layout for workforce-management QA imports
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WfmFormat {
+    Csv,
+    Xml,
+}
+
+/// One output column: an export field and the header (or XML element) the
+/// WFM vendor expects for it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WfmColumn {
+    pub field: String,
+    pub header: String,
+}
+
+/// NICE/Verint-style default layout: agent and interaction IDs, start time,
+/// one hit count per rule category in the ruleset, total alerts and QA score
+pub fn default_columns(rules: &RuleSet) -> Vec<WfmColumn> {
+    let column = |field: &str, header: &str| WfmColumn { field: field.to_string(), header: header.to_string() };
+    let mut columns = vec![
+        column("agent_id", "AgentID"),
+        column("agent_name", "AgentName"),
+        column("call_id", "InteractionID"),
+        column("call_start_time", "StartTime"),
+    ];
+    for rule in &rules.rules {
+        let field = category_field(&rule.category);
+        if !columns.iter().any(|c| c.field == field) {
+            let header: String = field.split('_')
+                .map(|word| word[..1].to_uppercase() + &word[1..])
+                .collect();
+            columns.push(WfmColumn { header: format!("{}Hits", header), field });
+        }
+    }
+    columns.push(column("alert_count", "TotalAlerts"));
+    columns.push(column("composite_score", "QAScore"));
+    columns
+}
+
+/// Reject unknown fields and empty or duplicate headers
+pub fn validate_columns(columns: &[WfmColumn]) -> Result<(), String> {
+    if columns.is_empty() {
+        return Err("WFM column mapping has no columns".to_string());
+    }
+    for (i, column) in columns.iter().enumerate() {
+        let known = CALL_FIELDS.contains(&column.field.as_str())
+            || column.field == OTHER_CATEGORY
+            || serde_json::from_value::<RuleCategory>(serde_json::Value::String(column.field.clone())).is_ok();
+        if !known {
+            return Err(format!("Unknown WFM export field: {}", column.field));
+        }
+        if column.header.trim().is_empty() {
+            return Err(format!("WFM column for {} has no header", column.field));
+        }
+        if columns[..i].iter().any(|c| c.header == column.header) {
+            return Err(format!("Duplicate WFM column header: {}", column.header));
+        }
+    }
+    Ok(())
+}
+
+/// QA score out of 100: each alert deducts 10x its live-risk severity weight
+/// (high 30, medium 15, low 7.5), floored at zero
+pub fn composite_score(alerts: &[(String, String)]) -> f64 {
+    let deductions: f64 = alerts.iter()
+        .map(|(_, severity)| match severity.as_str() {
+            "high" => 30.0,
+            "medium" => 15.0,
+            _ => 7.5,
+        })
+        .sum();
+    (100.0 - deductions).max(0.0)
+}
+
+/// Render one row per call in the requested format
+pub fn render(calls: &[CallAlertSummary], rules: &RuleSet, columns: &[WfmColumn], format: WfmFormat) -> Result<String, String> {
+    validate_columns(columns)?;
+    let category_by_rule: HashMap<&str, String> = rules.rules.iter()
+        .map(|r| (r.id.as_str(), category_field(&r.category)))
+        .collect();
+
+    let rows: Vec<Vec<String>> = calls.iter()
+        .map(|call| {
+            let mut category_counts: HashMap<&str, u32> = HashMap::new();
+            for (rule_id, _) in &call.alerts {
+                let category = category_by_rule.get(rule_id.as_str()).map_or(OTHER_CATEGORY, |c| c.as_str());
+                *category_counts.entry(category).or_default() += 1;
+            }
+            columns.iter()
+                .map(|column| match column.field.as_str() {
+                    "call_id" => call.call_id.clone(),
+                    "agent_id" => call.agent_id.clone(),
+                    "agent_name" => call.agent_name.clone(),
+                    "call_start_time" => call.call_start_time.clone(),
+                    "alert_count" => call.alerts.len().to_string(),
+                    "composite_score" => format!("{:.1}", composite_score(&call.alerts)),
+                    category => category_counts.get(category).copied().unwrap_or(0).to_string(),
+                })
+                .collect()
+        })
+        .collect();
+
+    match format {
+        WfmFormat::Csv => Ok(render_csv(columns, &rows)),
+        WfmFormat::Xml => render_xml(columns, &rows),
+    }
+}
+
+fn render_csv(columns: &[WfmColumn], rows: &[Vec<String>]) -> String {
+    let line = |fields: Vec<String>| fields.join(",") + "\n";
+    let mut csv = line(columns.iter().map(|c| csv_field(&c.header, ",")).collect());
+    for row in rows {
+        csv.push_str(&line(row.iter().map(|f| csv_field(f, ",")).collect()));
+    }
+    csv
+}
+
+fn render_xml(columns: &[WfmColumn], rows: &[Vec<String>]) -> Result<String, String> {
+    if let Some(column) = columns.iter().find(|c| !is_xml_name(&c.header)) {
+        return Err(format!("WFM column header '{}' is not a valid XML element name", column.header));
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<QAEvaluations>\n");
+    for row in rows {
+        xml.push_str("  <Evaluation>\n");
+        for (column, value) in columns.iter().zip(row) {
+            xml.push_str(&format!("    <{0}>{1}</{0}>\n", column.header, xml_escape(value)));
+        }
+        xml.push_str("  </Evaluation>\n");
+    }
+    xml.push_str("</QAEvaluations>\n");
+    Ok(xml)
+}
+
+/// Field name of a category, as used for its hit-count column
+fn category_field(category: &RuleCategory) -> String {
+    serde_json::to_value(category)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| OTHER_CATEGORY.to_string())
+}
+
+/// ASCII subset of XML element names: a letter or underscore, then letters,
+/// digits, `_`, `-` or `.`
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
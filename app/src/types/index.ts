@@ -11,6 +11,18 @@ export interface CallMetadata {
     has_prior_consent: boolean;
     is_prerecorded: boolean;
     call_type: string;
+    number_reassigned?: boolean;
+    consumer_state?: string;
+    campaign_id?: string;
+    company_name?: string;
+    metadata_source?: 'verified_scrub' | 'agent_entered' | 'unknown';
+    tenant_id?: string;
+}
+
+export interface MetadataIssue {
+    field: string;
+    message: string;
+    severity: 'error' | 'warning';
 }
 
 export interface TranscriptSegment {
@@ -20,10 +32,14 @@ export interface TranscriptSegment {
     timestamp_ms: number;
     start_char: number;
     end_char: number;
+    // STT confidence (0-1) in the speaker label; below the threshold the
+    // segment is checked against both agent and customer rules
+    diarization_confidence?: number;
 }
 
 export interface Evidence {
     quote: string;
+    // UTF-16 code unit offsets into the transcript (JavaScript string indices)
     start_char: number;
     end_char: number;
 }
@@ -37,11 +53,24 @@ export interface Alert {
     evidence: Evidence;
     why_it_matters: string;
     agent_fix_suggestion: string;
+    segment_id?: string;
+    priority?: number;
+    display_order?: number;
+    timestamp_ms?: number;
+    sources?: string[];
+    coaching?: {
+        description: string;
+        legal_reference: string;
+    };
+    metadata_source?: 'verified_scrub' | 'agent_entered' | 'unknown';
+    // Named regex capture groups from the matching pattern, e.g. { amount: '$600' }
+    captures?: Record<string, string>;
 }
 
 export interface SuggestedLine {
     text: string;
     confidence: number;
+    rule_id?: string;
 }
 
 export interface EvaluationResult {
@@ -49,6 +78,44 @@ export interface EvaluationResult {
     suggested_next_lines: SuggestedLine[];
     evaluation_time_ms?: number;
     llm_used?: boolean;
+    llm_degraded?: boolean;
+    llm_degraded_reason?: string;
+    training_mode?: boolean;
+    // Data-quality problems such as an empty transcript; zero alerts with
+    // warnings doesn't mean a clean call
+    warnings?: string[];
+    timing?: TimingBreakdown;
+}
+
+// evaluate_transcript options; unset fields are off
+export interface EvaluateOptions {
+    training_mode?: boolean;
+    rule_ids?: string[];
+    timing?: boolean;
+}
+
+// Returned when evaluate_transcript is called with timing: true
+export interface TimingBreakdown {
+    regex_ms: number;
+    llm_request_ms: number;
+    llm_parse_ms: number;
+    offset_correction_ms: number;
+    semantic_ms: number;
+}
+
+export interface SemanticSettings {
+    enabled: boolean;
+    model: string;
+    threshold: number;
+}
+
+// Built-in scripted calls for run_scenario, which returns one EvaluationResult
+// per segment followed by the end-of-call alerts
+export interface ScenarioInfo {
+    id: string;
+    title: string;
+    description: string;
+    segments: number;
 }
 
 export interface StoredAlert extends Alert {
@@ -56,6 +123,93 @@ export interface StoredAlert extends Alert {
     agent_id: string;
     agent_name: string;
     created_at: string;
+    tags?: string[];
+    escalated_from?: 'high' | 'medium' | 'low';
+    acknowledged_at?: string;
+}
+
+// get_alerts filters; set fields are combined
+export interface AlertQuery {
+    start_date?: string;
+    end_date?: string;
+    agent_id?: string;
+    severity?: 'high' | 'medium' | 'low';
+    rule_id?: string;
+    tag?: string;
+}
+
+// get_alerts page; pass next_cursor back as after_cursor for the next page
+export interface AlertPage {
+    alerts: StoredAlert[];
+    next_cursor?: string;
+}
+
+// Payload of the alert-stored event, emitted as each alert is written
+export interface AlertStoredEvent {
+    tenant_id?: string;
+    alert: StoredAlert;
+}
+
+export interface EscalationPolicy {
+    enabled: boolean;
+    threshold: number;
+    window_days: number;
+}
+
+export interface PriorityPolicy {
+    enabled: boolean;
+    escalate_at: number;
+    demote_below: number;
+    levels: 1 | 2;
+}
+
+export interface AckSla {
+    high_secs: number;
+    medium_secs: number;
+    low_secs: number;
+}
+
+export interface AckSlaRow {
+    agent_id: string;
+    agent_name: string;
+    severity: 'high' | 'medium' | 'low';
+    sla_secs: number;
+    alerts: number;
+    acknowledged: number;
+    within_sla: number;
+    within_sla_pct: number;
+    avg_ack_secs?: number;
+}
+
+// Returned by export_alerts_json when a locale or tz_offset is given
+export interface LocalizedAlert extends StoredAlert {
+    created_at_local: string;
+    confidence_local: string;
+}
+
+export interface WfmColumn {
+    // 'call_id' | 'agent_id' | 'agent_name' | 'call_start_time' | 'alert_count' |
+    // 'composite_score' | a rule category such as 'do_not_call' | 'other'
+    field: string;
+    header: string;
+}
+
+// TIME-001 hours for one call_type; get_calling_windows maps exempt types to null
+export interface CallingWindow {
+    start_hour: number;
+    end_hour: number;
+    consent_downgrades: boolean;
+}
+
+// Approved alert wording for a rule; unset fields fall back to the rule's text
+export interface AlertTextOverride {
+    why_it_matters?: string;
+    agent_fix_suggestion?: string;
+}
+
+export interface TagCount {
+    tag: string;
+    count: number;
 }
 
 export interface AnalyticsData {
@@ -69,12 +223,218 @@ export interface AnalyticsData {
     alerts_by_rule: Array<{ rule_id: string; count: number }>;
     alerts_by_agent: Array<{ agent_id: string; agent_name: string; count: number }>;
     daily_trend?: Array<{ date: string; count: number }>;
+    agent_baselines?: Array<{
+        agent_id: string;
+        agent_name: string;
+        window_alert_rate: number;
+        baseline_alert_rate?: number;
+        delta?: number;
+        direction: 'improving' | 'regressing' | 'steady' | 'no_baseline';
+    }>;
+    suggestion_usage?: Array<{
+        suggestion_text: string;
+        uses: number;
+        calls: number;
+        alerts_after_per_use: number;
+    }>;
+    call_durations?: {
+        completed_calls: number;
+        open_calls: number;
+        avg_seconds?: number;
+        p50_seconds?: number;
+        p90_seconds?: number;
+        by_length: Array<{ label: string; calls: number; alerts_per_call: number }>;
+    };
+    escalations?: Array<{ agent_id: string; agent_name: string; rule_id: string; count: number }>;
+    talk_time?: {
+        calls: number;
+        avg_agent_ratio?: number;
+        agent_dominant_calls: number;
+        by_agent: Array<{
+            agent_id: string;
+            agent_name: string;
+            calls: number;
+            avg_agent_ratio: number;
+            agent_dominant_calls: number;
+        }>;
+    };
+}
+
+// Sent on get_analytics_streamed's on_progress channel; sections not yet
+// computed are empty in `partial`
+export interface AnalyticsProgress {
+    section: string;
+    completed: number;
+    total: number;
+    partial: AnalyticsData;
+}
+
+export interface AnomalyDay {
+    date: string;
+    count: number;
+    trailing_mean: number;
+    trailing_std_dev: number;
+    z_score: number;
+}
+
+export interface RulesetVersion {
+    version: string;
+    last_updated: string;
+    rule_count: number;
+    retained_at: string;
+}
+
+export interface AlertChange {
+    before: Alert;
+    after: Alert;
+}
+
+export interface CallAlertDiff {
+    call_id: string;
+    added: Alert[];
+    removed: Alert[];
+    changed: AlertChange[];
+    unchanged: number;
+}
+
+export interface PenaltyTableEntry {
+    rule_id: string;
+    min: number;
+    max: number;
+    custom: boolean;
+}
+
+export interface ExposureEstimate {
+    min_total: number;
+    max_total: number;
+    by_rule: Array<{ rule_id: string; violations: number; min: number; max: number; custom: boolean }>;
+}
+
+export interface QuoteCount {
+    quote: string;
+    count: number;
+}
+
+export interface RuleCoverage {
+    rule_id: string;
+    count: number;
+    last_fired?: string;
+}
+
+export interface SuggestionStats {
+    rule_id: string;
+    calls_shown: number;
+    calls_avoided: number;
+    calls_repeated: number;
+    avoidance_rate: number;
+    calls_used: number;
+    calls_used_avoided: number;
+}
+
+export interface LegalHold {
+    call_id: string;
+    agent_id: string;
+    agent_name: string;
+    call_start_time: string;
+    call_end_time?: string;
+    alert_count: number;
+}
+
+export interface PurgeSummary {
+    alerts_deleted: number;
+    calls_deleted: number;
+    held_calls_skipped: number;
+}
+
+export interface DeletionReport {
+    calls_deleted: number;
+    alerts_deleted: number;
+    milestones_deleted: number;
+    held_call_ids: string[];
+}
+
+export interface TimelineEvent {
+    kind: 'call_start' | 'alert' | 'disclosure' | 'call_end';
+    title: string;
+    rule_id?: string;
+    alert_id?: string;
+    severity?: 'high' | 'medium' | 'low';
+    quote?: string;
+    start_char?: number;
+    timestamp_ms?: number;
+    at?: string;
+}
+
+export interface LiveRisk {
+    call_id: string;
+    level: 'green' | 'amber' | 'red';
+    score: number;
+    contributing_rules: string[];
+}
+
+// Set fields are combined; at least one is required
+export interface AlertFilter {
+    call_id?: string;
+    agent_id?: string;
+    alert_ids?: string[];
+}
+
+export interface ChecklistItem {
+    rule_id: string;
+    label: string;
+    status: 'done' | 'pending' | 'violated';
+}
+
+export interface BenchmarkResult {
+    iterations: number;
+    use_llm: boolean;
+    calls_per_sec: number;
+    mean_latency_ms: number;
+    p95_latency_ms: number;
+    rules_mean_ms: number;
+    llm_mean_ms?: number;
+    llm_failures: number;
+    bound: 'cpu' | 'llm';
+}
+
+export type DncListFormat = 'internal' | 'national';
+
+export interface DncLoadResult {
+    format: DncListFormat;
+    loaded: number;
+    skipped: number;
+}
+
+export interface RuleScore {
+    rule_id: string;
+    true_positives: number;
+    false_positives: number;
+    false_negatives: number;
+    precision: number;
+    recall: number;
+    f1: number;
+}
+
+export interface ModelCorpusReport {
+    model: string;
+    cases: number;
+    llm_failures: number;
+    rules: RuleScore[];
+    precision: number;
+    recall: number;
+    f1: number;
+    total_latency_ms: number;
+    mean_latency_ms: number;
 }
 
 export interface LlmStatus {
     available: boolean;
     model: string;
     endpoint?: string;
+    connection?:
+        | { status: 'connected' }
+        | { status: 'model_not_installed'; available_models: string[] }
+        | { status: 'unavailable' };
 }
 
 export interface Rule {
@@ -92,4 +452,9 @@ export interface Rule {
     legal_reference: string;
     enabled: boolean;
     optional: boolean;
+    depends_on?: string;
+    suppresses?: string[];
+    semantic?: boolean;
+    jurisdictions?: string[];
+    satisfying_phrases?: string[];
 }